@@ -0,0 +1,137 @@
+// Plays a game through the JSON API of `serve --http`: each command is
+// posted to the game, which answers with a snapshot of its state. Changes
+// the game makes by itself, such as a time limit running out, come in as
+// server-sent events.
+
+"use strict";
+
+let gameId = null;
+let events = null;
+
+const $ = (id) => document.getElementById(id);
+
+function element(tag, props, ...children) {
+  const el = Object.assign(document.createElement(tag), props);
+  el.append(...children);
+  return el;
+}
+
+async function post(path, body) {
+  const response = await fetch(path, {
+    method: "POST",
+    headers: { "Content-Type": "application/json" },
+    body: body === undefined ? "" : JSON.stringify(body),
+  });
+  if (!response.ok) {
+    throw new Error(await response.text());
+  }
+  return response.json();
+}
+
+async function newGame() {
+  const { id, snapshot } = await post("/api/games");
+  gameId = id;
+  render(snapshot);
+  events?.close();
+  events = new EventSource(`/api/games/${id}/events`);
+  events.onmessage = (event) => render(JSON.parse(event.data));
+}
+
+async function send(command) {
+  try {
+    render(await post(`/api/games/${gameId}`, command));
+  } catch (e) {
+    // The game was dropped by the server, e.g. after it restarted
+    $("error").textContent = e.message;
+  }
+}
+
+function render(snapshot) {
+  const { state, board, holes, layout, revealed } = snapshot;
+  $("status").textContent =
+    `${state.replace(/_/g, " ")} · guesses ${snapshot.guesses}` +
+    ` · pairs ${snapshot.pairs_found}` +
+    (snapshot.lives === null ? "" : ` · lives ${snapshot.lives}`);
+  $("error").textContent = snapshot.error ?? "";
+
+  const isHole = (x, y) => holes.some(([hx, hy]) => hx === x && hy === y);
+  const isRevealed = (x, y) =>
+    revealed.some(([rx, ry]) => rx === x && ry === y);
+  const guessing = state === "guess";
+  $("board").replaceChildren(
+    ...board.map((row, i) => {
+      const y = i + 1;
+      const shifted = layout === "hex" && y % 2 === 0;
+      return element(
+        "div",
+        { className: shifted ? "row shifted" : "row", role: "row" },
+        ...row.map((card, j) => {
+          const x = j + 1;
+          if (isHole(x, y)) {
+            return element("span", { className: "hole" });
+          }
+          const classes = ["card"];
+          if (card !== null) classes.push("face-up");
+          if (isRevealed(x, y)) classes.push("revealed");
+          return element("button", {
+            className: classes.join(" "),
+            role: "gridcell",
+            textContent: card ?? "",
+            ariaLabel: `${x},${y} ${card ?? "face down"}`,
+            disabled: !guessing || card !== null,
+            onclick: () => send({ cmd: "reveal", x, y }),
+          });
+        }),
+      );
+    }),
+  );
+
+  $("actions").replaceChildren(...actions(state));
+}
+
+function button(label, command) {
+  return element("button", {
+    textContent: label,
+    onclick: () => send(command),
+  });
+}
+
+function actions(state) {
+  switch (state) {
+    case "welcome":
+    case "preview":
+    case "correct_guess":
+    case "incorrect_guess":
+    case "bomb_exploded":
+      return [button("Continue", { cmd: "continue" })];
+    case "size_menu":
+    case "set_dimensions": {
+      const columns = element("input", { type: "number", min: 1, value: 4 });
+      const rows = element("input", { type: "number", min: 1, value: 4 });
+      const start = element("button", {
+        textContent: "Start",
+        onclick: () =>
+          send({ cmd: "start", x: +columns.value, y: +rows.value }),
+      });
+      return [columns, " × ", rows, start];
+    }
+    case "guess":
+      return [button("Hint", { cmd: "hint" })];
+    case "victory":
+    case "defeat":
+      return [
+        button("Play again", { cmd: "play_again", value: true }),
+        button("Quit", { cmd: "play_again", value: false }),
+      ];
+    case "exit":
+      return [
+        element("button", { textContent: "New game", onclick: newGame }),
+      ];
+    default:
+      return [];
+  }
+}
+
+newGame().catch((e) => {
+  $("error").textContent = e.message;
+});
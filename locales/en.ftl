@@ -0,0 +1,320 @@
+# Prompts
+welcome = Welcome! Press <Enter> to begin.
+welcome-settings = Type :settings to change the settings.
+set-dimensions = Set board dimensions (x, y)
+size-menu = Pick a board size:
+size-menu-prompt = Type a number (1-{ $max }), or press <Enter> for { $choice }. { $name }
+size-easy = Easy
+size-medium = Medium
+size-hard = Hard
+size-expert = Expert
+size-custom = Custom
+memorize = Memorize the board, then press <Enter> to begin.
+preview = Memorize the board! Play starts in { $seconds } seconds, or press <Enter> to begin now.
+pick-card = Pick a card (x, y or e.g. c4), or type `hint`
+board-code = Board code: { $code }
+result-code = Result code for your friends: { $code }
+compare-you = You
+compare-friend = Friend
+compare-guesses = Guesses
+compare-time = Time
+compare-hints = Hints
+compare-score = Score
+compare-won = You beat your friend!
+compare-lost = Your friend did better this time.
+compare-tied = It's a tie!
+waiting-for-opponent = Waiting for the opponent...
+waiting-chat = Type :say and a message to chat in the meantime.
+cards-shuffled = The hidden cards have been shuffled!
+pairs-faded = Matched pairs faded and were turned face down again: { $count }
+correct-guess = A match!
+incorrect-guess = Try again
+bomb-penalty = Boom! That was a bomb, it cost you { $points } points.
+bomb-shuffle = Boom! That was a bomb, and the blast shuffled some of the hidden cards.
+time-up = Time's up! That counts as a miss.
+turn-expired-yours = Time's up! Your turn passes to the opponent.
+turn-expired-theirs = The opponent ran out of time. It's your turn.
+time-left = { $seconds }s left
+play-again = Congratulations! Play again? (y / N)
+out-of-lives = Out of lives! This is where the cards were.
+play-again-after-defeat = Try again? (y / N)
+play-again-commands = Or type :restart for a new shuffle, :rematch for the same board again or :resize for another size.
+press-enter-to-exit = Press <Enter> to exit.
+press-enter-to-continue = Press <Enter> to continue.
+confirm-quit = Quit the game in progress? (y / N)
+confirm-quit-save = Quit the game in progress? Type s to save it for later, y to abandon it, or press <Enter> to keep playing.
+confirm-restart = Abandon this board and deal a new one? (y / N)
+confirm-rematch = Abandon this board and start it over from the beginning? (y / N)
+confirm-resize = Abandon this board and pick another size? (y / N)
+confirm-recover = The game in progress when the last session ended unexpectedly was saved. Continue it? (y / N)
+
+# Commands
+help-input = Picking cards:
+help-input-coords = c4 or 3,4   turn over the card in column c (3) of row 4
+help-input-formats = 3 4, (3,4) or x=3 y=4   the same as 3,4
+help-input-number = 7           turn over the 7th card, counting row by row
+help-input-batch = a1 b2 c3    turn over several cards one after the other
+help-input-scroll = up, down, left, right   scroll a board that doesn't fit on the screen
+help-rules = Rules of this game:
+rule-standard = Standard rules
+rule-time-limit = Every guess has to be made in { $seconds } seconds
+rule-lives = The game is lost after { $lives } incorrect guesses
+rule-jokers = { $count } jokers match any other card
+rule-bombs-penalty = { $count } bombs cost points and a life when revealed
+rule-bombs-shuffle = { $count } bombs shuffle the hidden cards around them when revealed
+rule-decay = Found pairs are turned face down again after { $turns } more guesses
+rule-shuffle-every = The hidden cards are shuffled after every { $turns } guesses
+rule-hint-penalty = Every hint adds { $guesses } to the guesses
+overlay-close = Press <Enter> to go back to the game.
+help-commands = Commands:
+help-quit = :quit     leave the game
+help-restart = :restart  deal a new board of the same size
+help-rematch = :rematch  deal the same board again, with every card in the same place
+help-resize = :resize   abandon the board and pick another size
+help-hint = :hint     show a matching card, for a penalty
+help-odds = :odds     show the chance in percent of each face-down card matching the one picked, for a penalty
+help-save = :save     save the game and quit, to continue it later with the resume command
+help-help = :help     show this list
+help-theme = :theme    switch to the next color theme, or pick one, e.g. :theme solarized
+help-heatmap = :heatmap  after a game, show which positions you turn over most on boards of its size
+help-settings = :settings change the colors, the cards, the difficulty and the sound
+help-keys = :keys     show the keys that stand in for commands
+help-numbers = :numbers  show or hide the number of each face-down card
+help-luck = :luck     show or hide how your guesses compare to the guesses expected
+help-say = :say hi   send a message to your opponent in a networked game
+keys-title = Keys, typed on a line of their own (change them in the [key_bindings] table of the configuration file):
+help-peek = :peek c4  show the cards around c4 for 2 seconds (power-up)
+help-freeze = :freeze   stop the clock for 10 seconds (power-up)
+help-match = :match    match a pair for you (power-up)
+
+# Scores and hints
+score = Guesses: { $guesses } | Correct guesses: { $correct }
+score-with-hints = Guesses: { $guesses } | Correct guesses: { $correct } | Hints: { $hints }
+luck = Luck: { $luck } | On course for { $projected } guesses, { $expected } expected
+lives = Lives: { $hearts }
+joker-legend = { $joker } is a joker, it matches any card
+bombs-hidden = Bombs hidden among the cards: { $count }
+score-your-turn = Your turn | You: { $mine } | Opponent: { $theirs }
+score-their-turn = Opponent's turn | You: { $mine } | Opponent: { $theirs }
+score-players = Player { $player }'s turn | Player 1: { $first } | Player 2: { $second }
+hint-one = Hint: look at { $first }
+hint-two = Hint: look at { $first } and { $second }
+odds = Chance in percent of each face-down card matching { $first }, from the cards seen so far
+viewport = Showing columns { $columns }, rows { $rows }. Type up, down, left or right to scroll.
+mode-solo = Solo
+mode-versus = Versus
+mode-daily = Daily challenge
+mode-tournament = Tournament
+mode-campaign = Level { $level }
+difficulty-easy = Easy
+difficulty-normal = Normal
+difficulty-hard = Hard
+settings = Settings:
+settings-prompt = Type a number (1-{ $max }) to change a setting, or press <Enter> to go back.
+settings-next-board = Changes to the cards and the difficulty apply from the next board.
+setting-colors = Colors
+setting-card-set = Cards
+setting-difficulty = Difficulty
+setting-sound = Sound
+setting-on = on
+setting-off = off
+card-set-symbols = Symbols
+card-set-playing = Playing cards
+card-set-ascii = Letters and digits
+card-set-emoji = Emoji
+pairs-left = Pairs left: { $left }/{ $total }
+turn-yours = Your turn
+turn-theirs = Opponent's turn
+turn-player = Player { $player }'s turn
+history = Last guesses
+history-guess = { $number }. { $cards }: { $outcome }
+history-match = pair
+history-mismatch = miss
+history-bomb = bomb
+history-time-up = time up
+chat = Chat
+chat-line = { $name }: { $text }
+chat-you = You
+chat-opponent = Opponent
+chat-player = Player { $player }
+outcome-win = You win!
+outcome-lose = You lose.
+outcome-draw = It's a draw.
+outcome-player-wins = Player { $player } wins!
+elo-change = Rating: { $rating } ({ $change })
+board-verified = The host's board checked out: no cards were moved.
+board-tampered = The host's board did not match what it committed to before the game!
+board-unverified = The host's board could not be checked.
+points-earned = +{ $points } points (streak: { $streak })
+final-score = Final score: { $points } (longest streak: { $streak })
+power-up-earned = Streak bonus: you earned a :{ $name } power-up!
+power-ups = Power-ups: { $list }
+clock-frozen = The clock is frozen for { $seconds } more seconds.
+
+# Statistics
+cleared-in = Cleared the board in { $time }.
+summary-title = Results
+summary-time = Time
+summary-guesses = Guesses
+summary-accuracy = Accuracy
+summary-streak = Longest streak
+summary-expected = Expected with perfect memory
+summary-par = Par
+summary-beat-expected = You did as well as a player with perfect memory would on average!
+summary-above-expected = That is { $extra } guesses more than a player with perfect memory would take on average.
+rating = Rating: { $stars }
+rating-best = Best rating on this board size: { $stars }
+ratings-title = Best ratings:
+analysis-clean = Every guess made use of the cards seen before it.
+analysis-flagged = { $count } of { $total } guesses didn't make use of the cards seen before them:
+analysis-wasted = Guess { $number }: the pair at { $first } and { $second } had been seen
+analysis-missed-partner = Guess { $number }: the match for { $first } had been seen at { $partner }
+analysis-repeated = Guess { $number }: { $at } had been turned over before
+analysis-more = ...and { $count } more
+best-time = Best time on { $size }: { $time }
+best-time-new-record = Best time on { $size }: { $time } (new record!)
+games-played = Games played: { $count }
+guesses-per-pair = Guesses per pair: { $average }
+guesses-per-pair-none = Guesses per pair: -
+win-rate = Win rate: { $percent }%
+win-rate-none = Win rate: - (no games with limits)
+best-times = Best times (longer bars are slower):
+elo-rating = Rating in networked games: { $rating }
+elo-opponent = Against { $name }: { $wins } won, { $draws } drawn, { $losses } lost (rated { $rating })
+opponent-unnamed = unnamed opponents
+stats-unavailable = No statistics available.
+history-modes = Games by mode:
+history-streaks = Games won in a row: { $current } now, { $longest } at most
+history-trend = Guesses per pair in the last { $count } games won, oldest first:
+history-mode = { $mode }: { $played } played, { $won } won, { $average } guesses on average, best time { $best }
+history-recent = Last { $count } games:
+history-entry = { $date }  { $mode } { $size } (seed { $seed }): { $outcome } after { $guesses } guesses in { $time }
+history-won = won
+history-lost = lost
+history-unavailable = The history of games is only kept when the game is built with the `history` feature.
+export-done = Exported { $count } games to { $path }.
+heatmap-title = Cards turned over on { $size } boards, from once to most often:
+daily-complete = Daily challenge { $date } complete!
+daily-first-attempt-only = Only the first attempt counts: { $guesses } guesses in { $time }.
+daily-no-log = Nowhere to record the daily results.
+no-saved-game = There is no saved game to continue.
+profile-menu = Profiles:
+profile-prompt = Pick a profile from 1 to { $max }, type a name for a new one, or press <Enter> to play without one:
+tutorial-first = Welcome! Cards are hidden face down. Turn one over by typing its position, e.g. a1 for the highlighted card.
+tutorial-second = Now turn over a second card. If both cards match, they are a pair.
+tutorial-mismatch = These two don't match, so they are turned back over. Remember where they were!
+tutorial-explore = Turn over a card you haven't seen yet.
+tutorial-partner = You have seen the card that matches this one before. Pick it!
+tutorial-known-pair = You know where both cards of a pair are. Turn one of them over.
+tutorial-match = A pair! Found pairs stay face up. Clear the board to win.
+tutorial-done = That's all there is to it. Have fun playing!
+tournament-round-complete = Round { $round } of { $rounds } complete!
+tournament-round = { $guesses } guesses in { $time }: { $points } points
+tournament-total = Total: { $guesses } guesses in { $time }: { $points } points
+tournament-next-round = Press <Enter> to start round { $round } on a { $size } board...
+tournament-over = Tournament over! Final score: { $points } points
+campaign-levels = Campaign levels:
+campaign-no-progress = Nowhere to record the campaign progress.
+campaign-complete = You cleared the whole campaign!
+level-locked = (locked)
+level-select = Pick a level (1-{ $max }), or press <Enter> for level { $level }
+level-intro = Level { $level }: { $size } board
+level-start = Press <Enter> to begin.
+level-rule-easy = The board is shown face up before the first guess
+level-rule-hard = The hidden cards are shuffled after every { $interval } mismatches
+level-rule-hex = The cards are laid out on hexagons
+level-rule-layers = The cards are stacked { $layers } deep
+level-rule-spread = The cards of a pair are at least { $steps } steps apart
+level-complete = Level { $level } complete!
+level-unlocked = Level { $level } unlocked!
+level-next = Continue to level { $level }? (y / N)
+solved = Solved a { $size } board in { $guesses } guesses.
+
+# Command line
+join-waiting = Waiting for the host to choose a board...
+join-failed = Couldn't join { $addr }: { $reason }
+host-waiting = Waiting for an opponent on port { $port }...
+host-failed = Couldn't host on port { $port }: { $reason }
+serve-listening = Serving the game at http://127.0.0.1:{ $port }/ (Ctrl+C to stop)
+serve-failed = Couldn't serve on port { $port }: { $reason }
+lobby-listening = Lobby open on port { $port } (Ctrl+C to stop)
+lobby-no-rooms = The lobby has no rooms open.
+lobby-room = Room { $room }: { $size }
+lobby-room-playing = Room { $room }: { $size }, playing (can be watched)
+lobby-seated = Seated in room { $room }, waiting for the other player...
+lobby-watching = Watching room { $room }, waiting for the players...
+lobby-no-such-room = There is no such room
+lobby-room-full = Both seats of the room are taken
+lobby-invalid-size = A board of that size can't be dealt
+size-required = No board size given, pass one with --size or --shape.
+input-failed = Couldn't get input: { $reason }
+script-failed = Couldn't read the script { $path }: { $reason }
+invalid-command = Invalid command: { $reason }
+cast-done = Wrote the cast to { $path }.
+cast-failed = Couldn't write the cast to { $path }: { $reason }
+
+# Errors
+error-already-revealed = Card at position ({ $x },{ $y }) is already revealed.
+error-empty-input = User input is required
+error-coordinate-overflow = { $axis } coordinate too large. Maximum possible value is { $max }.
+error-coordinate-underflow = { $axis } coordinate too small. Minimum possible value is 0.
+error-odd-board-cells = Number of board cells (horizontal size * vertical size) must be even, not counting jokers and bombs
+error-too-many-extra-cards = There can be at most { $max } jokers and bombs on this board
+error-empty-shape = The board shape has no cells
+error-invalid-shape-char = Board shapes can only contain #, . and spaces, not { $char }
+error-shape-io = Couldn't read the board shape { $path }: { $reason }
+error-no-card = There is no card at position ({ $x },{ $y })
+error-partner-covered = The other card of this pair is still covered
+error-invalid-board-code = The board code is invalid or from another version of the game
+error-invalid-result-code = The result code is invalid, check that it was copied whole
+error-not-enough-card-types = Cannot create board with more than { $max } cells
+error-board-too-large = A board of { $x } by { $y } cells is too large
+error-unparsable-input = User input could not be parsed
+error-single-number = `{ $input }` is only one number. Type two, like 3,4.
+error-unreadable-coords = `{ $input }` is not a position. Type two numbers like 3,4, or a column and a row like c4.
+error-unreadable-dimensions = `{ $input }` is not a board size. Type the number of columns and rows like 6,4.
+error-unreadable-answer = `{ $input }` is not an answer. Type y or n.
+error-no-such-cell = There is no cell { $input }. The cells are numbered from 1 to { $max }, row by row.
+error-off-board-x = `{ $input }` is off the board. The board is { $size } wide, try an x between 1 and { $size }.
+error-off-board-y = `{ $input }` is off the board. The board is { $size } tall, try a y between 1 and { $size }.
+error-invalid-column-letter = `{ $letter }` is not a column letter.
+error-column-out-of-range = There is no column { $column }. The last column is { $last }.
+error-config-io = Couldn't read configuration file { $path }: { $reason }
+error-invalid-config = Invalid configuration: { $reason }
+error-config-save = Couldn't save the settings to { $path }: { $reason }
+error-connection-lost = Lost connection to the opponent: { $reason }
+error-hints-disabled = Hints are not available in this game
+error-stats-io = Couldn't access statistics file { $path }: { $reason }
+error-save-io = Couldn't access saved game { $path }: { $reason }
+error-history-io = Couldn't access the history of games { $path }: { $reason }
+error-export-io = Couldn't write the export { $path }: { $reason }
+error-replay-io = Couldn't access the recording { $path }: { $reason }
+error-unknown-command = Unknown command `:{ $command }`. Type :help for the list of commands.
+error-command-unavailable = The `:{ $command }` command can't be used right now.
+error-unknown-theme = There is no theme called `{ $name }`. Pick one of: { $themes }
+error-invalid-profile-name = `{ $name }` can't be the name of a profile. Use up to 32 letters, digits, - or _.
+error-not-your-turn = It's your opponent's turn. Type :say and a message to chat in the meantime.
+error-chat-too-fast = You're sending messages too quickly. Wait a few seconds.
+error-invalid-state = That can't be done from the { $state } state.
+error-no-power-up = You don't have a :{ $name } power-up. Find pairs in a row to earn one.
+error-invalid-level = There is no such level. Pick one from 1 to { $max }.
+error-invalid-menu-choice = There is no such choice. Pick one from 1 to { $max }.
+error-level-locked = Level { $level } is locked. Clear the levels before it first.
+
+# Accessible output
+board-row = row { $row }: { $cards }
+card-hidden = hidden
+card-hidden-layer = hidden, layer { $layer }
+card-hole = empty
+card-face-up = { $card }
+card-matched = matched { $card }
+card-revealed = revealed { $card }
+card-hinted = hinted { $card }
+card-odds = face down, { $percent }% chance of a match
+card-peeked = peeked { $card }
+card-joker = joker
+card-bomb = bomb
+card-at = { $card } at { $position }
+action-revealed = Revealed { $card }.
+action-match = Matched { $first } with { $second }.
+action-mismatch = No match: { $first } and { $second }.
@@ -0,0 +1,320 @@
+# Prompts
+welcome = Üdvözlünk! A kezdéshez nyomd meg az <Enter>-t.
+welcome-settings = A beállítások módosításához írd be: :settings
+set-dimensions = Add meg a tábla méretét (x, y)
+size-menu = Válassz táblaméretet:
+size-menu-prompt = Írj be egy számot (1-{ $max }), vagy nyomj <Enter>-t ehhez: { $choice }. { $name }
+size-easy = Könnyű
+size-medium = Közepes
+size-hard = Nehéz
+size-expert = Profi
+size-custom = Egyéni
+memorize = Jegyezd meg a táblát, majd a kezdéshez nyomd meg az <Enter>-t.
+preview = Jegyezd meg a táblát! A játék { $seconds } másodperc múlva indul, vagy nyomj <Enter>-t az azonnali kezdéshez.
+pick-card = Válassz egy kártyát (x, y vagy pl. c4), vagy írd be: `hint`
+board-code = Táblakód: { $code }
+result-code = Eredménykód a barátaidnak: { $code }
+compare-you = Te
+compare-friend = Barát
+compare-guesses = Tippek
+compare-time = Idő
+compare-hints = Segítségek
+compare-score = Pontszám
+compare-won = Legyőzted a barátodat!
+compare-lost = A barátod most jobb volt.
+compare-tied = Döntetlen!
+waiting-for-opponent = Várakozás az ellenfélre...
+waiting-chat = Addig is csevegni a :say és az üzenet beírásával tudsz.
+cards-shuffled = A lefordított kártyák össze lettek keverve!
+pairs-faded = Elhalványult és újra lefordított párok: { $count }
+correct-guess = Talált!
+incorrect-guess = Próbáld újra
+bomb-penalty = Bumm! Ez egy bomba volt, { $points } pontodba került.
+bomb-shuffle = Bumm! Ez egy bomba volt, a robbanás összekeverte a rejtett kártyák egy részét.
+time-up = Lejárt az idő! Ez tévedésnek számít.
+turn-expired-yours = Lejárt az idő! Az ellenfeled következik.
+turn-expired-theirs = Az ellenfeled kifutott az időből. Te jössz.
+time-left = még { $seconds } mp
+play-again = Gratulálunk! Új játék? (y / N)
+out-of-lives = Elfogytak az életeid! Itt voltak a kártyák.
+play-again-after-defeat = Újra próbálod? (y / N)
+play-again-commands = Vagy írd be: :restart egy új keveréshez, :rematch ugyanehhez a táblához vagy :resize egy másik mérethez.
+press-enter-to-exit = A kilépéshez nyomd meg az <Enter>-t.
+press-enter-to-continue = A folytatáshoz nyomd meg az <Enter>-t.
+confirm-quit = Kilépsz a folyamatban lévő játékból? (y / N)
+confirm-quit-save = Kilépsz a folyamatban lévő játékból? Írj s-t a mentéshez, y-t a feladáshoz, vagy nyomj <Enter>-t a folytatáshoz.
+confirm-restart = Eldobod ezt a táblát, és újat osztasz? (y / N)
+confirm-rematch = Eldobod ezt a táblát, és elölről kezded ugyanezt? (y / N)
+confirm-resize = Eldobod ezt a táblát, és másik méretet választasz? (y / N)
+confirm-recover = A legutóbbi, váratlanul véget ért munkamenet folyamatban lévő játéka el lett mentve. Folytatod? (y / N)
+
+# Parancsok
+help-input = Kártyák kiválasztása:
+help-input-coords = c4 vagy 3,4   a 4. sor c (3.) oszlopában lévő kártya felfordítása
+help-input-formats = 3 4, (3,4) vagy x=3 y=4   ugyanaz, mint a 3,4
+help-input-number = 7           a 7. kártya felfordítása, soronként számolva
+help-input-batch = a1 b2 c3      több kártya felfordítása egymás után
+help-input-scroll = up, down, left, right   a képernyőre nem férő tábla görgetése
+help-rules = A játék szabályai:
+rule-standard = Szokásos szabályok
+rule-time-limit = Minden tippre { $seconds } másodperc jut
+rule-lives = { $lives } rossz tipp után a játék elveszett
+rule-jokers = { $count } joker bármely kártyával párt alkot
+rule-bombs-penalty = { $count } bomba pontot és életet vesz el, ha felfordítod
+rule-bombs-shuffle = { $count } bomba összekeveri a körülötte lévő rejtett kártyákat, ha felfordítod
+rule-decay = A megtalált párok további { $turns } tipp után visszafordulnak
+rule-shuffle-every = A rejtett kártyák minden { $turns }. tipp után összekeverednek
+rule-hint-penalty = Minden segítség { $guesses } tippel növeli a számlálót
+overlay-close = A játékhoz való visszatéréshez nyomd meg az <Enter>-t.
+help-commands = Parancsok:
+help-quit = :quit     kilépés a játékból
+help-restart = :restart  új, azonos méretű tábla
+help-rematch = :rematch  ugyanennek a táblának az újrakezdése, minden kártya ugyanott
+help-resize = :resize   a tábla feladása és másik méret választása
+help-hint = :hint     egy pár megmutatása, büntetésért
+help-odds = :odds     megmutatja, hány százalék eséllyel párja az egyes lefordított kártyák a felfordítottnak, büntetésért
+help-save = :save     a játék mentése és kilépés, később a resume paranccsal folytatható
+help-help = :help     ez a lista
+help-theme = :theme    váltás a következő színtémára, vagy egy adott témára, pl. :theme solarized
+help-heatmap = :heatmap  játék után megmutatja, mely mezőket fordítod fel a legtöbbször ekkora táblán
+help-settings = :settings a színek, a kártyák, a nehézség és a hang beállítása
+help-keys = :keys     a parancsok helyett használható billentyűk listája
+help-numbers = :numbers  a lefordított kártyák számának megjelenítése vagy elrejtése
+help-luck = :luck     a tippjeid és a várható tippek összevetésének megjelenítése vagy elrejtése
+help-say = :say szia üzenet küldése az ellenfélnek hálózati játékban
+keys-title = Billentyűk, külön sorba írva (a beállításfájl [key_bindings] táblájában módosíthatók):
+help-peek = :peek c4  a c4 körüli kártyák megmutatása 2 másodpercre (képesség)
+help-freeze = :freeze   az óra megállítása 10 másodpercre (képesség)
+help-match = :match    egy pár megtalálása helyetted (képesség)
+
+# Scores and hints
+score = Tippek: { $guesses } | Helyes tippek: { $correct }
+score-with-hints = Tippek: { $guesses } | Helyes tippek: { $correct } | Segítségek: { $hints }
+luck = Szerencse: { $luck } | Várhatóan { $projected } tipp, átlagosan { $expected }
+lives = Életek: { $hearts }
+joker-legend = A { $joker } joker, bármelyik kártyával párt alkot
+bombs-hidden = A kártyák között rejtőző bombák: { $count }
+score-your-turn = Te jössz | Te: { $mine } | Ellenfél: { $theirs }
+score-their-turn = Az ellenfél jön | Te: { $mine } | Ellenfél: { $theirs }
+score-players = A(z) { $player }. játékos jön | 1. játékos: { $first } | 2. játékos: { $second }
+hint-one = Segítség: nézd meg ezt: { $first }
+hint-two = Segítség: nézd meg ezeket: { $first } és { $second }
+odds = Az egyes lefordított kártyák esélye százalékban, hogy a(z) { $first } párja, az eddig látott kártyák alapján
+viewport = Látható oszlopok: { $columns }, sorok: { $rows }. Görgetés: up, down, left vagy right.
+mode-solo = Egyjátékos
+mode-versus = Párbaj
+mode-daily = Napi kihívás
+mode-tournament = Verseny
+mode-campaign = { $level }. pálya
+difficulty-easy = Könnyű
+difficulty-normal = Normál
+difficulty-hard = Nehéz
+settings = Beállítások:
+settings-prompt = Írj be egy számot (1-{ $max }) egy beállítás módosításához, vagy nyomj <Enter>-t a visszalépéshez.
+settings-next-board = A kártyák és a nehézség módosítása a következő táblától érvényes.
+setting-colors = Színek
+setting-card-set = Kártyák
+setting-difficulty = Nehézség
+setting-sound = Hang
+setting-on = be
+setting-off = ki
+card-set-symbols = Szimbólumok
+card-set-playing = Francia kártya
+card-set-ascii = Betűk és számok
+card-set-emoji = Emodzsik
+pairs-left = Hátralévő párok: { $left }/{ $total }
+turn-yours = Te jössz
+turn-theirs = Az ellenfél jön
+turn-player = A(z) { $player }. játékos jön
+history = Utolsó tippek
+history-guess = { $number }. { $cards }: { $outcome }
+history-match = pár
+history-mismatch = nem pár
+history-bomb = bomba
+history-time-up = lejárt az idő
+chat = Csevegés
+chat-line = { $name }: { $text }
+chat-you = Te
+chat-opponent = Ellenfél
+chat-player = { $player }. játékos
+outcome-win = Nyertél!
+outcome-lose = Vesztettél.
+outcome-draw = Döntetlen.
+outcome-player-wins = A(z) { $player }. játékos nyert!
+elo-change = Értékszám: { $rating } ({ $change })
+board-verified = A házigazda táblája ellenőrizve: egy kártyát sem mozgatott el.
+board-tampered = A házigazda táblája nem egyezik azzal, amit a játék előtt rögzített!
+board-unverified = A házigazda tábláját nem sikerült ellenőrizni.
+points-earned = +{ $points } pont (sorozat: { $streak })
+final-score = Végső pontszám: { $points } (leghosszabb sorozat: { $streak })
+power-up-earned = Sorozat bónusz: kaptál egy :{ $name } képességet!
+power-ups = Képességek: { $list }
+clock-frozen = Az óra még { $seconds } másodpercig áll.
+
+# Statistics
+cleared-in = A táblát { $time } alatt teljesítetted.
+summary-title = Eredmények
+summary-time = Idő
+summary-guesses = Tippek
+summary-accuracy = Pontosság
+summary-streak = Leghosszabb sorozat
+summary-expected = Várható tökéletes memóriával
+summary-par = Par
+summary-beat-expected = Legalább olyan jól játszottál, mint egy tökéletes memóriájú játékos átlagosan!
+summary-above-expected = Ez { $extra } tippel több, mint amennyi egy tökéletes memóriájú játékosnak átlagosan kellene.
+rating = Értékelés: { $stars }
+rating-best = Legjobb értékelés ezen a táblaméreten: { $stars }
+ratings-title = Legjobb értékelések:
+analysis-clean = Minden tipp felhasználta a korábban látott kártyákat.
+analysis-flagged = { $total } tippből { $count } nem használta fel a korábban látott kártyákat:
+analysis-wasted = { $number }. tipp: a { $first } és { $second } mezőn lévő pár már ismert volt
+analysis-missed-partner = { $number }. tipp: a { $first } párja már látszott itt: { $partner }
+analysis-repeated = { $number }. tipp: a { $at } kártyát már korábban felfordítottad
+analysis-more = ...és még { $count }
+best-time = Legjobb idő ({ $size }): { $time }
+best-time-new-record = Legjobb idő ({ $size }): { $time } (új rekord!)
+games-played = Lejátszott játékok: { $count }
+guesses-per-pair = Tippek páronként: { $average }
+guesses-per-pair-none = Tippek páronként: -
+win-rate = Nyerési arány: { $percent }%
+win-rate-none = Nyerési arány: - (nem volt korlátozott játék)
+best-times = Legjobb idők (a hosszabb sáv lassabb):
+elo-rating = Értékszám hálózati játékokban: { $rating }
+elo-opponent = { $name } ellen: { $wins } győzelem, { $draws } döntetlen, { $losses } vereség (értékszám: { $rating })
+opponent-unnamed = névtelen ellenfelek
+stats-unavailable = Nincs elérhető statisztika.
+history-modes = Játékok módonként:
+history-streaks = Egymás utáni győzelmek: most { $current }, legfeljebb { $longest }
+history-trend = Tippek páronként az utolsó { $count } megnyert játékban, a legrégebbitől:
+history-mode = { $mode }: { $played } játék, { $won } győzelem, átlagosan { $average } tipp, legjobb idő { $best }
+history-recent = Az utolsó { $count } játék:
+history-entry = { $date }  { $mode } { $size } (mag: { $seed }): { $outcome } { $guesses } tipp után, { $time } alatt
+history-won = győzelem
+history-lost = vereség
+history-unavailable = A játékok előzményei csak a `history` funkcióval fordított játékban érhetők el.
+export-done = { $count } játék exportálva ide: { $path }.
+heatmap-title = Felfordított kártyák { $size } méretű táblákon, egyszertől a leggyakrabbig:
+daily-complete = A(z) { $date } napi kihívás teljesítve!
+daily-first-attempt-only = Csak az első próbálkozás számít: { $guesses } tipp, { $time } idő.
+daily-no-log = A napi eredményeket nincs hová menteni.
+no-saved-game = Nincs folytatható mentett játék.
+profile-menu = Profilok:
+profile-prompt = Válassz profilt 1 és { $max } között, írd be egy új nevét, vagy nyomj <Enter>-t a profil nélküli játékhoz:
+tutorial-first = Üdv! A kártyák lefordítva rejtőznek. Fordíts fel egyet a helyének beírásával, pl. a1 a kiemelt kártyához.
+tutorial-second = Most fordíts fel egy második kártyát. Ha a kettő egyezik, az egy pár.
+tutorial-mismatch = Ez a kettő nem egyezik, ezért visszafordulnak. Jegyezd meg, hol voltak!
+tutorial-explore = Fordíts fel egy kártyát, amit még nem láttál.
+tutorial-partner = Ennek a kártyának a párját már láttad. Válaszd ki!
+tutorial-known-pair = Tudod, hol van egy pár mindkét kártyája. Fordítsd fel az egyiket.
+tutorial-match = Egy pár! A megtalált párok felfordítva maradnak. Tisztítsd meg a táblát a győzelemhez.
+tutorial-done = Ennyi az egész. Jó játékot!
+tournament-round-complete = A(z) { $round }. kör kész a(z) { $rounds } közül!
+tournament-round = { $guesses } tipp { $time } alatt: { $points } pont
+tournament-total = Összesen: { $guesses } tipp { $time } alatt: { $points } pont
+tournament-next-round = Nyomj <Enter>-t a(z) { $round }. kör indításához egy { $size } méretű táblán...
+tournament-over = A verseny véget ért! Végső pontszám: { $points } pont
+campaign-levels = A kampány pályái:
+campaign-no-progress = A kampány állását nincs hová menteni.
+campaign-complete = Végigjátszottad az egész kampányt!
+level-locked = (zárolva)
+level-select = Válassz pályát (1-{ $max }), vagy nyomj <Enter>-t a(z) { $level }. pályához
+level-intro = { $level }. pálya: { $size } méretű tábla
+level-start = A kezdéshez nyomd meg az <Enter>-t.
+level-rule-easy = A tábla az első tipp előtt képpel felfelé látható
+level-rule-hard = A lefordított kártyák minden { $interval }. tévedés után összekeverednek
+level-rule-hex = A kártyák hatszögekben vannak elrendezve
+level-rule-layers = A kártyák { $layers } rétegben vannak egymásra rakva
+level-rule-spread = Egy pár kártyái legalább { $steps } lépésre vannak egymástól
+level-complete = A(z) { $level }. pálya kész!
+level-unlocked = A(z) { $level }. pálya feloldva!
+level-next = Tovább a(z) { $level }. pályára? (y / N)
+solved = Egy { $size } méretű tábla megoldva { $guesses } tippből.
+
+# Command line
+join-waiting = Várakozás, amíg a házigazda kiválasztja a táblát...
+join-failed = Nem sikerült csatlakozni ide: { $addr }: { $reason }
+host-waiting = Várakozás egy ellenfélre a(z) { $port } porton...
+host-failed = Nem sikerült játékot indítani a(z) { $port } porton: { $reason }
+serve-listening = A játék itt érhető el: http://127.0.0.1:{ $port }/ (Ctrl+C a leállításhoz)
+serve-failed = Nem sikerült kiszolgálni a(z) { $port } porton: { $reason }
+lobby-listening = A váró a(z) { $port } porton fogad játékosokat (Ctrl+C a leállításhoz)
+lobby-no-rooms = A váróban nincs nyitott szoba.
+lobby-room = { $room }. szoba: { $size }
+lobby-room-playing = { $room }. szoba: { $size }, játékban (nézhető)
+lobby-seated = Helyet foglaltál a(z) { $room }. szobában, várakozás a másik játékosra...
+lobby-watching = A(z) { $room }. szoba nézése, várakozás a játékosokra...
+lobby-no-such-room = Nincs ilyen szoba
+lobby-room-full = A szoba mindkét helye foglalt
+lobby-invalid-size = Ekkora táblát nem lehet leosztani
+size-required = Nincs megadva táblaméret, add meg a --size vagy a --shape kapcsolóval.
+input-failed = Nem sikerült beolvasni a bemenetet: { $reason }
+script-failed = Nem sikerült beolvasni a(z) { $path } szkriptet: { $reason }
+invalid-command = Érvénytelen parancs: { $reason }
+cast-done = A felvétel kiírva ide: { $path }.
+cast-failed = Nem sikerült kiírni a felvételt ide: { $path }: { $reason }
+
+# Errors
+error-already-revealed = A(z) ({ $x },{ $y }) helyen lévő kártya már fel van fordítva.
+error-empty-input = Bemenet megadása kötelező
+error-coordinate-overflow = Túl nagy { $axis } koordináta. A legnagyobb lehetséges érték { $max }.
+error-coordinate-underflow = Túl kicsi { $axis } koordináta. A legkisebb lehetséges érték 0.
+error-odd-board-cells = A tábla mezőinek száma (vízszintes méret * függőleges méret) a jokereket és bombákat nem számítva csak páros lehet
+error-too-many-extra-cards = Ezen a táblán legfeljebb { $max } joker és bomba lehet
+error-empty-shape = A tábla alakzatában nincs egy mező sem
+error-invalid-shape-char = A tábla alakzata csak #, . és szóköz karaktereket tartalmazhat, { $char } karaktert nem
+error-shape-io = Nem sikerült beolvasni a(z) { $path } alakzatot: { $reason }
+error-no-card = A(z) ({ $x },{ $y }) helyen nincs kártya
+error-partner-covered = A pár másik kártyája még le van takarva
+error-invalid-board-code = A táblakód érvénytelen, vagy a játék egy másik verziójából származik
+error-invalid-result-code = Az eredménykód érvénytelen, ellenőrizd, hogy teljes egészében másoltad-e
+error-not-enough-card-types = Nem hozható létre { $max } mezőnél nagyobb tábla
+error-board-too-large = A { $x } × { $y } mezős tábla túl nagy
+error-unparsable-input = A bemenet nem értelmezhető
+error-single-number = A(z) `{ $input }` csak egy szám. Írj be kettőt, pl. 3,4.
+error-unreadable-coords = A(z) `{ $input }` nem egy mező. Írj be két számot, pl. 3,4, vagy egy oszlopot és egy sort, pl. c4.
+error-unreadable-dimensions = A(z) `{ $input }` nem egy táblaméret. Írd be az oszlopok és a sorok számát, pl. 6,4.
+error-unreadable-answer = A(z) `{ $input }` nem válasz. Írj y-t vagy n-t.
+error-no-such-cell = Nincs { $input }. mező. A mezők soronként 1-től { $max }-ig vannak számozva.
+error-off-board-x = A(z) `{ $input }` kívül esik a táblán. A tábla { $size } széles, az x 1 és { $size } között lehet.
+error-off-board-y = A(z) `{ $input }` kívül esik a táblán. A tábla { $size } magas, az y 1 és { $size } között lehet.
+error-invalid-column-letter = A(z) `{ $letter }` nem oszlopbetű.
+error-column-out-of-range = Nincs { $column } oszlop. Az utolsó oszlop: { $last }.
+error-config-io = Nem sikerült beolvasni a(z) { $path } beállításfájlt: { $reason }
+error-invalid-config = Érvénytelen beállítás: { $reason }
+error-config-save = Nem sikerült a beállításokat menteni ide: { $path }: { $reason }
+error-connection-lost = Megszakadt a kapcsolat az ellenféllel: { $reason }
+error-hints-disabled = Ebben a játékban nem kérhetsz segítséget
+error-stats-io = Nem sikerült elérni a(z) { $path } statisztikafájlt: { $reason }
+error-save-io = Nem sikerült elérni a(z) { $path } mentett játékot: { $reason }
+error-history-io = Nem sikerült elérni a játékok előzményeit ({ $path }): { $reason }
+error-export-io = Nem sikerült kiírni az exportot ({ $path }): { $reason }
+error-replay-io = Nem sikerült elérni a(z) { $path } felvételt: { $reason }
+error-unknown-command = Ismeretlen parancs: `:{ $command }`. A parancsok listája: :help
+error-command-unavailable = A(z) `:{ $command }` parancs most nem használható.
+error-unknown-theme = Nincs `{ $name }` nevű téma. Választható: { $themes }
+error-invalid-profile-name = A(z) `{ $name }` nem lehet egy profil neve. Legfeljebb 32 betűt, számjegyet, - vagy _ jelet használj.
+error-not-your-turn = Az ellenfeled jön. Addig is csevegni a :say és az üzenet beírásával tudsz.
+error-chat-too-fast = Túl gyorsan küldöd az üzeneteket. Várj néhány másodpercet.
+error-invalid-state = Ez nem lehetséges a(z) { $state } állapotból.
+error-no-power-up = Nincs :{ $name } képességed. Találj meg több párt egymás után, hogy szerezz egyet.
+error-invalid-level = Nincs ilyen pálya. Válassz 1 és { $max } között.
+error-invalid-menu-choice = Nincs ilyen lehetőség. Válassz 1 és { $max } között.
+error-level-locked = A(z) { $level }. pálya zárolva van. Előbb teljesítsd az előtte lévőket.
+
+# Akadálymentes kimenet
+board-row = { $row }. sor: { $cards }
+card-hidden = rejtett
+card-hidden-layer = rejtett, { $layer }. réteg
+card-hole = üres
+card-face-up = { $card }
+card-matched = megtalált { $card }
+card-revealed = felfordított { $card }
+card-hinted = jelzett { $card }
+card-odds = lefordítva, { $percent }% eséllyel pár
+card-peeked = megnézett { $card }
+card-joker = joker
+card-bomb = bomba
+card-at = { $card } itt: { $position }
+action-revealed = Felfordítva: { $card }.
+action-match = Pár: { $first } és { $second }.
+action-mismatch = Nem pár: { $first } és { $second }.
@@ -0,0 +1,100 @@
+use crate::board::{Card, Pairing, Vec2};
+
+/// How a guess ended.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Outcome {
+    Match,
+    Mismatch,
+    Bomb,
+    TimeUp,
+}
+
+/// A card turned face up during a guess, and what the player knew about it
+/// beforehand.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Pick {
+    pub at: Vec2,
+    pub card: Card,
+    /// Set if the player had seen the card at `at` before, and it hadn't
+    /// moved since.
+    pub seen: bool,
+    /// Where the player had seen a card that pairs with this one, if
+    /// anywhere.
+    pub partner: Option<Vec2>,
+}
+
+/// A guess made by either player, along with what they had seen of the
+/// board when they made it.
+#[derive(Clone, Debug)]
+pub struct Guess {
+    /// 1-based number of the guess on the current board.
+    pub number: usize,
+    /// The cards turned face up, in order.
+    pub picks: Vec<Pick>,
+    pub outcome: Outcome,
+    /// Two cards that had been seen and pair up, if there were any when the
+    /// guess was started.
+    pub known_pair: Option<(Vec2, Vec2)>,
+}
+
+/// What a guess did with what the player had seen.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Verdict {
+    /// Nothing seen before was left unused.
+    Good,
+    /// Both cards of a pair had been seen, but the guess went elsewhere.
+    Wasted { first: Vec2, second: Vec2 },
+    /// The first card turned over matched one seen before, but the second
+    /// card wasn't that one.
+    MissedPartner { first: Vec2, partner: Vec2 },
+    /// A card seen before was turned over again without completing a pair,
+    /// where a new card would have shown something.
+    Repeated { at: Vec2 },
+}
+
+impl Guess {
+    /// Judge the guess by what had been seen when it was made. A found
+    /// pair is always [`Verdict::Good`].
+    pub fn verdict(&self) -> Verdict {
+        if self.outcome == Outcome::Match {
+            return Verdict::Good;
+        }
+        if let Some((first, second)) = self.known_pair {
+            return Verdict::Wasted { first, second };
+        }
+        if let Some(first) = self.picks.first() {
+            if let Some(partner) = first.partner.filter(|_| !first.seen) {
+                return Verdict::MissedPartner {
+                    first: first.at,
+                    partner,
+                };
+            }
+        }
+        match self.picks.iter().find(|pick| pick.seen) {
+            Some(pick) => Verdict::Repeated { at: pick.at },
+            None => Verdict::Good,
+        }
+    }
+}
+
+/// Find two of the `seen` cards that pair up, preferring equal ones.
+/// Jokers and bombs are left out: a joker pairs with any card, so seeing
+/// one tells nothing about where a pair is.
+pub fn known_pair(seen: &[(Vec2, Card)]) -> Option<(Vec2, Vec2)> {
+    let seen: Vec<(Vec2, Card)> = seen
+        .iter()
+        .copied()
+        .filter(|(_, card)| !card.is_joker() && !card.is_bomb())
+        .collect();
+    let find = |equal: bool| {
+        seen.iter().enumerate().find_map(|(i, (a, card))| {
+            seen[i + 1..]
+                .iter()
+                .find(|(_, other)| {
+                    card.pairs_with(other) && (!equal || card == other)
+                })
+                .map(|(b, _)| (*a, *b))
+        })
+    };
+    find(true).or_else(|| find(false))
+}
@@ -0,0 +1,100 @@
+use crate::event::{Event, Observer};
+
+/// A sound effect played in response to a game event.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Sound {
+    /// A card was turned face up.
+    Reveal,
+    /// The two revealed cards form a pair.
+    Match,
+    /// The two revealed cards don't form a pair.
+    Mismatch,
+    /// The board was cleared.
+    Victory,
+}
+
+impl Sound {
+    /// The WAV sample for the sound, embedded in the binary.
+    #[cfg(feature = "sound")]
+    fn sample(self) -> &'static [u8] {
+        match self {
+            Sound::Reveal => include_bytes!("../assets/sounds/reveal.wav"),
+            Sound::Match => include_bytes!("../assets/sounds/match.wav"),
+            Sound::Mismatch => include_bytes!("../assets/sounds/mismatch.wav"),
+            Sound::Victory => include_bytes!("../assets/sounds/victory.wav"),
+        }
+    }
+}
+
+/// Plays sound effects without blocking the game loop. Does nothing if the
+/// game was built without the `sound` feature, if muted, or if no audio
+/// device is available.
+pub struct Audio {
+    #[cfg(feature = "sound")]
+    output: Option<(rodio::OutputStream, rodio::OutputStreamHandle)>,
+}
+
+impl Audio {
+    /// Open the default audio device, unless `muted`.
+    pub fn new(muted: bool) -> Audio {
+        let mut audio = Audio {
+            #[cfg(feature = "sound")]
+            output: None,
+        };
+        audio.set_muted(muted);
+        audio
+    }
+
+    /// Close the audio device if `muted`, or open it again otherwise.
+    pub fn set_muted(&mut self, muted: bool) {
+        #[cfg(feature = "sound")]
+        {
+            self.output = match muted {
+                true => None,
+                false => rodio::OutputStream::try_default().ok(),
+            };
+        }
+        #[cfg(not(feature = "sound"))]
+        let _ = muted;
+    }
+
+    /// Start playing a sound effect and return immediately.
+    pub fn play(&self, sound: Sound) {
+        #[cfg(feature = "sound")]
+        if let Some((_, handle)) = &self.output {
+            use rodio::Source;
+
+            let sample = std::io::Cursor::new(sound.sample());
+            if let Ok(source) = rodio::Decoder::new_wav(sample) {
+                // A failure to play a sound effect is not worth reporting
+                let _ = handle.play_raw(source.convert_samples());
+            }
+        }
+        #[cfg(not(feature = "sound"))]
+        let _ = sound;
+    }
+}
+
+/// Plays the sound effect matching each event.
+impl Observer for Audio {
+    fn notify(&mut self, event: &Event) {
+        let sound = match event {
+            Event::SoundToggled { muted } => {
+                self.set_muted(*muted);
+                return;
+            }
+            // The outcome of the guess has a sound of its own
+            Event::CardRevealed {
+                completes_guess: false,
+                ..
+            } => Sound::Reveal,
+            Event::PairMatched { .. } => Sound::Match,
+            Event::Mismatch { .. } | Event::TimeUp | Event::BombExploded => {
+                Sound::Mismatch
+            }
+            Event::GameWon { .. } => Sound::Victory,
+            _ => return,
+        };
+        self.play(sound);
+    }
+}
@@ -0,0 +1,871 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::error::{GameError, Result};
+use crate::grid::Grid;
+use crate::shape::Shape;
+
+/// The suit of a playing card.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Suit {
+    Spades,
+    Hearts,
+    Diamonds,
+    Clubs,
+}
+
+impl Suit {
+    /// Every suit, in the order of their ids.
+    pub const ALL: [Suit; 4] =
+        [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+
+    /// The symbol drawn for the suit.
+    pub fn symbol(self) -> char {
+        match self {
+            Suit::Spades => '♠',
+            Suit::Hearts => '♥',
+            Suit::Diamonds => '♦',
+            Suit::Clubs => '♣',
+        }
+    }
+
+    /// The letter standing in for the suit on terminals that can't draw
+    /// its symbol.
+    pub fn letter(self) -> char {
+        match self {
+            Suit::Spades => 'S',
+            Suit::Hearts => 'H',
+            Suit::Diamonds => 'D',
+            Suit::Clubs => 'C',
+        }
+    }
+
+    /// Check if the suit is drawn in red rather than black.
+    pub fn is_red(self) -> bool {
+        matches!(self, Suit::Hearts | Suit::Diamonds)
+    }
+}
+
+/// The default card type, identified by the pair it belongs to. Boards
+/// with more pairs than [symbols](`Board::CARD_CHARS`) use each symbol for
+/// several pairs, told apart by a number after the symbol. Playing cards
+/// have ids of their own, made up of a rank and a [`Suit`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Card(pub u32);
+
+impl Card {
+    /// A wildcard that forms a pair with any other card.
+    pub const JOKER: Card = Card(u32::MAX - 1);
+
+    /// A trap that doesn't pair with anything, and goes off when revealed.
+    pub const BOMB: Card = Card(u32::MAX - 2);
+
+    /// Stands in for a card whose face isn't known yet, on the board of
+    /// the joining player of a networked game until the host reveals it.
+    pub const UNKNOWN: Card = Card(u32::MAX);
+
+    /// The symbol drawn for jokers, which no other card uses.
+    pub const JOKER_CHAR: char = '✪';
+
+    /// The symbol drawn for bombs, which no other card uses.
+    pub const BOMB_CHAR: char = '✹';
+
+    /// The ranks of playing cards, from the ace to the king.
+    pub const RANK_CHARS: [char; 13] = [
+        'A', '2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K',
+    ];
+
+    /// The id of the first playing card, far above the ids of symbols.
+    const PLAYING_BASE: u32 = 1 << 30;
+
+    /// The id of the first card drawn with [ASCII](`Board::ASCII_CHARS`)
+    /// characters, between the ids of symbols and playing cards.
+    const ASCII_BASE: u32 = 1 << 29;
+
+    /// The card of the given pair drawn with ASCII characters, for
+    /// terminals and fonts that can't draw the symbols. Pairs after the
+    /// first [36](`Board::ASCII_CHARS`) are drawn with two characters, e.g.
+    /// `AA` and `AB`.
+    pub fn ascii(pair: u32) -> Card {
+        Card(Card::ASCII_BASE + pair)
+    }
+
+    /// The pair of a card drawn with ASCII characters, or `None` for other
+    /// cards.
+    fn ascii_pair(self) -> Option<u32> {
+        let pair = self.0.checked_sub(Card::ASCII_BASE)?;
+        (self.0 < Card::PLAYING_BASE).then_some(pair)
+    }
+
+    /// The id of the first [emoji](`Board::EMOJI_CHARS`) card, between the
+    /// ids of symbols and ASCII cards.
+    const EMOJI_BASE: u32 = 1 << 28;
+
+    /// The card of the given pair drawn with an emoji. Like symbols,
+    /// emoji are numbered once every one of them is used.
+    pub fn emoji(pair: u32) -> Card {
+        Card(Card::EMOJI_BASE + pair)
+    }
+
+    /// The pair of an emoji card, or `None` for other cards.
+    fn emoji_pair(self) -> Option<u32> {
+        let pair = self.0.checked_sub(Card::EMOJI_BASE)?;
+        (self.0 < Card::ASCII_BASE).then_some(pair)
+    }
+
+    /// Check if the card is drawn with an [emoji](`Card::emoji`).
+    pub fn is_emoji(self) -> bool {
+        self.emoji_pair().is_some()
+    }
+
+    /// Check if the card is drawn with [ASCII](`Card::ascii`) characters.
+    pub fn is_ascii(self) -> bool {
+        self.ascii_pair().is_some()
+    }
+
+    /// The characters drawn for a card of the ASCII set, or `None` for
+    /// other cards.
+    fn ascii_text(self) -> Option<String> {
+        let chars = &Board::ASCII_CHARS;
+        let pair = self.ascii_pair()? as usize;
+        Some(match pair.checked_sub(chars.len()) {
+            None => chars[pair].to_string(),
+            Some(n) => {
+                let first = chars[n / chars.len() % chars.len()];
+                format!("{}{}", first, chars[n % chars.len()])
+            }
+        })
+    }
+
+    /// The playing card of the given 0-based rank, where 0 is the ace.
+    pub fn playing(rank: u32, suit: Suit) -> Card {
+        debug_assert!((rank as usize) < Card::RANK_CHARS.len());
+        Card(Card::PLAYING_BASE + rank * 4 + suit as u32)
+    }
+
+    /// The 0-based rank of a playing card, or `None` for other cards.
+    pub fn rank(self) -> Option<u32> {
+        self.playing_index().map(|i| i / 4)
+    }
+
+    /// The suit of a playing card, or `None` for other cards.
+    pub fn suit(self) -> Option<Suit> {
+        self.playing_index().map(|i| Suit::ALL[i as usize % 4])
+    }
+
+    /// The position of a playing card in a deck sorted by rank.
+    fn playing_index(self) -> Option<u32> {
+        let deck = Card::RANK_CHARS.len() as u32 * 4;
+        let index = self.0.checked_sub(Card::PLAYING_BASE)?;
+        (index < deck).then_some(index)
+    }
+
+    /// Check if this is a [joker](`Card::JOKER`).
+    pub fn is_joker(self) -> bool {
+        self == Card::JOKER
+    }
+
+    /// Check if this is a [bomb](`Card::BOMB`).
+    pub fn is_bomb(self) -> bool {
+        self == Card::BOMB
+    }
+
+    /// Check if the id belongs to a card that can be dealt: a symbol, an
+    /// emoji, an ASCII or a playing card, a joker or a bomb.
+    pub fn is_valid(self) -> bool {
+        self.0 < Card::PLAYING_BASE
+            || self.playing_index().is_some()
+            || self.is_joker()
+            || self.is_bomb()
+    }
+
+    /// The symbol drawn for the card, which is the rank for playing cards.
+    pub fn symbol(self) -> char {
+        match self {
+            Card::JOKER => Card::JOKER_CHAR,
+            Card::BOMB => Card::BOMB_CHAR,
+            _ => {
+                let nth = |chars: &[char], pair: u32| {
+                    chars[pair as usize % chars.len()]
+                };
+                if let Some(rank) = self.rank() {
+                    Card::RANK_CHARS[rank as usize]
+                } else if let Some(pair) = self.ascii_pair() {
+                    nth(&Board::ASCII_CHARS, pair)
+                } else if let Some(pair) = self.emoji_pair() {
+                    nth(&Board::EMOJI_CHARS, pair)
+                } else {
+                    nth(&Board::CARD_CHARS, self.0)
+                }
+            }
+        }
+    }
+
+    /// The card as it is drawn on terminals that can't draw characters
+    /// beyond ASCII. Symbol cards are [dealt](`Card::ascii`) with ASCII
+    /// characters already.
+    pub fn ascii_face(self) -> String {
+        match (self, self.suit()) {
+            (Card::JOKER, _) => "*".to_string(),
+            (Card::BOMB, _) => "!".to_string(),
+            (_, Some(suit)) => format!("{}{}", self.symbol(), suit.letter()),
+            _ => self.to_string(),
+        }
+    }
+
+    /// How many pairs with the same symbol come before this one.
+    pub fn repeat(self) -> u32 {
+        match self {
+            Card::JOKER | Card::BOMB => 0,
+            _ if self.rank().is_some() => 0,
+            _ if self.is_ascii() => 0,
+            _ => match self.emoji_pair() {
+                Some(pair) => pair / Board::EMOJI_CHARS.len() as u32,
+                None => self.0 / Board::CARD_CHARS.len() as u32,
+            },
+        }
+    }
+}
+
+/// Decides which cards form a pair when they are revealed together.
+pub trait Pairing {
+    /// Check if this card and `other` form a pair.
+    fn pairs_with(&self, other: &Self) -> bool;
+
+    /// Check if the card pairs with any other, so that it has no partner
+    /// of its own.
+    fn is_wild(&self) -> bool {
+        false
+    }
+}
+
+/// Cards pair up with the other card of their kind, playing cards with any
+/// card of the same rank, and jokers with any card but bombs, which never
+/// pair up.
+impl Pairing for Card {
+    fn pairs_with(&self, other: &Card) -> bool {
+        if self.is_bomb() || other.is_bomb() {
+            return false;
+        }
+        if self.is_joker() || other.is_joker() {
+            return true;
+        }
+        match (self.rank(), other.rank()) {
+            (Some(rank), Some(other)) => rank == other,
+            _ => self == other,
+        }
+    }
+
+    fn is_wild(&self) -> bool {
+        self.is_joker()
+    }
+}
+
+/// The symbol of the card, followed by the number of its pair for repeated
+/// symbols, e.g. `☀` and `☀2`, or by the suit for playing cards, e.g. `A♠`.
+/// Cards of the ASCII set are one or two characters, e.g. `A` and `AB`.
+/// Emoji are numbered like symbols. Padding counts characters, not columns
+/// of the terminal, so use [`pad`](crate::screen::pad) to line cards up.
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(text) = self.ascii_text() {
+            return f.pad(&text);
+        }
+        // Padded as a whole, so that it lines up on the board
+        match (self.suit(), self.repeat()) {
+            (Some(suit), _) => {
+                f.pad(&format!("{}{}", self.symbol(), suit.symbol()))
+            }
+            (None, 0) => f.pad(&self.symbol().to_string()),
+            (None, n) => f.pad(&format!("{}{}", self.symbol(), n + 1)),
+        }
+    }
+}
+
+/// Read a card back from the way it is [displayed](`fmt::Display`), e.g.
+/// in snapshots.
+impl FromStr for Card {
+    type Err = GameError;
+
+    fn from_str(s: &str) -> Result<Card> {
+        let mut chars = s.trim().chars();
+        let symbol = chars.next().ok_or(GameError::UnparsableInput)?;
+        let rest = chars.as_str();
+
+        let suit = Suit::ALL.into_iter().find(|suit| {
+            let mut buf = [0; 4];
+            rest == suit.symbol().encode_utf8(&mut buf)
+        });
+        if let Some(suit) = suit {
+            let rank = Card::RANK_CHARS
+                .iter()
+                .position(|c| *c == symbol)
+                .ok_or(GameError::UnparsableInput)?;
+            return Ok(Card::playing(rank as u32, suit));
+        }
+        match symbol {
+            Card::JOKER_CHAR if rest.is_empty() => return Ok(Card::JOKER),
+            Card::BOMB_CHAR if rest.is_empty() => return Ok(Card::BOMB),
+            _ => {}
+        }
+
+        let ascii = |c: char| {
+            let index = Board::ASCII_CHARS.iter().position(|a| *a == c)?;
+            Some(index as u32)
+        };
+        if let Some(first) = ascii(symbol) {
+            let mut rest = rest.chars();
+            let pair = match (rest.next().map(ascii), rest.next()) {
+                (None, _) => first,
+                (Some(Some(second)), None) => {
+                    let len = Board::ASCII_CHARS.len() as u32;
+                    (first + 1) * len + second
+                }
+                _ => return Err(GameError::UnparsableInput),
+            };
+            return Ok(Card::ascii(pair));
+        }
+
+        let repeat = match rest {
+            "" => 0,
+            n => match n.parse::<u32>() {
+                Ok(n) if n >= 2 => n - 1,
+                _ => return Err(GameError::UnparsableInput),
+            },
+        };
+        let find = |chars: &[char]| {
+            let index = chars.iter().position(|c| *c == symbol)?;
+            Some(repeat * chars.len() as u32 + index as u32)
+        };
+        if let Some(pair) = find(&Board::EMOJI_CHARS) {
+            return Ok(Card::emoji(pair));
+        }
+        find(&Board::CARD_CHARS)
+            .map(Card)
+            .ok_or(GameError::UnparsableInput)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Vec2 {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// An object used to convert coordinates (2D index) into an array (1D) index.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Idx2d {
+    pub size_x: i32,
+    pub size_y: i32,
+}
+
+impl Idx2d {
+    /// Create a new indexer object with the given column/row counts.
+    pub fn new(size_x: i32, size_y: i32) -> Idx2d {
+        Idx2d { size_x, size_y }
+    }
+
+    /// Convert coordinates into an array index with bounds checking. If
+    /// the coordinates don't map to an array element defined by the stored
+    /// sizes, return an `Err`.
+    pub fn of(&self, coords: Vec2) -> Result<usize> {
+        let Vec2 { x, y } = coords;
+        if x < 0 {
+            return Err(GameError::CoordinateUnderflow { axis: 'x' });
+        }
+        if y < 0 {
+            return Err(GameError::CoordinateUnderflow { axis: 'y' });
+        }
+        if x >= self.size_x {
+            return Err(GameError::CoordinateOverflow {
+                axis: 'x',
+                max: self.size_x,
+            });
+        }
+        if y >= self.size_y {
+            return Err(GameError::CoordinateOverflow {
+                axis: 'y',
+                max: self.size_y,
+            });
+        }
+        Ok(self.unchecked(coords))
+    }
+
+    /// Convert coordinates into an array index without bounds checking.
+    /// The index is calculated in `usize`, so it doesn't overflow for
+    /// coordinates within the bounds.
+    pub fn unchecked(&self, coords: Vec2) -> usize {
+        let Vec2 { x, y } = coords;
+        y as usize * self.size_x as usize + x as usize
+    }
+
+    /// The number of cells, or `None` if it doesn't fit in an `i32`, which
+    /// is the largest board that coordinates can address.
+    pub fn cells(&self) -> Option<i32> {
+        self.size_x.checked_mul(self.size_y)
+    }
+
+    /// Convert an array index back into coordinates, the inverse of
+    /// [`Idx2d::unchecked`].
+    pub fn coords(&self, index: usize) -> Vec2 {
+        let x = (index % self.size_x as usize) as i32;
+        let y = (index / self.size_x as usize) as i32;
+        Vec2 { x, y }
+    }
+
+    /// The 1-based number of the cell at `coords`, counting row by row in
+    /// reading order.
+    pub fn number(&self, coords: Vec2) -> i32 {
+        coords.y * self.size_x + coords.x + 1
+    }
+
+    /// The coordinates of the cell with the given [number](`Idx2d::number`),
+    /// or `None` if there is no such cell.
+    pub fn from_number(&self, number: i32) -> Option<Vec2> {
+        let cells = self.cells()?;
+        (1..=cells)
+            .contains(&number)
+            .then(|| self.coords(number as usize - 1))
+    }
+
+    /// Iterate through all the possible coordinates - defined by `size_x`
+    /// and `size_y` - in row major order.
+    pub fn iter_all(&self) -> impl Iterator<Item = Vec2> + '_ {
+        let max = self.size_x as usize * self.size_y as usize;
+        (0..max).map(|i| self.coords(i))
+    }
+}
+
+/// A board of playing cards. Each card kind appears exactly twice, apart
+/// from special cards such as jokers and bombs.
+#[derive(Clone, Debug)]
+pub struct Board<T = Card> {
+    idx: Idx2d,
+    /// The cards in row major order. Holes in the [`Shape`] of the board
+    /// are `None`.
+    pub cards: Vec<Option<T>>,
+}
+
+impl<T: Clone> Board<T> {
+    /// Create a new board with the given sizes and fill it randomly with
+    /// pairs of cards taken from the front of `kinds`.
+    pub fn with_kinds<R: Rng + ?Sized>(
+        size_x: i32,
+        size_y: i32,
+        kinds: &[T],
+        rng: &mut R,
+    ) -> Result<Board<T>> {
+        debug_assert!(size_x > 0);
+        debug_assert!(size_y > 0);
+        let cells = Idx2d::new(size_x, size_y).cells().ok_or(
+            GameError::BoardTooLarge {
+                x: size_x,
+                y: size_y,
+            },
+        )?;
+        debug_assert!(cells % 2 == 0);
+
+        let shape = Shape::rectangle(Vec2 {
+            x: size_x,
+            y: size_y,
+        });
+        Board::with_shape(&shape, kinds, rng)
+    }
+
+    /// Create a new board of the given shape and fill its cells randomly
+    /// with pairs of cards taken from the front of `kinds`.
+    pub fn with_shape<R: Rng + ?Sized>(
+        shape: &Shape,
+        kinds: &[T],
+        rng: &mut R,
+    ) -> Result<Board<T>> {
+        Board::deal(shape, kinds, Vec::new(), rng)
+    }
+
+    /// Create a new board of the given shape with some of its cells taken
+    /// by the `extra` cards, such as jokers, and the rest filled like
+    /// [`Board::with_shape`].
+    pub fn with_extra<R: Rng + ?Sized>(
+        shape: &Shape,
+        kinds: &[T],
+        extra: &[T],
+        rng: &mut R,
+    ) -> Result<Board<T>> {
+        if extra.len() > shape.cells() {
+            return Err(GameError::TooManyExtraCards {
+                max: shape.cells() as i32,
+            });
+        }
+        Board::deal(shape, kinds, extra.to_vec(), rng)
+    }
+
+    /// Scatter the `extra` cards and pairs from the front of `kinds` over
+    /// the cells of `shape`.
+    fn deal<R: Rng + ?Sized>(
+        shape: &Shape,
+        kinds: &[T],
+        extra: Vec<T>,
+        rng: &mut R,
+    ) -> Result<Board<T>> {
+        let size = shape.cells() - extra.len();
+        if !size.is_multiple_of(2) {
+            return Err(GameError::OddBoardCells);
+        }
+        if size / 2 > kinds.len() {
+            return Err(GameError::NotEnoughCardTypes {
+                max: kinds.len() as i32,
+            });
+        }
+
+        // Assign a card to each pair of spaces, then scatter the pairs
+        let mut pairs: Vec<T> = kinds[..size / 2]
+            .iter()
+            .flat_map(|card| [card.clone(), card.clone()])
+            .chain(extra)
+            .collect();
+        pairs.shuffle(rng);
+
+        let idx = *shape.idx();
+        let cards = idx
+            .iter_all()
+            .map(|c| if shape.contains(c) { pairs.pop() } else { None })
+            .collect();
+        Ok(Board { idx, cards })
+    }
+}
+
+impl<T> Board<T> {
+    /// Number of swaps [`Board::spread_pairs`] tries for each distance.
+    const SPREAD_ATTEMPTS: usize = 20_000;
+
+    /// Create a board with the cards laid out in row major order, and
+    /// `None` for holes.
+    /// # Panics
+    /// Panics if the number of cards doesn't match the board size.
+    pub fn from_cards(
+        size_x: i32,
+        size_y: i32,
+        cards: Vec<Option<T>>,
+    ) -> Board<T> {
+        assert_eq!(cards.len(), size_x as usize * size_y as usize);
+        Board {
+            idx: Idx2d::new(size_x, size_y),
+            cards,
+        }
+    }
+
+    /// The indexer describing the dimensions of the board.
+    pub fn idx(&self) -> &Idx2d {
+        &self.idx
+    }
+
+    /// Check if there is a card at `c`, rather than a hole.
+    pub fn has_card(&self, c: Vec2) -> bool {
+        self.idx.of(c).is_ok_and(|i| self.cards[i].is_some())
+    }
+
+    /// The number of holes in the shape of the board.
+    pub fn holes(&self) -> usize {
+        self.cards.iter().filter(|c| c.is_none()).count()
+    }
+
+    /// The shape of the board, to deal another one like it.
+    pub fn shape(&self) -> Shape {
+        Shape::from_cells(self.idx, self.cards.iter().map(Option::is_some))
+    }
+
+    /// Randomly rearrange the cards, leaving the ones at positions for
+    /// which `fixed` returns `true` in place.
+    pub fn reshuffle<R, F>(&mut self, rng: &mut R, fixed: F)
+    where
+        R: Rng + ?Sized,
+        F: Fn(Vec2) -> bool,
+    {
+        let movable: Vec<usize> = self
+            .idx
+            .iter_all()
+            .filter(|c| self.has_card(*c) && !fixed(*c))
+            .map(|c| self.idx.unchecked(c))
+            .collect();
+
+        // Fisher-Yates over the movable positions only
+        for i in (1..movable.len()).rev() {
+            let j = rng.gen_range(0..=i);
+            self.cards.swap(movable[i], movable[j]);
+        }
+    }
+
+    /// Move cards around until both cards of every pair are at least
+    /// `min_distance` steps apart on `grid`. If that takes too long, e.g.
+    /// because the board is too small for it, the distance is lowered one
+    /// step at a time, starting from the distance between the cards
+    /// farthest apart if `min_distance` is more than that. Returns the
+    /// distance that was reached.
+    pub fn spread_pairs<R>(
+        &mut self,
+        grid: &dyn Grid,
+        min_distance: i32,
+        rng: &mut R,
+    ) -> i32
+    where
+        R: Rng + ?Sized,
+        T: PartialEq + Pairing,
+    {
+        let cells: Vec<Vec2> =
+            self.idx.iter_all().filter(|c| self.has_card(*c)).collect();
+        let farthest = cells
+            .iter()
+            .flat_map(|a| cells.iter().map(|b| grid.distance(*a, *b)))
+            .max()
+            .unwrap_or(0);
+        let mut distance = min_distance.min(farthest);
+        while distance > 1 {
+            let too_close = |board: &Board<T>, c: Vec2| {
+                board
+                    .find_partner(c)
+                    .is_some_and(|p| grid.distance(c, p) < distance)
+            };
+            // Each pair that is too close is counted for both of its cards
+            let mut conflicts =
+                cells.iter().filter(|c| too_close(self, **c)).count();
+
+            // Swap a card that is too close to its partner with a random
+            // one, unless that makes things worse
+            for _ in 0..Board::<T>::SPREAD_ATTEMPTS {
+                if conflicts == 0 {
+                    return distance;
+                }
+                let (a, b) = (
+                    cells[rng.gen_range(0..cells.len())],
+                    cells[rng.gen_range(0..cells.len())],
+                );
+                if !too_close(self, a) || self[a] == self[b] {
+                    continue;
+                }
+                // The swap can only change the partners of the two cards
+                // and those that pair with either, counted once each even
+                // when more than two cards pair up
+                let affected: Vec<Vec2> = cells
+                    .iter()
+                    .copied()
+                    .filter(|c| {
+                        [a, b].contains(c)
+                            || self.is_match(*c, a)
+                            || self.is_match(*c, b)
+                    })
+                    .collect();
+                let count = |board: &Board<T>| {
+                    affected.iter().filter(|c| too_close(board, **c)).count()
+                };
+                let before = count(self);
+                self.swap(a, b);
+                let after = count(self);
+                if after > before {
+                    self.swap(a, b);
+                } else {
+                    conflicts = conflicts + after - before;
+                }
+            }
+            distance -= 1;
+        }
+        distance
+    }
+
+    /// Swap the cards at two positions.
+    fn swap(&mut self, a: Vec2, b: Vec2) {
+        let (a, b) = (self.idx.unchecked(a), self.idx.unchecked(b));
+        self.cards.swap(a, b);
+    }
+
+    /// Find a card that forms a pair with the one at `c`. Cards that
+    /// [pair with](`Pairing`) any card, such as jokers, don't have one and
+    /// are never returned.
+    pub fn find_partner(&self, c: Vec2) -> Option<Vec2>
+    where
+        T: Pairing,
+    {
+        self.idx.iter_all().find(|other| {
+            self.is_match(c, *other)
+                && !self[c].is_wild()
+                && !self[*other].is_wild()
+        })
+    }
+
+    /// Check if the cards at two positions form a pair.
+    pub fn is_match(&self, c1: Vec2, c2: Vec2) -> bool
+    where
+        T: Pairing,
+    {
+        c1 != c2
+            && self.has_card(c1)
+            && self.has_card(c2)
+            && self[c1].pairs_with(&self[c2])
+    }
+}
+
+impl Board<Card> {
+    /// Symbols to use as "cards"
+    pub const CARD_CHARS: [char; 55] = [
+        '☀', '☁', '★', '☇', '☈', '☉', '☊', '☋', '☌', '☍', '☎', '☔', '☕', '☗',
+        '☘', '☙', '☚', '☛', '☝', '☠', '☡', '☢', '☣', '☤', '☥', '☦', '☧', '☩',
+        '☫', '☬', '☭', '☮', '☯', '☼', '☿', '♀', '♁', '♂', '♃', '♄', '♅', '♆',
+        '♇', '♈', '♉', '♊', '♋', '♌', '♍', '♎', '♏', '♐', '♑', '♒',
+        '♓',
+    ];
+
+    /// Characters to use as "cards" on terminals that can't draw the
+    /// [symbols](`Board::CARD_CHARS`).
+    pub const ASCII_CHARS: [char; 36] = [
+        'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N',
+        'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '0', '1',
+        '2', '3', '4', '5', '6', '7', '8', '9',
+    ];
+
+    /// Emoji to use as "cards", each two columns wide in the terminal.
+    pub const EMOJI_CHARS: [char; 60] = [
+        '🍎', '🍊', '🍋', '🍌', '🍉', '🍇', '🍓', '🍒', '🍑', '🍍', '🥝', '🥥',
+        '🥑', '🍆', '🥕', '🌽', '🥦', '🍄', '🌰', '🍞', '🧀', '🍔', '🍕', '🌮',
+        '🍩', '🍪', '🎂', '🍭', '🐶', '🐱', '🐭', '🐹', '🐰', '🦊', '🐻', '🐼',
+        '🐨', '🐯', '🦁', '🐮', '🐷', '🐸', '🐵', '🐔', '🐧', '🐤', '🦆', '🦉',
+        '🐺', '🐗', '🐴', '🦄', '🐝', '🐛', '🦋', '🐌', '🐞', '🐢', '🐙', '🦀',
+    ];
+
+    /// Number of pairs that can share a symbol, so that the number after
+    /// it stays a single digit.
+    pub const MAX_REPEATS: usize = 9;
+
+    /// Maximum possible board size
+    pub const MAX_SIZE: i32 =
+        (Self::CARD_CHARS.len() * Self::MAX_REPEATS * 2) as i32;
+
+    /// Maximum possible board size with the [ASCII](`Card::ascii`) cards,
+    /// which are doubled up rather than numbered. No card set allows more.
+    pub const MAX_ASCII_SIZE: i32 =
+        (Self::ASCII_CHARS.len() * (Self::ASCII_CHARS.len() + 1) * 2) as i32;
+
+    /// A card of each pair on the largest board of symbols.
+    pub fn symbol_cards() -> Vec<Card> {
+        (0..Self::MAX_SIZE as u32 / 2).map(Card).collect()
+    }
+
+    /// Maximum possible board size with [emoji](`Board::EMOJI_CHARS`).
+    pub const MAX_EMOJI_SIZE: i32 =
+        (Self::EMOJI_CHARS.len() * Self::MAX_REPEATS * 2) as i32;
+
+    /// A card of each pair on the largest board of emoji.
+    pub fn emoji_cards() -> Vec<Card> {
+        (0..Self::MAX_EMOJI_SIZE as u32 / 2)
+            .map(Card::emoji)
+            .collect()
+    }
+
+    /// A card of each pair on the largest board of ASCII cards.
+    pub fn ascii_cards() -> Vec<Card> {
+        (0..Self::MAX_ASCII_SIZE as u32 / 2)
+            .map(Card::ascii)
+            .collect()
+    }
+
+    /// Create a new board with the given sizes and fill it randomly with cards
+    /// from the [predefined list](`Board::CARD_CHARS`). Symbols are only
+    /// repeated once every one of them is on the board.
+    pub fn new<R: Rng + ?Sized>(
+        size_x: i32,
+        size_y: i32,
+        rng: &mut R,
+    ) -> Result<Board> {
+        Board::with_kinds(size_x, size_y, &Board::symbol_cards(), rng)
+    }
+
+    /// Create a stack of boards of the given shape, from the top layer
+    /// down. Every layer has cards of its own, so pairs never span layers,
+    /// and the `extra` cards, such as jokers and bombs, among them. Pairs
+    /// are dealt from the front of `kinds`, e.g. [`Board::symbol_cards`].
+    pub fn layered<R: Rng + ?Sized>(
+        shape: &Shape,
+        layers: usize,
+        kinds: &[Card],
+        extra: &[Card],
+        rng: &mut R,
+    ) -> Result<Vec<Board>> {
+        let pairs = shape.cells().saturating_sub(extra.len()) / 2;
+        let max = kinds.len();
+        if pairs * layers > max {
+            return Err(GameError::NotEnoughCardTypes {
+                max: (max / layers) as i32,
+            });
+        }
+        (0..layers)
+            .map(|z| Board::with_extra(shape, &kinds[z * pairs..], extra, rng))
+            .collect()
+    }
+
+    /// Create a new board of the given shape filled with playing cards, with
+    /// the `extra` cards, such as jokers and bombs, among them. The cards of
+    /// each pair have the same rank and suits of different colors, and
+    /// another deck is started every 26 pairs.
+    pub fn playing<R: Rng + ?Sized>(
+        shape: &Shape,
+        extra: &[Card],
+        rng: &mut R,
+    ) -> Result<Board> {
+        let ranks = Card::RANK_CHARS.len() as u32;
+        let pairs = shape.cells().saturating_sub(extra.len()) / 2;
+        let cards: Vec<Card> = (0..pairs as u32)
+            .flat_map(|k| {
+                let suits = match (k / ranks) % 2 {
+                    0 => [Suit::Spades, Suit::Hearts],
+                    _ => [Suit::Diamonds, Suit::Clubs],
+                };
+                suits.map(|suit| Card::playing(k % ranks, suit))
+            })
+            .chain(extra.iter().copied())
+            .collect();
+        Board::with_extra(shape, &[], &cards, rng)
+    }
+
+    /// Create a new board of the given shape, filled like [`Board::new`].
+    pub fn shaped<R: Rng + ?Sized>(
+        shape: &Shape,
+        rng: &mut R,
+    ) -> Result<Board> {
+        let kinds: Vec<Card> =
+            (0..Self::MAX_SIZE as u32 / 2).map(Card).collect();
+        Board::with_shape(shape, &kinds, rng)
+    }
+}
+
+impl<T> Default for Board<T> {
+    /// Create an empty board with 0 size.
+    fn default() -> Board<T> {
+        Board {
+            idx: Idx2d::new(0, 0),
+            cards: Vec::new(),
+        }
+    }
+}
+
+impl<T> Index<Vec2> for Board<T> {
+    type Output = T;
+
+    /// # Panics
+    /// Panics if there is a hole at `index`.
+    fn index(&self, index: Vec2) -> &Self::Output {
+        self.cards[self.idx.unchecked(index)]
+            .as_ref()
+            .expect("no card in a hole")
+    }
+}
+
+impl<T> IndexMut<Vec2> for Board<T> {
+    fn index_mut(&mut self, index: Vec2) -> &mut Self::Output {
+        self.cards[self.idx.unchecked(index)]
+            .as_mut()
+            .expect("no card in a hole")
+    }
+}
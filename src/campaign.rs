@@ -0,0 +1,155 @@
+use std::{
+    fs,
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::board::Vec2;
+use crate::config::Difficulty;
+use crate::error::{GameError, Result};
+use crate::grid::Layout;
+use crate::persistence::format::{self, FileFormat};
+use crate::stats::{data_dir, write_atomic};
+
+/// A board of the campaign along with the rules it is played by.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Level {
+    /// Size of the board.
+    pub size: Vec2,
+    pub difficulty: Difficulty,
+    pub layout: Layout,
+    /// Number of layers of cards stacked on each cell.
+    pub layers: usize,
+    /// Number of steps the cards of a pair are dealt apart.
+    pub min_pair_distance: u32,
+}
+
+impl Level {
+    const fn new(x: i32, y: i32, difficulty: Difficulty) -> Level {
+        Level {
+            size: Vec2 { x, y },
+            difficulty,
+            layout: Layout::Square,
+            layers: 1,
+            min_pair_distance: 0,
+        }
+    }
+
+    const fn hex(mut self) -> Level {
+        self.layout = Layout::Hex;
+        self
+    }
+
+    const fn layers(mut self, layers: usize) -> Level {
+        self.layers = layers;
+        self
+    }
+
+    const fn spread(mut self, min_pair_distance: u32) -> Level {
+        self.min_pair_distance = min_pair_distance;
+        self
+    }
+}
+
+/// The levels of the campaign, from the first to the last. Each one has to
+/// be cleared to unlock the next.
+pub const LEVELS: [Level; 12] = [
+    Level::new(2, 2, Difficulty::Easy),
+    Level::new(3, 2, Difficulty::Easy),
+    Level::new(4, 3, Difficulty::Normal),
+    Level::new(4, 4, Difficulty::Normal),
+    Level::new(5, 4, Difficulty::Normal).spread(2),
+    Level::new(6, 4, Difficulty::Normal).hex(),
+    Level::new(6, 5, Difficulty::Hard),
+    Level::new(6, 6, Difficulty::Normal).layers(2),
+    Level::new(8, 6, Difficulty::Hard).hex(),
+    Level::new(8, 8, Difficulty::Normal).spread(3),
+    Level::new(10, 8, Difficulty::Hard).layers(2),
+    Level::new(10, 10, Difficulty::Hard).spread(2),
+];
+
+/// The layout of the progress file.
+const FORMAT: FileFormat = FileFormat {
+    kind: "campaign",
+    migrations: &[format::enveloped],
+};
+
+/// What is stored in the progress file.
+#[derive(
+    Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize,
+)]
+struct Progress {
+    /// Number of levels cleared at least once.
+    cleared: usize,
+}
+
+/// How far the player got in the campaign, backed by a file.
+pub struct Campaign {
+    path: PathBuf,
+    progress: Progress,
+}
+
+impl Campaign {
+    /// Location of the progress file:
+    /// `$XDG_DATA_HOME/card-matching/campaign.json`, falling back to
+    /// `~/.local/share/card-matching/campaign.json`.
+    pub fn default_path() -> Option<PathBuf> {
+        Some(data_dir()?.join("campaign.json"))
+    }
+
+    /// Load the progress stored at `path`. A missing file starts the
+    /// campaign from the first level.
+    pub fn open(path: &Path) -> Result<Campaign> {
+        let progress = match fs::read(path) {
+            Ok(bytes) => {
+                FORMAT.decode(&bytes).map_err(|e| campaign_io(path, e))?
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => Progress::default(),
+            Err(e) => return Err(campaign_io(path, e)),
+        };
+        Ok(Campaign {
+            path: path.to_owned(),
+            progress,
+        })
+    }
+
+    /// The 0-based index of the furthest level unlocked.
+    pub fn furthest(&self) -> usize {
+        self.progress.cleared.min(LEVELS.len() - 1)
+    }
+
+    /// Check if the 0-based `level` can be played.
+    pub fn is_unlocked(&self, level: usize) -> bool {
+        level <= self.furthest()
+    }
+
+    /// Check if the 0-based `level` was cleared at least once.
+    pub fn is_cleared(&self, level: usize) -> bool {
+        level < self.progress.cleared
+    }
+
+    /// Record that the 0-based `level` was cleared and save the progress.
+    /// Returns `true` if this was the first time, unlocking the next level.
+    pub fn complete(&mut self, level: usize) -> Result<bool> {
+        if self.is_cleared(level) {
+            return Ok(false);
+        }
+        self.progress.cleared = level + 1;
+
+        let bytes = FORMAT.encode(&self.progress);
+        write_atomic(&self.path, &bytes)
+            .map_err(|e| campaign_io(&self.path, e))?;
+        Ok(true)
+    }
+}
+
+fn campaign_io(path: &Path, e: io::Error) -> GameError {
+    GameError::StatsIo {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+        source: Some(Arc::new(e)),
+    }
+}
@@ -0,0 +1,69 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::code::{base64_decode, base64_encode, BoardCode};
+use crate::error::{GameError, Result};
+
+/// The outcome of a game played on a shared board, exchanged with friends
+/// as a result code to compare how each of them did.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct ChallengeResult {
+    /// The [`BoardCode`] of the board that was played.
+    pub board: String,
+    /// Number of guesses, including hint penalties.
+    pub guesses: i32,
+    /// Time taken to clear the board, in milliseconds.
+    pub time_ms: u64,
+    /// Number of hints used.
+    pub hints: i32,
+    /// Points earned.
+    pub score: u64,
+}
+
+impl ChallengeResult {
+    /// The board that was played.
+    pub fn board_code(&self) -> Result<BoardCode> {
+        BoardCode::decode(&self.board)
+    }
+
+    pub fn time(&self) -> Duration {
+        Duration::from_millis(self.time_ms)
+    }
+
+    /// Encode the result as a base64 string, followed by a checksum of its
+    /// contents. The checksum catches results that were mistyped or edited
+    /// by hand, but can't stop anyone who knows how it is computed.
+    pub fn encode(&self) -> String {
+        let mut buf =
+            serde_json::to_vec(self).expect("result is always serializable");
+        let checksum = fnv1a(&buf);
+        buf.extend_from_slice(&checksum.to_be_bytes());
+        base64_encode(&buf)
+    }
+
+    /// Decode a string made by [`ChallengeResult::encode`].
+    pub fn decode(code: &str) -> Result<ChallengeResult> {
+        let buf =
+            base64_decode(code.trim()).ok_or(GameError::InvalidResultCode)?;
+        if buf.len() < 8 {
+            return Err(GameError::InvalidResultCode);
+        }
+        let (payload, checksum) = buf.split_at(buf.len() - 8);
+        if fnv1a(payload).to_be_bytes() != checksum {
+            return Err(GameError::InvalidResultCode);
+        }
+        let result: ChallengeResult = serde_json::from_slice(payload)
+            .map_err(|_| GameError::InvalidResultCode)?;
+        result.board_code()?;
+        Ok(result)
+    }
+}
+
+/// The 64-bit FNV-1a hash of `bytes`, which is stable across platforms and
+/// versions unlike the hashers in the standard library.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
@@ -0,0 +1,92 @@
+//! Simple charts drawn with text, for the statistics of past games.
+
+/// Eighths of a bar, from the thinnest to a full block.
+const EIGHTHS: [char; 8] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
+
+/// A horizontal bar chart, with a bar for each row.
+pub struct Bar {
+    pub label: String,
+    pub value: f64,
+    /// The value as shown after the bar, e.g. a formatted time.
+    pub text: String,
+}
+
+/// Draw a bar for each of `bars`, scaled so that the largest value fills
+/// `width` columns, with the labels on the left and the texts on the
+/// right.
+pub fn bars(bars: &[Bar], width: usize, unicode: bool) -> Vec<String> {
+    let max = bars.iter().map(|bar| bar.value).fold(0.0, f64::max);
+    let label_width = bars.iter().map(|bar| bar.label.len()).max();
+    let label_width = label_width.unwrap_or(0);
+    bars.iter()
+        .map(|bar| {
+            let length = if max > 0.0 {
+                bar.value / max * width as f64
+            } else {
+                0.0
+            };
+            let drawn = draw_bar(length, unicode);
+            format!(
+                "{:>lw$}  {:<bw$}  {}",
+                bar.label,
+                drawn,
+                bar.text,
+                lw = label_width,
+                bw = width,
+            )
+        })
+        .collect()
+}
+
+/// A bar `length` columns long, drawn with eighths of a column if the
+/// terminal can show them.
+fn draw_bar(length: f64, unicode: bool) -> String {
+    if !unicode {
+        return "#".repeat(length.round() as usize);
+    }
+    let eighths = (length * 8.0).round() as usize;
+    let mut bar = EIGHTHS[7].to_string().repeat(eighths / 8);
+    if let Some(part) = (eighths % 8).checked_sub(1) {
+        bar.push(EIGHTHS[part]);
+    }
+    bar
+}
+
+/// Plot `values` from left to right, a column each, on `height` rows. The
+/// highest and lowest values label the top and bottom rows, and an axis
+/// runs along the left and the bottom.
+pub fn line(values: &[f64], height: usize, unicode: bool) -> Vec<String> {
+    let (point, vertical, corner, horizontal) = match unicode {
+        true => ('•', '│', '└', '─'),
+        false => ('*', '|', '+', '-'),
+    };
+    let height = height.max(2);
+    let max = values.iter().copied().fold(f64::MIN, f64::max);
+    let min = values.iter().copied().fold(f64::MAX, f64::min);
+    let row_of = |value: f64| match max - min {
+        span if span > 0.0 => {
+            ((value - min) / span * (height - 1) as f64).round() as usize
+        }
+        _ => 0,
+    };
+
+    let (top, bottom) = (format!("{:.1}", max), format!("{:.1}", min));
+    let label_width = top.len().max(bottom.len());
+    let mut lines = Vec::new();
+    for row in (0..height).rev() {
+        let label = match row {
+            _ if values.is_empty() => "",
+            _ if row == height - 1 => &top,
+            0 => &bottom,
+            _ => "",
+        };
+        let mut line = format!("{:>1$} {vertical}", label, label_width);
+        for value in values {
+            line.push(if row_of(*value) == row { point } else { ' ' });
+        }
+        lines.push(line.trim_end().to_string());
+    }
+    let axis = horizontal.to_string().repeat(values.len());
+    lines.push(format!("{:>1$} {corner}{axis}", "", label_width));
+    lines
+}
@@ -0,0 +1,102 @@
+//! Short messages exchanged by the players of a networked game, shown in a
+//! panel beside the board. Messages are cleaned up and rate limited both
+//! where they are typed and where they arrive, as the other side may not
+//! play by the same rules.
+
+use std::{collections::VecDeque, time::Duration};
+
+use crate::clock::Instant;
+
+/// Longest message in characters. Longer ones are cut short.
+pub const MAX_LEN: usize = 60;
+
+/// Clean up a message before sending or showing it: control characters,
+/// which could move the cursor or clear the screen, are dropped, runs of
+/// whitespace are squeezed into a single space, and the message is cut to
+/// [`MAX_LEN`] characters. `None` if nothing is left of it.
+pub fn sanitize(text: &str) -> Option<String> {
+    let words = text
+        .split(|c: char| c.is_whitespace())
+        .map(|word| word.chars().filter(|c| !c.is_control()))
+        .map(String::from_iter)
+        .filter(|word| !word.is_empty());
+    let text: String = words
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(MAX_LEN)
+        .collect();
+    (!text.is_empty()).then_some(text)
+}
+
+/// Limits how many messages a player can send in quick succession.
+#[derive(Default)]
+pub struct RateLimit {
+    /// When the messages within the last [`RateLimit::WINDOW`] were sent.
+    sent: VecDeque<Instant>,
+}
+
+impl RateLimit {
+    /// Most messages allowed within [`RateLimit::WINDOW`].
+    const BURST: usize = 3;
+    const WINDOW: Duration = Duration::from_secs(5);
+
+    /// Check if another message may be sent at `now`, and count it if so.
+    pub fn allow(&mut self, now: Instant) -> bool {
+        while self.sent.front().is_some_and(|t| {
+            now.saturating_duration_since(*t) >= RateLimit::WINDOW
+        }) {
+            self.sent.pop_front();
+        }
+        if self.sent.len() >= RateLimit::BURST {
+            return false;
+        }
+        self.sent.push_back(now);
+        true
+    }
+}
+
+/// A message said by the player in `seat`. Seat 0 is the host, or the one
+/// taking the first turn in a room of a [lobby](`crate::lobby`).
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ChatLine {
+    pub seat: usize,
+    pub text: String,
+}
+
+/// The last messages of a game, with a rate limit for each player.
+#[derive(Default)]
+pub struct Chat {
+    lines: VecDeque<ChatLine>,
+    limits: [RateLimit; 2],
+}
+
+impl Chat {
+    /// Number of messages kept.
+    const KEPT: usize = 20;
+
+    /// Note a message said by the player in `seat` at `now`. Returns the
+    /// message as kept, or `None` if nothing was left of it or the player
+    /// is saying too much.
+    pub fn add(
+        &mut self,
+        seat: usize,
+        text: &str,
+        now: Instant,
+    ) -> Option<&ChatLine> {
+        let text = sanitize(text)?;
+        if !self.limits.get_mut(seat)?.allow(now) {
+            return None;
+        }
+        if self.lines.len() == Chat::KEPT {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(ChatLine { seat, text });
+        self.lines.back()
+    }
+
+    /// The messages kept, the latest last.
+    pub fn lines(&self) -> impl DoubleEndedIterator<Item = &ChatLine> {
+        self.lines.iter()
+    }
+}
@@ -0,0 +1,481 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
+use rs_card_matching::{
+    board::Vec2,
+    challenge::ChallengeResult,
+    code::BoardCode,
+    config::{self, BombEffect, CardSet, ColorMode, Difficulty, Pacing},
+    export::Format,
+    grid::Layout,
+    theme::Theme,
+};
+
+/// A card matching game for the terminal.
+#[derive(Parser, Debug)]
+#[command(version, about)]
+pub struct Cli {
+    /// Read settings from this file instead of
+    /// `~/.config/card-matching/config.toml`
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
+    /// Play as this profile, with its own statistics, saved games and
+    /// settings. Without it, the game asks which profile to play as if
+    /// there are any
+    #[arg(long, global = true, value_name = "NAME")]
+    pub profile: Option<String>,
+    /// Turn off sound effects
+    #[arg(long, global = true)]
+    pub mute: bool,
+    /// When to use colors: `auto`, `always` or `never`. Overrides the
+    /// configuration file. `auto` uses them if the output is a terminal and
+    /// `NO_COLOR` isn't set
+    #[arg(long, global = true, value_name = "WHEN", value_parser = parse_color_mode)]
+    pub color: Option<ColorMode>,
+    /// Same as `--color never`
+    #[arg(long, global = true, conflicts_with = "color")]
+    pub no_color: bool,
+    /// Color theme: `default`, `solarized`, `high-contrast` or
+    /// `monochrome`. Overrides the configuration file
+    #[arg(long, global = true, value_parser = parse_theme)]
+    pub theme: Option<Theme>,
+    /// Use the colors with the most contrast, whatever the theme
+    #[arg(long, global = true)]
+    pub high_contrast: bool,
+    /// Turn off animations and flashing
+    #[arg(long, global = true)]
+    pub reduced_motion: bool,
+    /// Draw the board densely, with no lines or blank rows between the
+    /// cards, even if it would fit in the terminal otherwise
+    #[arg(long, global = true)]
+    pub compact: bool,
+    /// Show the number of each face-down cell in it, to be typed instead of
+    /// its coordinates
+    #[arg(long, global = true)]
+    pub cell_numbers: bool,
+    /// Show how the guesses so far compare to what a player with perfect
+    /// memory is expected to take, given the cards seen
+    #[arg(long, global = true)]
+    pub luck_meter: bool,
+    /// How to move on from the feedback about a guess: `enter` waits for
+    /// <Enter>, `hold` for any key, `fast` moves on by itself after a
+    /// moment. Overrides the configuration file
+    #[arg(long, global = true, value_parser = parse_pacing)]
+    pub pacing: Option<Pacing>,
+    /// Draw the cards as text even if the terminal can show images
+    #[arg(long, global = true)]
+    pub no_images: bool,
+    /// Describe the board in words and never clear the screen, for use with
+    /// screen readers
+    #[arg(long, global = true)]
+    pub accessible: bool,
+    /// Language of the messages, e.g. `en` or `hu` (default: from `LANG`)
+    #[arg(long, global = true, value_name = "LANG")]
+    pub lang: Option<String>,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+// Parsed once at startup, so the size of `PlayArgs` doesn't matter
+#[allow(clippy::large_enum_variant)]
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Play an interactive game (the default)
+    Play(PlayArgs),
+    /// Replay a game recorded with `play --record`
+    Replay {
+        /// The recording to play back
+        file: PathBuf,
+    },
+    /// Turn a game recorded with `play --record` into an asciinema cast
+    /// file, to share it or convert it into a GIF with a tool such as
+    /// `agg`
+    Export {
+        /// The recording to turn into a cast
+        file: PathBuf,
+        /// The cast file to write
+        #[arg(long, value_name = "PATH")]
+        out: PathBuf,
+    },
+    /// Play today's challenge board, the same for everyone
+    Daily,
+    /// Learn how to play on a small board, one step at a time
+    Tutorial,
+    /// Continue the game saved when quitting
+    Resume,
+    /// Show statistics about past games
+    Stats {
+        #[command(subcommand)]
+        action: Option<StatsCommand>,
+        /// List the last this many games, from the history of games kept
+        /// when built with the `history` feature
+        #[arg(long, value_name = "N")]
+        recent: Option<usize>,
+        /// Only show the history of games of this mode, e.g. `classic`,
+        /// `daily`, `campaign`, `tournament` or `challenge`
+        #[arg(long, global = true)]
+        mode: Option<String>,
+    },
+    /// Let the computer solve a board and report how many guesses it took
+    Solve(SolveArgs),
+    /// Play the levels of the campaign, unlocking each by clearing the one
+    /// before it
+    Campaign,
+    /// Play a series of boards of increasing size for a combined score
+    Tournament {
+        /// Seed used to shuffle the boards, for reproducible tournaments
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// Play the board of a friend's result code, then compare results
+    Challenge {
+        /// The result code shown when the friend cleared the board
+        #[arg(value_parser = parse_result_code)]
+        result: ChallengeResult,
+    },
+    /// Host a web page to play in a browser, talking to the game with the
+    /// same JSON commands as `play --json`, or a lobby where players meet
+    /// to play networked games
+    Serve {
+        /// Port of the loopback interface to serve the page on
+        #[arg(
+            long,
+            value_name = "PORT",
+            required_unless_present = "lobby",
+            conflicts_with = "lobby"
+        )]
+        http: Option<u16>,
+        /// Port to listen on for players connecting with `lobby`. The lobby
+        /// deals the board of each room and decides whose turn it is
+        #[arg(long, value_name = "PORT")]
+        lobby: Option<u16>,
+        /// Pass play to the other player of a room of the lobby if a turn
+        /// takes longer than this many seconds
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            value_parser = clap::value_parser!(u64).range(1..=config::MAX_SECONDS),
+            requires = "lobby"
+        )]
+        turn_time: Option<u64>,
+    },
+    /// Meet other players in a lobby started with `serve --lobby`
+    Lobby {
+        /// Address of the lobby as `<host>:<port>`
+        addr: String,
+        #[command(subcommand)]
+        action: LobbyCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum LobbyCommand {
+    /// List the rooms, both those waiting for a second player and those
+    /// that can only be watched
+    List,
+    /// Open a room and wait in it for a second player
+    Create {
+        /// Size of the board as `<columns>x<rows>`, e.g. `6x4`
+        #[arg(long, value_parser = parse_size)]
+        size: Vec2,
+    },
+    /// Take the second seat of a listed room
+    Join {
+        /// The number of the room
+        room: u32,
+    },
+    /// Play against anyone waiting for a board of the same size, or wait
+    /// for someone to come along
+    Match {
+        /// Size of the board as `<columns>x<rows>`, e.g. `6x4`
+        #[arg(long, value_parser = parse_size)]
+        size: Vec2,
+    },
+    /// Watch the game of a room without taking part in it
+    Watch {
+        /// The number of the room
+        room: u32,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum StatsCommand {
+    /// Write the statistics and the history of games to a file, to be
+    /// analyzed in other tools
+    Export {
+        /// `csv` for a row per game, or `json` for the statistics followed
+        /// by the games
+        #[arg(long, value_parser = parse_export_format)]
+        format: Format,
+        /// The file to write
+        #[arg(long, value_name = "PATH")]
+        out: PathBuf,
+    },
+}
+
+#[derive(Args, Debug, Default)]
+pub struct PlayArgs {
+    /// Size of the board as `<columns>x<rows>`, e.g. `6x4`. Skips the size
+    /// prompt. Overrides the configuration file.
+    #[arg(long, value_parser = parse_size)]
+    pub size: Option<Vec2>,
+    /// Shape of the board: `heart`, `diamond`, `donut` or a file with a
+    /// mask where `#` is a card and `.` is a hole. Skips the size prompt
+    #[arg(long, value_name = "NAME|FILE", conflicts_with = "size")]
+    pub shape: Option<PathBuf>,
+    /// Play the board of a code shown by another game, with its rules
+    #[arg(
+        long,
+        value_name = "CODE",
+        value_parser = parse_board_code,
+        conflicts_with_all = [
+            "size", "shape", "seed", "difficulty", "layout", "layers",
+            "card_set", "ascii", "jokers", "bombs", "min_distance", "host", "join"
+        ]
+    )]
+    pub board_code: Option<BoardCode>,
+    /// Seed used to shuffle the board, for reproducible games
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Rule preset: `easy` shows the whole board before the first guess,
+    /// `hard` reshuffles the hidden cards every few mistakes. Overrides the
+    /// configuration file.
+    #[arg(long, value_parser = parse_difficulty)]
+    pub difficulty: Option<Difficulty>,
+    /// Board layout: `square`, or `hex` for hexagonal cells in offset rows.
+    /// Overrides the configuration file.
+    #[arg(long, value_parser = parse_layout)]
+    pub layout: Option<Layout>,
+    /// Number of layers of cards stacked on each cell. Matching a pair
+    /// exposes the cards under it
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u8).range(1..),
+        conflicts_with_all = ["host", "join"]
+    )]
+    pub layers: u8,
+    /// The cards to deal: `symbols`, `playing` for playing cards that
+    /// match by rank, whatever their suit, `ascii` for letters and digits,
+    /// or `emoji`. Overrides the configuration file
+    #[arg(long, value_parser = parse_card_set, conflicts_with = "layers")]
+    pub card_set: Option<CardSet>,
+    /// Deal letters and digits instead of symbols, for terminals and fonts
+    /// that can't draw them. Same as `--card-set ascii`
+    #[arg(long, conflicts_with = "card_set")]
+    pub ascii: bool,
+    /// Deal this many jokers, which match any other card. With an odd
+    /// number of jokers, boards with an odd number of cells can be played.
+    /// Overrides the configuration file
+    #[arg(
+        long,
+        value_name = "N",
+        conflicts_with_all = ["layers", "host", "join"]
+    )]
+    pub jokers: Option<u32>,
+    /// Hide this many bombs, which end the guess when revealed. Overrides
+    /// the configuration file
+    #[arg(
+        long,
+        value_name = "N",
+        conflicts_with_all = ["layers", "host", "join"]
+    )]
+    pub bombs: Option<u32>,
+    /// What a bomb does: `penalty` costs points and a life, `shuffle`
+    /// shuffles the hidden cards in a random area. Overrides the
+    /// configuration file
+    #[arg(long, value_parser = parse_bomb_effect)]
+    pub bomb_effect: Option<BombEffect>,
+    /// Show the whole board for this many seconds before the first guess.
+    /// Overrides the configuration file
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        value_parser = clap::value_parser!(u64).range(..=config::MAX_SECONDS)
+    )]
+    pub preview: Option<u64>,
+    /// Deal the cards of each pair at least this many steps apart, e.g. 2
+    /// to never put them next to each other. Overrides the configuration
+    /// file
+    #[arg(long, value_name = "STEPS")]
+    pub min_distance: Option<u32>,
+    /// Silently reshuffle the hidden cards after every this many guesses.
+    /// Overrides the configuration file
+    #[arg(
+        long,
+        value_name = "TURNS",
+        value_parser = clap::value_parser!(u32).range(1..),
+        conflicts_with_all = ["host", "join"]
+    )]
+    pub shuffle_every: Option<u32>,
+    /// Count a guess as incorrect if it takes longer than this many
+    /// seconds. Overrides the configuration file
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        value_parser = clap::value_parser!(u64).range(1..=config::MAX_SECONDS),
+        conflicts_with_all = ["host", "join"]
+    )]
+    pub time_limit: Option<u64>,
+    /// Turn matched cards face down again after this many more guesses, so
+    /// the board has to be cleared before they fade. Overrides the
+    /// configuration file
+    #[arg(
+        long,
+        value_name = "TURNS",
+        value_parser = clap::value_parser!(u32).range(1..),
+        conflicts_with_all = ["host", "join"]
+    )]
+    pub decay: Option<u32>,
+    /// Lose the game after this many incorrect guesses. Overrides the
+    /// configuration file
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..),
+        conflicts_with_all = ["host", "join"]
+    )]
+    pub lives: Option<u32>,
+    /// Host a two-player game, waiting for the opponent on this port
+    #[arg(long, value_name = "PORT", conflicts_with = "join")]
+    pub host: Option<u16>,
+    /// Join a two-player game hosted at this address, e.g. `10.0.0.2:7777`
+    #[arg(
+        long,
+        value_name = "ADDR",
+        conflicts_with_all = ["size", "shape", "seed"]
+    )]
+    pub join: Option<String>,
+    /// Print the game state as JSON lines and read JSON commands from
+    /// stdin, for bots and external interfaces
+    #[arg(long, conflicts_with_all = ["host", "join"])]
+    pub json: bool,
+    /// Watch a perfect-memory player solve the board. Requires a board
+    /// size from `--size` or the configuration file
+    #[arg(long, conflicts_with_all = ["host", "join", "json"])]
+    pub autoplay: bool,
+    /// Play the inputs in this file, one per line, and exit with status 0
+    /// if they clear the board or 3 if they don't. Lines starting with `#`
+    /// are skipped
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["host", "join", "json", "autoplay"]
+    )]
+    pub script: Option<PathBuf>,
+    /// Delay between moves in autoplay and script mode, in milliseconds.
+    /// Defaults to 500 for autoplay and none for scripts
+    #[arg(long, value_name = "MS")]
+    pub delay: Option<u64>,
+    /// Record the moves made on the board to this file, to watch them
+    /// again with `replay` or share them with `export`
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["host", "join", "json", "autoplay", "script"]
+    )]
+    pub record: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct SolveArgs {
+    /// Size of the board as `<columns>x<rows>`, e.g. `6x4`. Defaults to the
+    /// size in the configuration file
+    #[arg(long, value_parser = parse_size)]
+    pub size: Option<Vec2>,
+    /// Seed used to shuffle the board
+    #[arg(long)]
+    pub seed: Option<u64>,
+}
+
+/// Parse a board size in the `<columns>x<rows>` format.
+fn parse_size(s: &str) -> Result<Vec2, String> {
+    config::parse_size(s).map_err(|e| e.as_string())
+}
+
+/// Parse a result code shown by the game.
+fn parse_result_code(s: &str) -> Result<ChallengeResult, String> {
+    ChallengeResult::decode(s).map_err(|e| e.as_string())
+}
+
+/// Parse a board code shown by the game.
+fn parse_board_code(s: &str) -> Result<BoardCode, String> {
+    BoardCode::decode(s).map_err(|e| e.as_string())
+}
+
+/// Parse the name of a color theme.
+fn parse_theme(s: &str) -> Result<Theme, String> {
+    Theme::named(s).map_err(|e| e.as_string())
+}
+
+/// Parse when to use colors.
+fn parse_color_mode(s: &str) -> Result<ColorMode, String> {
+    match s.to_lowercase().as_str() {
+        "auto" => Ok(ColorMode::Auto),
+        "always" => Ok(ColorMode::Always),
+        "never" => Ok(ColorMode::Never),
+        _ => Err(format!("expected auto, always or never, got `{}`", s)),
+    }
+}
+
+/// Parse the name of a board layout.
+fn parse_layout(s: &str) -> Result<Layout, String> {
+    match s.to_lowercase().as_str() {
+        "square" => Ok(Layout::Square),
+        "hex" => Ok(Layout::Hex),
+        _ => Err(format!("expected square or hex, got `{}`", s)),
+    }
+}
+
+/// Parse the name of a card set.
+fn parse_card_set(s: &str) -> Result<CardSet, String> {
+    match s.to_lowercase().as_str() {
+        "symbols" => Ok(CardSet::Symbols),
+        "playing" => Ok(CardSet::Playing),
+        "ascii" => Ok(CardSet::Ascii),
+        "emoji" => Ok(CardSet::Emoji),
+        _ => Err(format!(
+            "expected symbols, playing, ascii or emoji, got `{}`",
+            s
+        )),
+    }
+}
+
+/// Parse the name of a bomb effect.
+fn parse_bomb_effect(s: &str) -> Result<BombEffect, String> {
+    match s.to_lowercase().as_str() {
+        "penalty" => Ok(BombEffect::Penalty),
+        "shuffle" => Ok(BombEffect::Shuffle),
+        _ => Err(format!("expected penalty or shuffle, got `{}`", s)),
+    }
+}
+
+/// Parse the name of a pacing.
+fn parse_pacing(s: &str) -> Result<Pacing, String> {
+    match s.to_lowercase().as_str() {
+        "enter" => Ok(Pacing::Enter),
+        "hold" => Ok(Pacing::Hold),
+        "fast" => Ok(Pacing::Fast),
+        _ => Err(format!("expected enter, hold or fast, got `{}`", s)),
+    }
+}
+
+/// Parse the name of an export format.
+fn parse_export_format(s: &str) -> Result<Format, String> {
+    match s.to_lowercase().as_str() {
+        "csv" => Ok(Format::Csv),
+        "json" => Ok(Format::Json),
+        _ => Err(format!("expected csv or json, got `{}`", s)),
+    }
+}
+
+/// Parse the name of a difficulty preset.
+fn parse_difficulty(s: &str) -> Result<Difficulty, String> {
+    match s.to_lowercase().as_str() {
+        "easy" => Ok(Difficulty::Easy),
+        "normal" => Ok(Difficulty::Normal),
+        "hard" => Ok(Difficulty::Hard),
+        _ => Err(format!("expected easy, normal or hard, got `{}`", s)),
+    }
+}
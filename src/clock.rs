@@ -0,0 +1,9 @@
+//! The clocks the game reads. The standard library has none when built for
+//! WebAssembly to run in a browser, where they come from the browser
+//! instead.
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+#[cfg(target_arch = "wasm32")]
+pub use web_time::{Instant, SystemTime, UNIX_EPOCH};
@@ -0,0 +1,219 @@
+use crate::board::{Card, Vec2};
+use crate::config::Difficulty;
+use crate::error::{GameError, Result};
+use crate::grid::Layout;
+
+/// Everything needed to deal the same board again: the cards of every
+/// layer, the rules, and the seed for whatever else is random, e.g. the
+/// reshuffles on [`Difficulty::Hard`]. Shared as a short URL-safe base64
+/// string.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BoardCode {
+    /// Seed of the game's random number generator, if it has one.
+    pub seed: Option<u64>,
+    pub difficulty: Difficulty,
+    pub layout: Layout,
+    /// Number of guesses added to the count for every hint.
+    pub hint_penalty: i32,
+    /// Number of steps the cards of a pair were dealt apart. Only needed to
+    /// draw the same random numbers as the original game.
+    pub min_pair_distance: i32,
+    /// Number of columns and rows.
+    pub size: Vec2,
+    /// The card ids of every layer from the top down, in row major order.
+    /// Holes are `None`.
+    pub layers: Vec<Vec<Option<u32>>>,
+}
+
+impl BoardCode {
+    /// Format version, bumped whenever the encoding changes.
+    const VERSION: u8 = 1;
+
+    /// Encode the board as a base64 string.
+    pub fn encode(&self) -> String {
+        let mut buf = vec![BoardCode::VERSION];
+        buf.push(match self.difficulty {
+            Difficulty::Easy => 0,
+            Difficulty::Normal => 1,
+            Difficulty::Hard => 2,
+        });
+        buf.push(match self.layout {
+            Layout::Square => 0,
+            Layout::Hex => 1,
+        });
+        match self.seed {
+            Some(seed) => {
+                buf.push(1);
+                buf.extend_from_slice(&seed.to_be_bytes());
+            }
+            None => buf.push(0),
+        }
+        write_varint(&mut buf, self.hint_penalty.max(0) as u64);
+        write_varint(&mut buf, self.min_pair_distance.max(0) as u64);
+        write_varint(&mut buf, self.size.x as u64);
+        write_varint(&mut buf, self.size.y as u64);
+        write_varint(&mut buf, self.layers.len() as u64);
+        // Ids are shifted up by one to make room for holes
+        for card in self.layers.iter().flatten() {
+            write_varint(&mut buf, card.map_or(0, |id| id as u64 + 1));
+        }
+        base64_encode(&buf)
+    }
+
+    /// Decode a string made by [`BoardCode::encode`].
+    pub fn decode(code: &str) -> Result<BoardCode> {
+        let buf =
+            base64_decode(code.trim()).ok_or(GameError::InvalidBoardCode)?;
+        let mut reader = Reader { buf: &buf };
+
+        if reader.byte()? != BoardCode::VERSION {
+            return Err(GameError::InvalidBoardCode);
+        }
+        let difficulty = match reader.byte()? {
+            0 => Difficulty::Easy,
+            1 => Difficulty::Normal,
+            2 => Difficulty::Hard,
+            _ => return Err(GameError::InvalidBoardCode),
+        };
+        let layout = match reader.byte()? {
+            0 => Layout::Square,
+            1 => Layout::Hex,
+            _ => return Err(GameError::InvalidBoardCode),
+        };
+        let seed = match reader.byte()? {
+            0 => None,
+            1 => Some(u64::from_be_bytes(reader.array()?)),
+            _ => return Err(GameError::InvalidBoardCode),
+        };
+        let hint_penalty = reader.number()?;
+        let min_pair_distance = reader.number()?;
+        let size = Vec2 {
+            x: reader.number()?,
+            y: reader.number()?,
+        };
+        let layer_count: i32 = reader.number()?;
+        let cells = size.x as usize * size.y as usize;
+        if layer_count == 0 || cells == 0 || cells > buf.len() {
+            return Err(GameError::InvalidBoardCode);
+        }
+
+        let mut layers = Vec::new();
+        for _ in 0..layer_count {
+            let layer = (0..cells)
+                .map(|_| match reader.varint()? {
+                    0 => Ok(None),
+                    id => u32::try_from(id - 1)
+                        .ok()
+                        .filter(|id| Card(*id).is_valid())
+                        .map(Some)
+                        .ok_or(GameError::InvalidBoardCode),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            layers.push(layer);
+        }
+        if !reader.buf.is_empty() {
+            return Err(GameError::InvalidBoardCode);
+        }
+
+        Ok(BoardCode {
+            seed,
+            difficulty,
+            layout,
+            hint_penalty,
+            min_pair_distance,
+            size,
+            layers,
+        })
+    }
+}
+
+/// Reads the fields of an encoded board code from the front of a buffer.
+struct Reader<'a> {
+    buf: &'a [u8],
+}
+
+impl Reader<'_> {
+    fn byte(&mut self) -> Result<u8> {
+        let (first, rest) =
+            self.buf.split_first().ok_or(GameError::InvalidBoardCode)?;
+        self.buf = rest;
+        Ok(*first)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        if self.buf.len() < N {
+            return Err(GameError::InvalidBoardCode);
+        }
+        let (bytes, rest) = self.buf.split_at(N);
+        self.buf = rest;
+        Ok(bytes.try_into().expect("split at the array length"))
+    }
+
+    /// Read a LEB128 varint: 7 bits per byte, lowest first, with the top
+    /// bit set on every byte but the last.
+    fn varint(&mut self) -> Result<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(GameError::InvalidBoardCode)
+    }
+
+    /// Read a varint that has to fit in an `i32`.
+    fn number(&mut self) -> Result<i32> {
+        i32::try_from(self.varint()?).map_err(|_| GameError::InvalidBoardCode)
+    }
+}
+
+fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+/// The URL-safe base64 alphabet, so that codes can be pasted into links.
+const BASE64: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Encode bytes as base64 without padding.
+pub(crate) fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for chunk in bytes.chunks(3) {
+        let mut group = [0u8; 3];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let bits = u32::from_be_bytes([0, group[0], group[1], group[2]]);
+        // Every byte takes up a character and 2 more bits
+        for i in 0..=chunk.len() {
+            let index = (bits >> (18 - 6 * i)) & 0x3f;
+            out.push(BASE64[index as usize] as char);
+        }
+    }
+    out
+}
+
+/// Decode base64 without padding, or `None` if `s` isn't valid base64.
+pub(crate) fn base64_decode(s: &str) -> Option<Vec<u8>> {
+    let values = s
+        .bytes()
+        .map(|c| BASE64.iter().position(|b| *b == c).map(|i| i as u32))
+        .collect::<Option<Vec<_>>>()?;
+    let mut out = Vec::new();
+    for chunk in values.chunks(4) {
+        if chunk.len() == 1 {
+            return None;
+        }
+        let bits = chunk
+            .iter()
+            .enumerate()
+            .fold(0, |bits, (i, v)| bits | v << (18 - 6 * i));
+        let bytes = bits.to_be_bytes();
+        out.extend_from_slice(&bytes[1..chunk.len()]);
+    }
+    Some(out)
+}
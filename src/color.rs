@@ -0,0 +1,136 @@
+use std::env;
+
+use crate::board::{Board, Card};
+
+/// How many colors the terminal can show.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColorDepth {
+    /// The 16 basic colors, which every color terminal has.
+    Basic,
+    /// The 256 colors of xterm.
+    Extended,
+    /// Any 24-bit color.
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// Guess the depth from `COLORTERM` and `TERM`, the way most terminals
+    /// advertise it.
+    pub fn detect() -> ColorDepth {
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        let term = env::var("TERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Extended
+        } else {
+            ColorDepth::Basic
+        }
+    }
+}
+
+/// A color given by its red, green and blue components.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const RED: Rgb = Rgb(220, 50, 47);
+    pub const GOLD: Rgb = Rgb(255, 200, 0);
+
+    /// Wrap `text` in the escape codes that draw it in this color, or the
+    /// closest one the terminal has, and switch back to the default color
+    /// after it.
+    pub fn paint(self, text: &str, depth: ColorDepth) -> String {
+        let esc = 27 as char;
+        format!("{esc}[{}m{}{esc}[39m", self.sgr(depth), text)
+    }
+
+    /// The parameters of the escape code that selects this color for the
+    /// text.
+    fn sgr(self, depth: ColorDepth) -> String {
+        let Rgb(r, g, b) = self;
+        match depth {
+            ColorDepth::TrueColor => format!("38;2;{};{};{}", r, g, b),
+            ColorDepth::Extended => {
+                // The 6x6x6 color cube that starts at 16
+                let level = |c: u8| (c as u16 * 5 + 127) / 255;
+                let index = 16 + 36 * level(r) + 6 * level(g) + level(b);
+                format!("38;5;{}", index)
+            }
+            ColorDepth::Basic => {
+                let nearest = (0..BASIC.len())
+                    .min_by_key(|i| BASIC[*i].distance(self))
+                    .expect("there are basic colors");
+                match nearest {
+                    0..=7 => (30 + nearest).to_string(),
+                    _ => (90 + nearest - 8).to_string(),
+                }
+            }
+        }
+    }
+
+    /// The squared distance between two colors.
+    fn distance(self, other: Rgb) -> u32 {
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+        d(self.0, other.0) + d(self.1, other.1) + d(self.2, other.2)
+    }
+}
+
+/// The 16 basic colors in the order of their escape codes, as xterm draws
+/// them by default. Other terminals use slightly different shades.
+const BASIC: [Rgb; 16] = [
+    Rgb(0, 0, 0),
+    Rgb(205, 0, 0),
+    Rgb(0, 205, 0),
+    Rgb(205, 205, 0),
+    Rgb(0, 0, 238),
+    Rgb(205, 0, 205),
+    Rgb(0, 205, 205),
+    Rgb(229, 229, 229),
+    Rgb(127, 127, 127),
+    Rgb(255, 0, 0),
+    Rgb(0, 255, 0),
+    Rgb(255, 255, 0),
+    Rgb(92, 92, 255),
+    Rgb(255, 0, 255),
+    Rgb(0, 255, 255),
+    Rgb(255, 255, 255),
+];
+
+/// Colors for the card symbols, as far apart from each other as possible.
+pub const PALETTE: [Rgb; 11] = [
+    Rgb(230, 25, 75),
+    Rgb(60, 180, 75),
+    Rgb(255, 225, 25),
+    Rgb(0, 130, 200),
+    Rgb(245, 130, 48),
+    Rgb(145, 30, 180),
+    Rgb(70, 240, 240),
+    Rgb(240, 50, 230),
+    Rgb(210, 245, 60),
+    Rgb(250, 190, 212),
+    Rgb(170, 110, 40),
+];
+
+/// The color a card is drawn in, if any. Every symbol keeps its color from
+/// game to game. The color is picked by the position of the symbol in
+/// [`Board::CARD_CHARS`], so symbols next to each other, which tend to look
+/// alike, never share one. Playing cards are red or drawn in the default
+/// color by suit.
+pub fn card_color(card: Card) -> Option<Rgb> {
+    if card.is_joker() {
+        return Some(Rgb::GOLD);
+    }
+    if card.is_bomb() {
+        return Some(Rgb::RED);
+    }
+    if let Some(suit) = card.suit() {
+        return suit.is_red().then_some(Rgb::RED);
+    }
+    let symbol = card.symbol();
+    let index = Board::CARD_CHARS
+        .iter()
+        .chain(&Board::ASCII_CHARS)
+        .position(|c| *c == symbol)?;
+    Some(PALETTE[index % PALETTE.len()])
+}
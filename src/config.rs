@@ -0,0 +1,318 @@
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::board::Vec2;
+use crate::color::ColorDepth;
+use crate::error::{GameError, Result};
+use crate::grid::Layout;
+use crate::input;
+use crate::keymap::Keymap;
+use crate::profile;
+use crate::scoring::ScoringRules;
+use crate::stats;
+use crate::theme::Theme;
+
+/// Most seconds any timed setting can be set to, a day.
+pub const MAX_SECONDS: u64 = 24 * 60 * 60;
+
+/// The set of symbols drawn on the cards.
+#[derive(
+    Clone, Copy, PartialEq, Eq, Debug, Default, Deserialize, Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum CardSet {
+    /// Astrological and miscellaneous Unicode symbols.
+    #[default]
+    Symbols,
+    /// Playing cards with a rank and a suit, matched by rank.
+    Playing,
+    /// Letters and digits, for terminals and fonts that can't draw the
+    /// symbols.
+    Ascii,
+    /// Emoji of fruits, food and animals.
+    Emoji,
+}
+
+impl CardSet {
+    /// Every card set, in the order they are cycled through.
+    pub const ALL: [CardSet; 4] = [
+        CardSet::Symbols,
+        CardSet::Playing,
+        CardSet::Ascii,
+        CardSet::Emoji,
+    ];
+
+    /// The card set after this one in [`CardSet::ALL`], wrapping around.
+    pub fn next(self) -> CardSet {
+        let index = CardSet::ALL.iter().position(|set| *set == self);
+        CardSet::ALL[index.map_or(0, |i| (i + 1) % CardSet::ALL.len())]
+    }
+}
+
+/// When to use colors in the terminal output.
+#[derive(
+    Clone, Copy, PartialEq, Eq, Debug, Default, Deserialize, Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    /// Use colors if the output looks like a terminal and `NO_COLOR` isn't
+    /// set.
+    #[default]
+    Auto,
+    /// Always use colors.
+    Always,
+    /// Never use colors.
+    Never,
+}
+
+impl ColorMode {
+    /// The colors to draw with, given the ones
+    /// [detected](`crate::terminal::Capabilities::detect`) for the terminal.
+    pub fn colors(self, detected: Option<ColorDepth>) -> Option<ColorDepth> {
+        match self {
+            ColorMode::Auto => detected,
+            ColorMode::Always => Some(ColorDepth::detect()),
+            ColorMode::Never => None,
+        }
+    }
+}
+
+/// What happens when a bomb is revealed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BombEffect {
+    /// Lose points and, if lives are limited, a life.
+    #[default]
+    Penalty,
+    /// Shuffle the hidden cards in a random area of the board.
+    Shuffle,
+}
+
+/// How the game moves on from the feedback about a guess.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Pacing {
+    /// Wait for <Enter>.
+    #[default]
+    Enter,
+    /// Keep the feedback on screen until any key is pressed.
+    Hold,
+    /// Move on by itself after a moment, or as soon as a key is pressed,
+    /// which starts the next guess.
+    Fast,
+}
+
+/// Preset rule sets.
+#[derive(
+    Clone, Copy, PartialEq, Eq, Debug, Default, Deserialize, Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    /// Every preset, from the easiest.
+    pub const ALL: [Difficulty; 3] =
+        [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    /// The preset after this one in [`Difficulty::ALL`], wrapping around.
+    pub fn next(self) -> Difficulty {
+        let index = Difficulty::ALL.iter().position(|d| *d == self);
+        Difficulty::ALL[index.map_or(0, |i| (i + 1) % Difficulty::ALL.len())]
+    }
+}
+
+/// User settings loaded from the configuration file. Every field is
+/// optional in the file and falls back to its default.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Size of the board as `<columns>x<rows>`. If unset, the game asks
+    /// for it.
+    pub size: Option<String>,
+    /// The symbols drawn on the cards.
+    pub card_set: CardSet,
+    /// When to use colors.
+    pub colors: ColorMode,
+    /// Turn off sound effects.
+    pub mute: bool,
+    /// The name of the color theme.
+    pub theme: Option<String>,
+    /// Use the colors with the most contrast, whatever the theme.
+    pub high_contrast: bool,
+    /// Turn off animations and flashing.
+    pub reduced_motion: bool,
+    /// Show the number of each face-down cell in it.
+    pub cell_numbers: bool,
+    /// Show how the guesses so far compare to the guesses expected.
+    pub luck_meter: bool,
+    /// How the game moves on from the feedback about a guess.
+    pub pacing: Pacing,
+    /// The rule preset.
+    pub difficulty: Difficulty,
+    /// How the cells of the board are arranged.
+    pub layout: Layout,
+    /// Overrides for the keys bound to each action, e.g. `hint = "h"`. See
+    /// [`Action`](crate::keymap::Action) for the names of the actions.
+    pub key_bindings: BTreeMap<String, String>,
+    /// Number of guesses added to the count for every hint.
+    pub hint_penalty: Option<u32>,
+    /// Seconds the whole board is shown before the first guess, if at all.
+    pub preview: Option<u64>,
+    /// Number of steps the cards of a pair are dealt apart, e.g. 2 to never
+    /// put them next to each other.
+    pub min_pair_distance: Option<u32>,
+    /// Silently reshuffle the hidden cards after every this many guesses.
+    pub shuffle_every: Option<u32>,
+    /// Seconds each guess has to be made in, if limited.
+    pub time_limit: Option<u64>,
+    /// Turn matched cards face down again after this many more guesses.
+    pub decay: Option<u32>,
+    /// Number of incorrect guesses allowed on a board, if limited.
+    pub lives: Option<u32>,
+    /// Number of jokers, cards that match any other, dealt on the board.
+    pub jokers: Option<u32>,
+    /// Number of bombs hidden on the board.
+    pub bombs: Option<u32>,
+    /// What happens when a bomb is revealed.
+    pub bomb_effect: BombEffect,
+    /// The formula for awarding points, as a `[scoring]` table.
+    pub scoring: ScoringRules,
+}
+
+impl Config {
+    /// Location of the configuration file:
+    /// `$XDG_CONFIG_HOME/card-matching/config.toml`, falling back to
+    /// `~/.config/card-matching/config.toml`. Each
+    /// [profile](`crate::profile`) has its own, in
+    /// `card-matching/profiles/<name>/config.toml`.
+    pub fn default_path() -> Option<PathBuf> {
+        let base = match env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+        };
+        let dir = profile::namespace(base.join("card-matching"));
+        Some(dir.join("config.toml"))
+    }
+
+    /// Load the configuration from the [default location](`Config::default_path`).
+    /// A missing file is not an error and yields the default settings.
+    pub fn load_default() -> Result<Config> {
+        match Config::default_path() {
+            Some(path) if path.exists() => Config::load(&path),
+            _ => Ok(Config::default()),
+        }
+    }
+
+    /// Load and validate the configuration file at `path`.
+    pub fn load(path: &Path) -> Result<Config> {
+        let text = fs::read_to_string(path).map_err(|e| GameError::ConfigIo {
+            path: path.display().to_string(),
+            reason: match e.kind() {
+                ErrorKind::NotFound => "file not found".to_owned(),
+                _ => e.to_string(),
+            },
+            source: Some(Arc::new(e)),
+        })?;
+        let config: Config =
+            toml::from_str(&text).map_err(|e| GameError::InvalidConfig {
+                reason: e.to_string(),
+            })?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Change the setting `key` in the configuration file at `path` to
+    /// `value`, keeping the rest of the file as it is. The file is created
+    /// if it doesn't exist yet.
+    pub fn store(path: &Path, key: &str, value: impl Serialize) -> Result<()> {
+        let save_error =
+            |reason: String, source: Option<io::Error>| GameError::ConfigSave {
+                path: path.display().to_string(),
+                reason,
+                source: source.map(Arc::new),
+            };
+        let io_error = |e: io::Error| save_error(e.to_string(), Some(e));
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(io_error(e)),
+        };
+        let mut table: toml::Table =
+            text.parse().map_err(|e| GameError::InvalidConfig {
+                reason: format!("{}", e),
+            })?;
+        let value = toml::Value::try_from(value)
+            .map_err(|e| save_error(e.to_string(), None))?;
+        table.insert(key.to_owned(), value);
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(io_error)?;
+        }
+        stats::write_atomic(path, table.to_string().as_bytes())
+            .map_err(io_error)
+    }
+
+    /// The keys bound to each action, with the configured overrides.
+    pub fn keymap(&self) -> Result<Keymap> {
+        Keymap::new(&self.key_bindings)
+    }
+
+    /// The configured board size, if any.
+    pub fn board_size(&self) -> Result<Option<Vec2>> {
+        self.size.as_deref().map(parse_size).transpose()
+    }
+
+    /// Check the settings that can't be verified while deserializing.
+    fn validate(&self) -> Result<()> {
+        if let Some(size) = self.board_size()? {
+            input::validate_dimensions(size)?;
+        }
+        if let Some(theme) = &self.theme {
+            Theme::named(theme)?;
+        }
+        Keymap::new(&self.key_bindings)?;
+        // Matched cards would be turned face down right away
+        if self.decay == Some(0) {
+            return Err(GameError::InvalidConfig {
+                reason: "decay must be at least 1".to_owned(),
+            });
+        }
+        if self.time_limit.is_some_and(|limit| limit > MAX_SECONDS) {
+            return Err(GameError::InvalidConfig {
+                reason: format!("time_limit must be at most {}", MAX_SECONDS),
+            });
+        }
+        if self.preview.is_some_and(|time| time > MAX_SECONDS) {
+            return Err(GameError::InvalidConfig {
+                reason: format!("preview must be at most {}", MAX_SECONDS),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Parse a board size in the `<columns>x<rows>` format, e.g. `6x4`.
+pub fn parse_size(s: &str) -> Result<Vec2> {
+    let (x, y) = s.split_once(['x', 'X']).ok_or(GameError::UnparsableInput)?;
+    let x = x
+        .trim()
+        .parse::<i32>()
+        .map_err(|_| GameError::UnparsableInput)?;
+    let y = y
+        .trim()
+        .parse::<i32>()
+        .map_err(|_| GameError::UnparsableInput)?;
+    Ok(Vec2 { x, y })
+}
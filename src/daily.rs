@@ -0,0 +1,166 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::board::Vec2;
+use crate::clock::{SystemTime, UNIX_EPOCH};
+use crate::error::{GameError, Result};
+use crate::persistence::format::{self, FileFormat};
+use crate::stats::{data_dir, write_atomic};
+
+/// Board sizes the daily challenge picks from.
+const DAILY_SIZES: [Vec2; 6] = [
+    Vec2 { x: 4, y: 4 },
+    Vec2 { x: 5, y: 4 },
+    Vec2 { x: 6, y: 4 },
+    Vec2 { x: 6, y: 5 },
+    Vec2 { x: 6, y: 6 },
+    Vec2 { x: 8, y: 5 },
+];
+
+/// The board everyone plays on a given day. Days start at midnight UTC.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Daily {
+    /// The day as `YYYY-MM-DD`.
+    pub date: String,
+    /// Seed used to shuffle the board.
+    pub seed: u64,
+    /// Size of the board.
+    pub size: Vec2,
+}
+
+impl Daily {
+    /// The challenge for the current day.
+    pub fn today() -> Daily {
+        let since_epoch = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        Daily::for_day(since_epoch.as_secs() / 86_400)
+    }
+
+    /// The challenge for the given number of days since 1970-01-01.
+    pub fn for_day(day: u64) -> Daily {
+        // Hash the day with a fixed function rather than `std`'s hasher,
+        // whose output may change between Rust versions
+        let seed = splitmix64(day);
+        let size = DAILY_SIZES[(seed % DAILY_SIZES.len() as u64) as usize];
+        let (y, m, d) = civil_from_days(day as i64);
+
+        Daily {
+            date: format!("{:04}-{:02}-{:02}", y, m, d),
+            seed,
+            size,
+        }
+    }
+}
+
+/// The layout of the results file.
+const FORMAT: FileFormat = FileFormat {
+    kind: "daily",
+    migrations: &[format::enveloped],
+};
+
+/// The outcome of a daily challenge.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct DailyResult {
+    pub guesses: i32,
+    /// Time taken to clear the board, in milliseconds.
+    pub time_ms: u64,
+}
+
+/// Results of past daily challenges, backed by a file.
+pub struct DailyLog {
+    path: PathBuf,
+    results: BTreeMap<String, DailyResult>,
+}
+
+impl DailyLog {
+    /// Location of the results file:
+    /// `$XDG_DATA_HOME/card-matching/daily.json`, falling back to
+    /// `~/.local/share/card-matching/daily.json`.
+    pub fn default_path() -> Option<PathBuf> {
+        Some(data_dir()?.join("daily.json"))
+    }
+
+    /// Load the results stored at `path`. A missing file yields no results.
+    pub fn open(path: &Path) -> Result<DailyLog> {
+        let results = match fs::read(path) {
+            Ok(bytes) => {
+                FORMAT.decode(&bytes).map_err(|e| daily_io(path, e))?
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(daily_io(path, e)),
+        };
+        Ok(DailyLog {
+            path: path.to_owned(),
+            results,
+        })
+    }
+
+    /// The result recorded for the day, as `YYYY-MM-DD`.
+    pub fn get(&self, date: &str) -> Option<DailyResult> {
+        self.results.get(date).copied()
+    }
+
+    /// Record the result for the day and save the log. Only the first
+    /// attempt of each day counts; returns `false` if one was already
+    /// recorded.
+    pub fn record(
+        &mut self,
+        date: &str,
+        guesses: i32,
+        time: Duration,
+    ) -> Result<bool> {
+        if self.results.contains_key(date) {
+            return Ok(false);
+        }
+        let result = DailyResult {
+            guesses,
+            time_ms: time.as_millis() as u64,
+        };
+        self.results.insert(date.to_owned(), result);
+
+        let bytes = FORMAT.encode(&self.results);
+        write_atomic(&self.path, &bytes)
+            .map_err(|e| daily_io(&self.path, e))?;
+        Ok(true)
+    }
+}
+
+/// Scramble the bits of `x`. See <https://prng.di.unimi.it/splitmix64.c>.
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Convert days since 1970-01-01 into a (year, month, day) date. See
+/// <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    (y, m, d)
+}
+
+fn daily_io(path: &Path, e: io::Error) -> GameError {
+    GameError::StatsIo {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+        source: Some(Arc::new(e)),
+    }
+}
@@ -0,0 +1,168 @@
+//! Ratings of players of networked games, updated with the Elo formula
+//! after every match, along with the record of the matches against each
+//! opponent. Every player keeps their own rating: an opponent's rating is
+//! what they introduce themselves with at the start of a match.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{GameError, Result};
+use crate::persistence::format::{self, FileFormat};
+use crate::stats::{data_dir, write_atomic};
+
+/// The rating of a player who hasn't finished a match yet.
+pub const INITIAL: f64 = 1200.0;
+
+/// Most points a rating can change by in a single match.
+const K: f64 = 32.0;
+
+/// The score a player with `rating` is expected to get against an opponent
+/// with `opponent`, from 0 for a certain loss to 1 for a certain win.
+pub fn expected(rating: f64, opponent: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((opponent - rating) / 400.0))
+}
+
+/// The rating of a player with `rating` after a match against an opponent
+/// with `opponent` ended with `result`.
+pub fn updated(rating: f64, opponent: f64, result: MatchResult) -> f64 {
+    rating + K * (result.score() - expected(rating, opponent))
+}
+
+/// How a match ended for the local player.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MatchResult {
+    Win,
+    Draw,
+    Loss,
+}
+
+impl MatchResult {
+    /// The score of the result in the Elo formula.
+    pub fn score(self) -> f64 {
+        match self {
+            MatchResult::Win => 1.0,
+            MatchResult::Draw => 0.5,
+            MatchResult::Loss => 0.0,
+        }
+    }
+}
+
+/// The matches played against an opponent.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OpponentRecord {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    /// The rating the opponent had at the start of the last match.
+    pub rating: f64,
+}
+
+/// The layout of the ratings file.
+const FORMAT: FileFormat = FileFormat {
+    kind: "ratings",
+    migrations: &[format::enveloped],
+};
+
+/// The rating of the local player and their record against every
+/// opponent.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Ratings {
+    pub rating: f64,
+    /// The record against each opponent, keyed by the name they introduced
+    /// themselves with. Opponents without a name share the empty key.
+    pub opponents: BTreeMap<String, OpponentRecord>,
+}
+
+impl Default for Ratings {
+    fn default() -> Ratings {
+        Ratings {
+            rating: INITIAL,
+            opponents: BTreeMap::new(),
+        }
+    }
+}
+
+impl Ratings {
+    /// Add the result of a match against the opponent called `opponent`,
+    /// rated `opponent_rating`. Returns the rating from before the match.
+    pub fn record(
+        &mut self,
+        opponent: &str,
+        opponent_rating: f64,
+        result: MatchResult,
+    ) -> f64 {
+        let before = self.rating;
+        self.rating = updated(before, opponent_rating, result);
+        let record = self.opponents.entry(opponent.to_owned()).or_default();
+        match result {
+            MatchResult::Win => record.wins += 1,
+            MatchResult::Draw => record.draws += 1,
+            MatchResult::Loss => record.losses += 1,
+        }
+        record.rating = opponent_rating;
+        before
+    }
+}
+
+/// Ratings backed by a file.
+pub struct RatingStore {
+    path: PathBuf,
+    pub ratings: Ratings,
+}
+
+impl RatingStore {
+    /// Location of the ratings file:
+    /// `$XDG_DATA_HOME/card-matching/ratings.json`, falling back to
+    /// `~/.local/share/card-matching/ratings.json`.
+    pub fn default_path() -> Option<PathBuf> {
+        Some(data_dir()?.join("ratings.json"))
+    }
+
+    /// Load the ratings stored at `path`. A missing file yields the
+    /// [initial](`INITIAL`) rating, without any matches.
+    pub fn open(path: &Path) -> Result<RatingStore> {
+        let ratings = match fs::read(path) {
+            Ok(bytes) => {
+                FORMAT.decode(&bytes).map_err(|e| ratings_io(path, e))?
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => Ratings::default(),
+            Err(e) => return Err(ratings_io(path, e)),
+        };
+        Ok(RatingStore {
+            path: path.to_owned(),
+            ratings,
+        })
+    }
+
+    /// Add the result of a match and save the ratings. Returns the rating
+    /// from before the match.
+    pub fn record(
+        &mut self,
+        opponent: &str,
+        opponent_rating: f64,
+        result: MatchResult,
+    ) -> Result<f64> {
+        let before = self.ratings.record(opponent, opponent_rating, result);
+        let bytes = FORMAT.encode(&self.ratings);
+        write_atomic(&self.path, &bytes)
+            .map_err(|e| ratings_io(&self.path, e))?;
+        Ok(before)
+    }
+}
+
+fn ratings_io(path: &Path, e: io::Error) -> GameError {
+    GameError::StatsIo {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+        source: Some(Arc::new(e)),
+    }
+}
@@ -1,5 +1,23 @@
+use std::{error::Error, fmt, io, sync::Arc};
+
+use crate::t;
+
 pub type Result<T> = std::result::Result<T, GameError>;
 
+/// Whether an error was caused by what the player asked for, or by
+/// something out of their hands.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ErrorCategory {
+    /// Input that can be corrected and tried again, e.g. coordinates off
+    /// the board or an invalid setting.
+    Input,
+    /// A failure of the system the game runs on, e.g. a file that can't
+    /// be written or a lost connection.
+    Internal,
+}
+
+/// Errors that failed I/O operations keep the [`io::Error`] behind them as
+/// their [source](`Error::source`), shared so that they can be cloned.
 #[derive(Clone, Debug)]
 pub enum GameError {
     /// Tried to reveal a card that was already revealed or matched.
@@ -12,40 +30,376 @@ pub enum GameError {
     CoordinateUnderflow { axis: char },
     /// Requested too many board spaces to be created.
     NotEnoughCardTypes { max: i32 },
-    /// Requested an odd number of board spaces to be created.
+    /// Requested a board with more spaces than coordinates can address.
+    BoardTooLarge { x: i32, y: i32 },
+    /// Requested an odd number of board spaces to be created, not counting
+    /// the ones taken by jokers and bombs.
     OddBoardCells,
+    /// Requested more jokers and bombs than the board has spaces.
+    TooManyExtraCards { max: i32 },
+    /// Supplied a board shape without any cells.
+    EmptyShape,
+    /// Supplied a board shape with a character other than `#`, `.` or a
+    /// space.
+    InvalidShapeChar { char: char },
+    /// Failed to read a board shape file.
+    ShapeIo {
+        path: String,
+        reason: String,
+        source: Option<Arc<io::Error>>,
+    },
+    /// Tried to reveal a hole in the shape of the board.
+    NoCard { x: i32, y: i32 },
+    /// Asked for a hint on a card whose partner is under another card.
+    PartnerCovered,
+    /// Supplied a board code that wasn't made by this version of the game.
+    InvalidBoardCode,
+    /// Supplied a result code that was mistyped or edited.
+    InvalidResultCode,
     /// Supplied input that we were unable to interpret.
     UnparsableInput,
+    /// Supplied `input` with a single number where two were expected.
+    SingleNumber { input: String },
+    /// Supplied `input` that isn't a position on the board in any format.
+    UnreadableCoords { input: String },
+    /// Supplied `input` that isn't the size of a board.
+    UnreadableDimensions { input: String },
+    /// Supplied `input` that isn't an answer to a yes/no question.
+    UnreadableAnswer { input: String },
+    /// Supplied a cell number in `input` that isn't between 1 and `max`.
+    NoSuchCell { input: String, max: i32 },
+    /// Supplied a position in `input` beyond the board, which is `size`
+    /// cards long along `axis`.
+    OffBoard {
+        input: String,
+        axis: char,
+        size: i32,
+    },
+    /// Supplied a column in the `c4` format with a character that isn't a
+    /// letter.
+    InvalidColumnLetter { letter: char },
+    /// Supplied a column in the `c4` format beyond the last column.
+    ColumnOutOfRange { column: String, last: String },
+    /// Failed to read the configuration file.
+    ConfigIo {
+        path: String,
+        reason: String,
+        source: Option<Arc<io::Error>>,
+    },
+    /// The configuration file contains invalid settings.
+    InvalidConfig { reason: String },
+    /// Failed to write the settings to the configuration file.
+    ConfigSave {
+        path: String,
+        reason: String,
+        source: Option<Arc<io::Error>>,
+    },
+    /// Lost the connection to the opponent.
+    ConnectionLost {
+        reason: String,
+        source: Option<Arc<io::Error>>,
+    },
+    /// Asked for a hint in a game that doesn't allow them.
+    HintsDisabled,
+    /// Failed to read or write the statistics file.
+    StatsIo {
+        path: String,
+        reason: String,
+        source: Option<Arc<io::Error>>,
+    },
+    /// Failed to read or write the saved game.
+    SaveIo {
+        path: String,
+        reason: String,
+        source: Option<Arc<io::Error>>,
+    },
+    /// Failed to read or write the history of games.
+    HistoryIo { path: String, reason: String },
+    /// Failed to read or write a recording of the moves made on a board.
+    ReplayIo {
+        path: String,
+        reason: String,
+        source: Option<Arc<io::Error>>,
+    },
+    /// Failed to write an export of the statistics.
+    ExportIo {
+        path: String,
+        reason: String,
+        source: Option<Arc<io::Error>>,
+    },
+    /// Picked an entry that isn't in the menu.
+    InvalidMenuChoice { max: usize },
+    /// Picked a campaign level that doesn't exist.
+    InvalidLevel { max: usize },
+    /// Picked a campaign level before clearing the ones leading up to it.
+    LevelLocked { level: usize },
+    /// Tried to use a power-up that the player doesn't have.
+    NoPowerUp { name: String },
+    /// Typed a `:` command that doesn't exist.
+    UnknownCommand { command: String },
+    /// Typed a `:` command that can't be used right now.
+    CommandUnavailable { command: String },
+    /// Picked a color theme that doesn't exist.
+    UnknownTheme { name: String, themes: String },
+    /// Picked a profile name with characters other than letters, digits,
+    /// `-` and `_`, or too long.
+    InvalidProfileName { name: String },
+    /// Typed something other than a line of chat while the opponent picks
+    /// a card.
+    NotYourTurn,
+    /// Sent too many lines of chat in quick succession.
+    ChatTooFast,
+    /// Tried to start a board, or something else that doesn't lead on from
+    /// `state`, e.g. while a board is in play.
+    InvalidState { state: String },
 }
 
 impl GameError {
+    /// Whether the player can do something about the error.
+    pub fn category(&self) -> ErrorCategory {
+        use GameError::*;
+
+        match self {
+            ShapeIo { .. }
+            | ConfigIo { .. }
+            | ConfigSave { .. }
+            | StatsIo { .. }
+            | SaveIo { .. }
+            | HistoryIo { .. }
+            | ExportIo { .. }
+            | ReplayIo { .. }
+            | ConnectionLost { .. } => ErrorCategory::Internal,
+            _ => ErrorCategory::Input,
+        }
+    }
+
+    /// A name for the kind of error that stays the same across versions
+    /// and languages, e.g. `already_revealed`, for programs reading the
+    /// [JSON protocol](`crate::protocol`).
+    pub fn code(&self) -> &'static str {
+        use GameError::*;
+
+        match self {
+            AlreadyRevealed { .. } => "already_revealed",
+            EmptyInput => "empty_input",
+            CoordinateOverflow { .. } => "coordinate_overflow",
+            CoordinateUnderflow { .. } => "coordinate_underflow",
+            NotEnoughCardTypes { .. } => "not_enough_card_types",
+            BoardTooLarge { .. } => "board_too_large",
+            OddBoardCells => "odd_board_cells",
+            TooManyExtraCards { .. } => "too_many_extra_cards",
+            EmptyShape => "empty_shape",
+            InvalidShapeChar { .. } => "invalid_shape_char",
+            ShapeIo { .. } => "shape_io",
+            NoCard { .. } => "no_card",
+            PartnerCovered => "partner_covered",
+            InvalidBoardCode => "invalid_board_code",
+            InvalidResultCode => "invalid_result_code",
+            UnparsableInput => "unparsable_input",
+            SingleNumber { .. } => "single_number",
+            UnreadableCoords { .. } => "unreadable_coords",
+            UnreadableDimensions { .. } => "unreadable_dimensions",
+            UnreadableAnswer { .. } => "unreadable_answer",
+            NoSuchCell { .. } => "no_such_cell",
+            OffBoard { .. } => "off_board",
+            InvalidColumnLetter { .. } => "invalid_column_letter",
+            ColumnOutOfRange { .. } => "column_out_of_range",
+            ConfigIo { .. } => "config_io",
+            InvalidConfig { .. } => "invalid_config",
+            ConfigSave { .. } => "config_save",
+            ConnectionLost { .. } => "connection_lost",
+            HintsDisabled => "hints_disabled",
+            StatsIo { .. } => "stats_io",
+            SaveIo { .. } => "save_io",
+            HistoryIo { .. } => "history_io",
+            ExportIo { .. } => "export_io",
+            ReplayIo { .. } => "replay_io",
+            InvalidMenuChoice { .. } => "invalid_menu_choice",
+            InvalidLevel { .. } => "invalid_level",
+            LevelLocked { .. } => "level_locked",
+            NoPowerUp { .. } => "no_power_up",
+            UnknownCommand { .. } => "unknown_command",
+            CommandUnavailable { .. } => "command_unavailable",
+            UnknownTheme { .. } => "unknown_theme",
+            InvalidProfileName { .. } => "invalid_profile_name",
+            NotYourTurn => "not_your_turn",
+            ChatTooFast => "chat_too_fast",
+            InvalidState { .. } => "invalid_state",
+        }
+    }
+
     pub fn as_string(&self) -> String {
         use GameError::*;
 
-        let message = match self {
+        match self {
             AlreadyRevealed { x, y } => {
-                format!("Card at position ({},{}) is already revealed.", x, y)
+                t!("error-already-revealed", x = x, y = y)
             }
             EmptyInput => {
-                "User input is required".to_owned()
+                t!("error-empty-input")
             }
             CoordinateOverflow { axis, max } => {
-                format!("{} coordinate too large. Maximum possible value is {}.", axis, max)
+                t!("error-coordinate-overflow", axis = axis, max = max)
             }
             CoordinateUnderflow { axis } => {
-                format!("{} coordinate too small. Minimum possible value is 0.", axis)
+                t!("error-coordinate-underflow", axis = axis)
+            }
+            BoardTooLarge { x, y } => {
+                t!("error-board-too-large", x = x, y = y)
             }
             OddBoardCells => {
-                "Number of board cells (horizontal size * vertical size) must be even".to_owned()
+                t!("error-odd-board-cells")
+            }
+            TooManyExtraCards { max } => {
+                t!("error-too-many-extra-cards", max = max)
             }
             NotEnoughCardTypes { max } => {
-                format!("Cannot create board with more than {} cells", max * 2)
+                t!("error-not-enough-card-types", max = max * 2)
+            }
+            EmptyShape => {
+                t!("error-empty-shape")
+            }
+            InvalidShapeChar { char } => {
+                t!("error-invalid-shape-char", char = char)
+            }
+            ShapeIo { path, reason, .. } => {
+                t!("error-shape-io", path = path, reason = reason)
+            }
+            NoCard { x, y } => {
+                t!("error-no-card", x = x, y = y)
+            }
+            PartnerCovered => {
+                t!("error-partner-covered")
+            }
+            InvalidBoardCode => {
+                t!("error-invalid-board-code")
+            }
+            InvalidResultCode => {
+                t!("error-invalid-result-code")
             }
             UnparsableInput => {
-                "User input could not be parsed".to_owned()
+                t!("error-unparsable-input")
+            }
+            SingleNumber { input } => {
+                t!("error-single-number", input = input)
+            }
+            UnreadableCoords { input } => {
+                t!("error-unreadable-coords", input = input)
+            }
+            UnreadableDimensions { input } => {
+                t!("error-unreadable-dimensions", input = input)
+            }
+            UnreadableAnswer { input } => {
+                t!("error-unreadable-answer", input = input)
+            }
+            NoSuchCell { input, max } => {
+                t!("error-no-such-cell", input = input, max = max)
+            }
+            OffBoard {
+                input,
+                axis: 'x',
+                size,
+            } => {
+                t!("error-off-board-x", input = input, size = size)
+            }
+            OffBoard { input, size, .. } => {
+                t!("error-off-board-y", input = input, size = size)
+            }
+            InvalidColumnLetter { letter } => {
+                t!("error-invalid-column-letter", letter = letter)
+            }
+            ColumnOutOfRange { column, last } => {
+                t!("error-column-out-of-range", column = column, last = last)
             }
-        };
+            ConfigIo { path, reason, .. } => {
+                t!("error-config-io", path = path, reason = reason)
+            }
+            InvalidConfig { reason } => {
+                t!("error-invalid-config", reason = reason)
+            }
+            ConfigSave { path, reason, .. } => {
+                t!("error-config-save", path = path, reason = reason)
+            }
+            ConnectionLost { reason, .. } => {
+                t!("error-connection-lost", reason = reason)
+            }
+            HintsDisabled => {
+                t!("error-hints-disabled")
+            }
+            StatsIo { path, reason, .. } => {
+                t!("error-stats-io", path = path, reason = reason)
+            }
+            SaveIo { path, reason, .. } => {
+                t!("error-save-io", path = path, reason = reason)
+            }
+            HistoryIo { path, reason } => {
+                t!("error-history-io", path = path, reason = reason)
+            }
+            ExportIo { path, reason, .. } => {
+                t!("error-export-io", path = path, reason = reason)
+            }
+            ReplayIo { path, reason, .. } => {
+                t!("error-replay-io", path = path, reason = reason)
+            }
+            InvalidMenuChoice { max } => {
+                t!("error-invalid-menu-choice", max = max)
+            }
+            InvalidLevel { max } => {
+                t!("error-invalid-level", max = max)
+            }
+            LevelLocked { level } => {
+                t!("error-level-locked", level = level)
+            }
+            NoPowerUp { name } => {
+                t!("error-no-power-up", name = name)
+            }
+            UnknownCommand { command } => {
+                t!("error-unknown-command", command = command)
+            }
+            CommandUnavailable { command } => {
+                t!("error-command-unavailable", command = command)
+            }
+            UnknownTheme { name, themes } => {
+                t!("error-unknown-theme", name = name, themes = themes)
+            }
+            InvalidProfileName { name } => {
+                t!("error-invalid-profile-name", name = name)
+            }
+            NotYourTurn => {
+                t!("error-not-your-turn")
+            }
+            ChatTooFast => {
+                t!("error-chat-too-fast")
+            }
+            InvalidState { state } => {
+                t!("error-invalid-state", state = state)
+            }
+        }
+    }
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.as_string())
+    }
+}
 
-        return message;
+impl Error for GameError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use GameError::*;
+
+        match self {
+            ShapeIo { source, .. }
+            | ConfigIo { source, .. }
+            | ConfigSave { source, .. }
+            | StatsIo { source, .. }
+            | SaveIo { source, .. }
+            | ExportIo { source, .. }
+            | ReplayIo { source, .. }
+            | ConnectionLost { source, .. } => {
+                source.as_deref().map(|e| e as &(dyn Error + 'static))
+            }
+            _ => None,
+        }
     }
 }
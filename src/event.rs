@@ -0,0 +1,62 @@
+use std::{sync::mpsc::Sender, time::Duration};
+
+use crate::board::{Card, Vec2};
+
+/// Something that happened in a game, reported to its observers.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Event {
+    /// The game moved to another state. States are named as in
+    /// [`Snapshot::state`](crate::protocol::Snapshot::state).
+    StateChanged {
+        from: &'static str,
+        to: &'static str,
+    },
+    /// A card was turned face up by either player.
+    CardRevealed {
+        at: Vec2,
+        card: Card,
+        /// `true` if this was the second card of a guess, which is followed
+        /// by a [`Event::PairMatched`] or [`Event::Mismatch`], or a bomb,
+        /// which is followed by a [`Event::BombExploded`].
+        completes_guess: bool,
+    },
+    /// The two revealed cards form a pair.
+    PairMatched {
+        first: Vec2,
+        second: Vec2,
+        card: Card,
+    },
+    /// The two revealed cards don't form a pair.
+    Mismatch { first: Vec2, second: Vec2 },
+    /// The player ran out of time to make a guess, which counts as a
+    /// mismatch.
+    TimeUp,
+    /// The player revealed a bomb.
+    BombExploded,
+    /// The last pair on the board was found.
+    GameWon { guesses: i32, time: Duration },
+    /// The player ran out of lives before clearing the board.
+    GameLost { guesses: i32, time: Duration },
+    /// Sound effects were turned on or off in the settings.
+    SoundToggled { muted: bool },
+}
+
+/// Reacts to the events of a game, without the game knowing about it.
+/// Register one with [`Game::subscribe`](crate::game::Game::subscribe).
+pub trait Observer {
+    fn notify(&mut self, event: &Event);
+}
+
+impl<F: FnMut(&Event)> Observer for F {
+    fn notify(&mut self, event: &Event) {
+        self(event)
+    }
+}
+
+/// Forwards events to another thread. Events are dropped once the receiver
+/// is gone.
+impl Observer for Sender<Event> {
+    fn notify(&mut self, event: &Event) {
+        let _ = self.send(*event);
+    }
+}
@@ -0,0 +1,321 @@
+use std::{
+    future,
+    io::{self, Write},
+    mem,
+    sync::mpsc::{self, Sender},
+    thread,
+    time::{Duration, Instant},
+};
+
+use rs_card_matching::{
+    config::Pacing,
+    game::Game,
+    net::{Connection, Message},
+    terminal,
+};
+use rustyline::{error::ReadlineError, DefaultEditor};
+use tokio::{
+    signal,
+    sync::{
+        mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+        oneshot,
+    },
+    time,
+};
+
+/// Time between the frames of an animation.
+pub const FRAME_TIME: Duration = Duration::from_millis(60);
+
+/// Time each step of a batch of guesses stays on screen.
+const BATCH_STEP: Duration = Duration::from_millis(400);
+
+/// Time between checks for chat from the opponent, or the end of the turn,
+/// while it's the local player's turn.
+const CHAT_POLL: Duration = Duration::from_millis(250);
+
+/// Something the game loop has to react to.
+enum LoopEvent {
+    /// A line typed by the player.
+    Input(io::Result<String>),
+    /// A key pressed by the player when asked for one, or `None` if none
+    /// was pressed in time.
+    Key(Option<String>),
+    /// The move of the opponent in a networked game.
+    Opponent(io::Result<Message>),
+    /// The player pressed Ctrl+C, or Ctrl+D at the prompt.
+    Interrupt,
+    /// The terminal was resized.
+    Resize,
+    /// The time of the next animation frame, batch step or deadline has
+    /// come.
+    Tick,
+}
+
+/// What the input thread is asked to read.
+enum Request {
+    /// A line, starting with some text already typed.
+    Line(String),
+    /// A single key press, waiting for it for up to the timeout, if any.
+    Key(Option<Duration>),
+}
+
+/// Drives a game from events awaited concurrently on an async runtime:
+/// input, interrupts, resizes, the opponent's moves and timers. Lines and
+/// keys are read on a separate thread, and the opponent's moves on another
+/// one while it's their turn, as neither can be read without blocking. Both
+/// send typed events over a channel, along with resizes. Animation frames
+/// and deadlines are ticks of a timer raced against them. While it's the
+/// local player's turn, the opponent can only have sent chat, or the lobby
+/// that the turn ran out of time, which are checked for on ticks instead.
+pub struct EventLoop {
+    events: UnboundedReceiver<LoopEvent>,
+    /// Queues events from other sources than the input thread.
+    sender: UnboundedSender<LoopEvent>,
+    /// Asks the input thread to show the prompt and read a line, or to read
+    /// a key.
+    prompt: Sender<Request>,
+    /// Set while the input thread is reading a line or a key.
+    reading: bool,
+    /// A key pressed to skip the feedback about a guess, which the next
+    /// line starts with.
+    typed: String,
+    /// The opponent's next move, while waiting for it.
+    opponent: Option<oneshot::Receiver<io::Result<Message>>>,
+}
+
+impl EventLoop {
+    /// Start the input thread.
+    pub fn new() -> EventLoop {
+        let (sender, events) = unbounded_channel();
+        let (prompt, requests) = mpsc::channel();
+
+        let input = sender.clone();
+        thread::spawn(move || {
+            let mut editor = DefaultEditor::new().ok();
+            for request in requests {
+                let event = match request {
+                    Request::Line(typed) => read_line(editor.as_mut(), &typed),
+                    Request::Key(timeout) => {
+                        match terminal::read_key(timeout) {
+                            Ok(key) => LoopEvent::Key(key),
+                            // Not a terminal that can read single keys
+                            Err(_) => read_line(editor.as_mut(), ""),
+                        }
+                    }
+                };
+                let done = !matches!(
+                    event,
+                    LoopEvent::Input(Ok(_)) | LoopEvent::Key(_)
+                );
+                if input.send(event).is_err() || done {
+                    break;
+                }
+            }
+        });
+
+        EventLoop {
+            events,
+            sender,
+            prompt,
+            reading: false,
+            typed: String::new(),
+            opponent: None,
+        }
+    }
+
+    /// Drive the game until the player exits, rendering it after every
+    /// event.
+    pub async fn run(&mut self, game: &mut Game) -> io::Result<()> {
+        #[cfg(unix)]
+        self.watch_resizes();
+        game.render();
+
+        while game.is_running() {
+            if game.awaits_opponent() && self.opponent.is_none() {
+                self.opponent = Some(wait_for_opponent(game)?);
+            }
+            // Players can chat while the opponent picks a card
+            let idle = !game.awaits_opponent() || game.can_chat();
+            if idle
+                && !self.reading
+                && !game.is_animating()
+                && !game.has_queued_input()
+            {
+                // Show the prompt once the frame is complete
+                let request = if game.awaits_key() {
+                    let now = Instant::now();
+                    Request::Key(
+                        game.deadline()
+                            .map(|t| t.saturating_duration_since(now)),
+                    )
+                } else {
+                    Request::Line(mem::take(&mut self.typed))
+                };
+                let typing = matches!(request, Request::Line(_));
+                self.reading = self.prompt.send(request).is_ok();
+                game.set_typing(self.reading && typing);
+            }
+
+            let event = self.next_event(game).await;
+            if matches!(event, LoopEvent::Input(_) | LoopEvent::Key(_)) {
+                self.reading = false;
+                game.set_typing(false);
+            }
+            match event {
+                LoopEvent::Input(line) => {
+                    game.set_input(line?);
+                    game.update();
+                }
+                LoopEvent::Key(Some(key)) => {
+                    // The feedback may have moved on by itself already
+                    if game.awaits_key() {
+                        game.set_input(String::new());
+                        game.update();
+                    }
+                    // Without having to press <Enter> first
+                    if game.pacing() == Pacing::Fast
+                        && !key.chars().any(char::is_control)
+                    {
+                        self.typed = key;
+                    }
+                }
+                LoopEvent::Opponent(msg) => {
+                    if let Some(msg) = game.receive(msg?) {
+                        game.set_opponent_move(msg)?;
+                        game.update();
+                    }
+                }
+                LoopEvent::Interrupt => {
+                    game.quit();
+                    break;
+                }
+                LoopEvent::Resize => game.resized(),
+                LoopEvent::Tick if game.is_animating() => game.tick(),
+                LoopEvent::Key(None) | LoopEvent::Tick => {
+                    game.poll_messages()?;
+                    if let Some(input) = game.next_queued_input() {
+                        game.set_input(input);
+                        game.update();
+                    } else if game
+                        .deadline()
+                        .is_some_and(|t| t <= Instant::now())
+                    {
+                        // The deadline passed without input
+                        game.set_input(String::new());
+                        game.update();
+                    }
+                }
+            }
+            game.render();
+        }
+        Ok(())
+    }
+
+    /// Queue a resize event whenever the terminal is resized.
+    #[cfg(unix)]
+    fn watch_resizes(&self) {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let Ok(mut resizes) = signal(SignalKind::window_change()) else {
+            return;
+        };
+        let events = self.sender.clone();
+        tokio::spawn(async move {
+            while resizes.recv().await.is_some() {
+                if events.send(LoopEvent::Resize).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+
+    /// Wait for the next event, or until the next tick is due.
+    async fn next_event(&mut self, game: &Game) -> LoopEvent {
+        let wake_at = if game.is_animating() {
+            Some(Instant::now() + FRAME_TIME)
+        } else if game.has_queued_input() {
+            Some(Instant::now() + BATCH_STEP)
+        } else {
+            let chat = (game.can_chat() && !game.awaits_opponent())
+                .then(|| Instant::now() + CHAT_POLL);
+            // Whichever comes first
+            [game.deadline(), game.redraw_at(), chat]
+                .into_iter()
+                .flatten()
+                .min()
+        };
+        let tick = async {
+            match wake_at {
+                Some(at) => time::sleep_until(at.into()).await,
+                None => future::pending().await,
+            }
+        };
+        let opponent = async {
+            match self.opponent.as_mut() {
+                Some(reply) => reply.await.unwrap_or_else(|_| {
+                    Err(io::ErrorKind::ConnectionAborted.into())
+                }),
+                None => future::pending().await,
+            }
+        };
+
+        let event = tokio::select! {
+            event = self.events.recv() => event,
+            msg = opponent => Some(LoopEvent::Opponent(msg)),
+            Ok(()) = signal::ctrl_c() => Some(LoopEvent::Interrupt),
+            () = tick => Some(LoopEvent::Tick),
+        };
+        if matches!(event, Some(LoopEvent::Opponent(_))) {
+            self.opponent = None;
+        }
+        // The input thread only stops after sending an error or EOF
+        event.unwrap_or_else(|| {
+            LoopEvent::Input(Err(io::ErrorKind::UnexpectedEof.into()))
+        })
+    }
+}
+
+/// Receive the opponent's next move on a thread of its own, so that the
+/// game can still be interrupted and redrawn in the meantime.
+fn wait_for_opponent(
+    game: &Game,
+) -> io::Result<oneshot::Receiver<io::Result<Message>>> {
+    let mut conn = game
+        .connection()
+        .map(Connection::try_clone)
+        .ok_or(io::ErrorKind::NotConnected)??;
+    let (reply, receiver) = oneshot::channel();
+    thread::spawn(move || {
+        let _ = reply.send(conn.recv());
+    });
+    Ok(receiver)
+}
+
+/// Read a line with `editor`, which keeps the history of previous inputs,
+/// or straight from stdin if the terminal doesn't support line editing. The
+/// line starts with `typed` if the editor can show it.
+fn read_line(editor: Option<&mut DefaultEditor>, typed: &str) -> LoopEvent {
+    let editor = match editor {
+        Some(editor) => editor,
+        None => {
+            print!("> ");
+            let _ = io::stdout().flush();
+            let mut line = String::new();
+            return match io::stdin().read_line(&mut line) {
+                Ok(0) => LoopEvent::Interrupt,
+                result => LoopEvent::Input(result.map(|_| line)),
+            };
+        }
+    };
+    match editor.readline_with_initial("> ", (typed, "")) {
+        Ok(line) => {
+            let _ = editor.add_history_entry(line.as_str());
+            LoopEvent::Input(Ok(line))
+        }
+        Err(ReadlineError::Interrupted | ReadlineError::Eof) => {
+            LoopEvent::Interrupt
+        }
+        Err(ReadlineError::Io(e)) => LoopEvent::Input(Err(e)),
+        Err(e) => LoopEvent::Input(Err(io::Error::other(e))),
+    }
+}
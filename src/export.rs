@@ -0,0 +1,176 @@
+//! Writing the statistics and the history of games to a file, to be
+//! analyzed in other tools.
+//!
+//! Both formats hold a row for each game in the history, the oldest first,
+//! with these fields:
+//!
+//! | field         | meaning                                          |
+//! |---------------|--------------------------------------------------|
+//! | `finished_at` | seconds since 1970-01-01 UTC when the game ended |
+//! | `date`        | the same day as `YYYY-MM-DD`, in UTC             |
+//! | `mode`        | the kind of game, e.g. `classic` or `daily`      |
+//! | `columns`     | number of columns of the board                   |
+//! | `rows`        | number of rows of the board                      |
+//! | `seed`        | the seed of a seeded board, or empty / `null`    |
+//! | `guesses`     | number of guesses, including hint penalties      |
+//! | `pairs`       | number of pairs on the board                     |
+//! | `duration_ms` | time taken in milliseconds                       |
+//! | `won`         | whether the board was cleared                    |
+//!
+//! CSV files start with a header row naming the fields in this order, and
+//! write `true` / `false` for `won`. JSON files hold a single object:
+//!
+//! ```json
+//! { "version": 1, "stats": { ... }, "games": [ { ... }, ... ] }
+//! ```
+//!
+//! where `stats` is the aggregated [statistics](`crate::stats::Stats`) as
+//! kept in `stats.json`. Fields are only ever added within a version.
+//!
+//! The games are written one at a time as they are read, so exporting a
+//! long history doesn't need to hold it in memory.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use serde::Serialize;
+
+use crate::error::{GameError, Result};
+use crate::stats::Stats;
+
+/// Version of the layout of JSON exports.
+pub const VERSION: u32 = 1;
+
+/// The fields of a game, in the order of the CSV columns.
+const CSV_HEADER: &str =
+    "finished_at,date,mode,columns,rows,seed,guesses,pairs,duration_ms,won";
+
+/// The file format to export to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    /// A row per game, with a header row.
+    Csv,
+    /// The statistics and the games in a single object.
+    Json,
+}
+
+/// A game as it is exported.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+pub struct ExportedGame {
+    pub finished_at: u64,
+    pub date: String,
+    pub mode: String,
+    pub columns: i32,
+    pub rows: i32,
+    pub seed: Option<u64>,
+    pub guesses: i32,
+    pub pairs: i32,
+    pub duration_ms: u64,
+    pub won: bool,
+}
+
+/// An export being written to a file.
+pub struct Exporter {
+    path: PathBuf,
+    out: BufWriter<File>,
+    format: Format,
+    games: usize,
+}
+
+impl Exporter {
+    /// Create the file at `path` and write everything that comes before
+    /// the games.
+    pub fn create(path: &Path, format: Format, stats: &Stats) -> Result<Self> {
+        let file = File::create(path).map_err(|e| export_io(path, e))?;
+        let mut exporter = Exporter {
+            path: path.to_owned(),
+            out: BufWriter::new(file),
+            format,
+            games: 0,
+        };
+        exporter
+            .write_start(stats)
+            .map_err(|e| export_io(path, e))?;
+        Ok(exporter)
+    }
+
+    /// Write the next game.
+    pub fn game(&mut self, game: &ExportedGame) -> Result<()> {
+        self.write_game(game)
+            .map_err(|e| export_io(&self.path, e))?;
+        self.games += 1;
+        Ok(())
+    }
+
+    /// Write everything that comes after the games. Returns the number of
+    /// games written.
+    pub fn finish(mut self) -> Result<usize> {
+        self.write_end().map_err(|e| export_io(&self.path, e))?;
+        Ok(self.games)
+    }
+
+    fn write_start(&mut self, stats: &Stats) -> io::Result<()> {
+        match self.format {
+            Format::Csv => writeln!(self.out, "{}", CSV_HEADER),
+            Format::Json => {
+                write!(self.out, "{{\"version\":{},\"stats\":", VERSION)?;
+                serde_json::to_writer(&mut self.out, stats)?;
+                write!(self.out, ",\"games\":[")
+            }
+        }
+    }
+
+    fn write_game(&mut self, game: &ExportedGame) -> io::Result<()> {
+        match self.format {
+            Format::Csv => writeln!(
+                self.out,
+                "{},{},{},{},{},{},{},{},{},{}",
+                game.finished_at,
+                game.date,
+                csv_field(&game.mode),
+                game.columns,
+                game.rows,
+                game.seed.map(|seed| seed.to_string()).unwrap_or_default(),
+                game.guesses,
+                game.pairs,
+                game.duration_ms,
+                game.won,
+            ),
+            Format::Json => {
+                if self.games > 0 {
+                    write!(self.out, ",")?;
+                }
+                serde_json::to_writer(&mut self.out, game)?;
+                Ok(())
+            }
+        }
+    }
+
+    fn write_end(&mut self) -> io::Result<()> {
+        if self.format == Format::Json {
+            writeln!(self.out, "]}}")?;
+        }
+        self.out.flush()
+    }
+}
+
+/// Quote `field` if it has any characters that are special in CSV.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+fn export_io(path: &Path, e: io::Error) -> GameError {
+    GameError::ExportIo {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+        source: Some(Arc::new(e)),
+    }
+}
@@ -0,0 +1,118 @@
+//! Commit-reveal for networked games, so that the joining player can check
+//! that the host didn't move any cards during play.
+//!
+//! Before the first turn, the host only sends the shape of the board and a
+//! commitment: the SHA-256 hash of a random salt followed by the
+//! [canonical serialization](`board_bytes`) of the board. The face of each
+//! card is sent when it is revealed. Once the last pair is found, the host
+//! opens the commitment by sending the salt and the whole board, and the
+//! joining player checks that it hashes to the commitment and holds every
+//! card that was revealed where it was revealed.
+//!
+//! Challenges don't need this, as both players deal the board from the
+//! same [board code](`crate::code::BoardCode`).
+
+use std::collections::HashMap;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use sha2::{Digest, Sha256};
+
+use crate::board::Vec2;
+
+/// A SHA-256 hash committing to a board.
+pub type Hash = [u8; 32];
+
+/// Random bytes hashed along with the board, so that the commitment can't
+/// be matched against guessed boards.
+pub type Salt = [u8; 32];
+
+/// Stands in for the holes of the board in its serialization.
+const HOLE: u32 = u32::MAX;
+
+/// The canonical serialization of a board: the number of columns and rows
+/// followed by the card at every position in row major order, each as a
+/// big-endian 32-bit word, with `u32::MAX` for holes.
+pub fn board_bytes(size: Vec2, cards: &[Option<u32>]) -> Vec<u8> {
+    let words = [size.x as u32, size.y as u32]
+        .into_iter()
+        .chain(cards.iter().map(|card| card.unwrap_or(HOLE)));
+    words.flat_map(u32::to_be_bytes).collect()
+}
+
+/// The commitment to the board of `size` with `cards`, salted with `salt`.
+pub fn commitment(salt: &Salt, size: Vec2, cards: &[Option<u32>]) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update(salt);
+    hasher.update(board_bytes(size, cards));
+    hasher.finalize().into()
+}
+
+/// The host's side: the board committed to, kept until it is opened.
+pub struct Sealed {
+    salt: Salt,
+    size: Vec2,
+    cards: Vec<Option<u32>>,
+}
+
+impl Sealed {
+    /// Commit to the board of `size` with `cards`, salted with fresh random
+    /// bytes. The salt doesn't come from the game's random number
+    /// generator, as its seed is shared with the board code.
+    pub fn new(size: Vec2, cards: Vec<Option<u32>>) -> Sealed {
+        Sealed {
+            salt: StdRng::from_entropy().gen(),
+            size,
+            cards,
+        }
+    }
+
+    /// The hash to send before the first turn.
+    pub fn commitment(&self) -> Hash {
+        commitment(&self.salt, self.size, &self.cards)
+    }
+
+    /// The salt and the board to send once the last pair is found.
+    pub fn open(&self) -> (Salt, Vec<Option<u32>>) {
+        (self.salt, self.cards.clone())
+    }
+}
+
+/// The joining player's side: the commitment received from the host, and
+/// the cards it revealed since.
+pub struct Commitment {
+    hash: Hash,
+    size: Vec2,
+    seen: HashMap<Vec2, u32>,
+    /// Cleared if a position was revealed with different cards.
+    consistent: bool,
+}
+
+impl Commitment {
+    pub fn new(hash: Hash, size: Vec2) -> Commitment {
+        Commitment {
+            hash,
+            size,
+            seen: HashMap::new(),
+            consistent: true,
+        }
+    }
+
+    /// Note that the host revealed `card` at `at`.
+    pub fn saw(&mut self, at: Vec2, card: u32) {
+        if self.seen.insert(at, card).is_some_and(|seen| seen != card) {
+            self.consistent = false;
+        }
+    }
+
+    /// Check that the board opened with `salt` is the one committed to, and
+    /// that it holds every card revealed where it was revealed.
+    pub fn verify(&self, salt: &Salt, cards: &[Option<u32>]) -> bool {
+        let index = |at: &Vec2| (at.y * self.size.x + at.x) as usize;
+        self.consistent
+            && commitment(salt, self.size, cards) == self.hash
+            && self
+                .seen
+                .iter()
+                .all(|(at, card)| cards.get(index(at)) == Some(&Some(*card)))
+    }
+}
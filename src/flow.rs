@@ -0,0 +1,387 @@
+//! The states a game goes through, and the table of transitions between
+//! them. A [`Game`](crate::game::Game) only changes state by looking up a
+//! transition here, so frontends and tests can follow the same flow.
+
+use self::GameState::*;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameState {
+    /// Show the welcome screen
+    Welcome,
+    /// Prompt the user to pick a preset size for the board, or a custom one
+    SizeMenu,
+    /// Prompt the user to set the size of the board
+    SetDimensions,
+    /// Show the whole board face up before the first guess
+    Memorize,
+    /// Show the whole board face up for a limited time before the first
+    /// guess
+    Preview,
+    /// Prompt the user to pick a card to reveal
+    Guess,
+    /// Provide feedback about a correct guess
+    CorrectGuessConfirm,
+    /// Provide feedback about an incorrect guess
+    IncorrectGuessConfirm,
+    /// Provide feedback about a revealed bomb
+    BombExploded,
+    /// Show the stats and prompt for input
+    Victory,
+    /// Show the whole board after running out of lives and prompt for input
+    Defeat,
+    /// Prompt the user to pick a level of the campaign
+    LevelSelect,
+    /// Show the rules of the campaign level about to be played
+    LevelIntro,
+    /// Show the outcome of a cleared campaign level
+    LevelOutro,
+    /// Ask whether to abandon the game in progress and exit
+    ConfirmQuit,
+    /// Ask whether to abandon the game in progress and deal a new board
+    ConfirmRestart,
+    /// Prompt the user to pick a setting to change
+    Settings,
+    /// Ask whether to continue the board of a session that was interrupted
+    ConfirmRecover,
+    /// End the game
+    Exit,
+}
+
+impl GameState {
+    /// Every state, in the order they are listed.
+    pub const ALL: [GameState; 19] = [
+        Welcome,
+        SizeMenu,
+        SetDimensions,
+        Memorize,
+        Preview,
+        Guess,
+        CorrectGuessConfirm,
+        IncorrectGuessConfirm,
+        BombExploded,
+        Victory,
+        Defeat,
+        LevelSelect,
+        LevelIntro,
+        LevelOutro,
+        ConfirmQuit,
+        ConfirmRestart,
+        Settings,
+        ConfirmRecover,
+        Exit,
+    ];
+
+    /// The name of the state in snapshots and events.
+    pub fn name(self) -> &'static str {
+        match self {
+            Welcome => "welcome",
+            SizeMenu => "size_menu",
+            SetDimensions => "set_dimensions",
+            Memorize => "memorize",
+            Preview => "preview",
+            Guess => "guess",
+            CorrectGuessConfirm => "correct_guess",
+            IncorrectGuessConfirm => "incorrect_guess",
+            BombExploded => "bomb_exploded",
+            Victory => "victory",
+            Defeat => "defeat",
+            LevelSelect => "level_select",
+            LevelIntro => "level_intro",
+            LevelOutro => "level_outro",
+            ConfirmQuit => "confirm_quit",
+            ConfirmRestart => "confirm_restart",
+            Settings => "settings",
+            ConfirmRecover => "confirm_recover",
+            Exit => "exit",
+        }
+    }
+}
+
+/// Something that makes the game move to another state.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Trigger {
+    /// <Enter> on a screen that only shows something
+    Continue,
+    /// `y` to a yes/no question
+    Yes,
+    /// `n` to a yes/no question
+    No,
+    /// A new board was dealt
+    Deal,
+    /// The custom size was picked in the board size menu
+    CustomSize,
+    /// A level of the campaign was picked
+    PickLevel,
+    /// Go to the level selection of the campaign
+    Campaign,
+    /// A board saved earlier, or dealt by the host of a networked game, was
+    /// taken over
+    Restore,
+    /// The autosave of a session that was interrupted was found
+    Recover,
+    /// The two revealed cards form a pair
+    Match,
+    /// The two revealed cards don't form a pair
+    Mismatch,
+    /// The time for the guess ran out
+    TimeUp,
+    /// A bomb was revealed
+    Bomb,
+    /// The feedback about a guess was seen, and the board isn't over yet
+    NextTurn,
+    /// The last pair of the board was found, or matched with a power-up
+    Clear,
+    /// The last pair of a campaign level was found
+    ClearLevel,
+    /// The last life was lost
+    Lose,
+    /// The `:quit` command
+    Quit,
+    /// The `:restart`, `:rematch` or `:resize` command during a game
+    Restart,
+    /// The `:resize` command after a game, or its confirmation
+    Resize,
+    /// The `:settings` command
+    OpenSettings,
+    /// <Enter> in the settings menu
+    Back,
+    /// The game ends, e.g. because it was interrupted or has no more boards
+    /// to play
+    Exit,
+}
+
+/// The state a transition leads to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Target {
+    To(GameState),
+    /// The state the game was in before the last [`Effect::Remember`]
+    Resume,
+    /// The first state on a fresh board: [`Memorize`](GameState::Memorize),
+    /// [`Preview`](GameState::Preview) or [`Guess`](GameState::Guess),
+    /// depending on the rules
+    FirstTurn,
+}
+
+/// Something done along with a transition.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Effect {
+    /// Remember the state left, to go back to it with [`Target::Resume`]
+    Remember,
+    /// Start timing the next guess for scoring
+    StartTurn,
+    /// Start the countdown of the preview, or of the guess entered
+    StartClock,
+}
+
+/// A row of the transition table.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Transition {
+    /// The states the transition leaves from.
+    pub from: &'static [GameState],
+    pub trigger: Trigger,
+    pub to: Target,
+    pub effects: &'static [Effect],
+}
+
+/// States with a board being played, which can be abandoned.
+const IN_PROGRESS: &[GameState] = &[
+    Memorize,
+    Preview,
+    Guess,
+    CorrectGuessConfirm,
+    IncorrectGuessConfirm,
+    BombExploded,
+];
+
+const fn row(
+    from: &'static [GameState],
+    trigger: Trigger,
+    to: Target,
+    effects: &'static [Effect],
+) -> Transition {
+    Transition {
+        from,
+        trigger,
+        to,
+        effects,
+    }
+}
+
+/// Every transition of the game. The first row leaving from the current
+/// state on a trigger is the one taken.
+pub const TRANSITIONS: &[Transition] = &[
+    // Setting up a board
+    row(&[Welcome], Trigger::Continue, Target::To(SizeMenu), &[]),
+    row(
+        &[SizeMenu],
+        Trigger::CustomSize,
+        Target::To(SetDimensions),
+        &[],
+    ),
+    row(
+        &[
+            Welcome,
+            SizeMenu,
+            SetDimensions,
+            LevelIntro,
+            Victory,
+            Defeat,
+            ConfirmRestart,
+        ],
+        Trigger::Deal,
+        Target::FirstTurn,
+        &[Effect::StartClock],
+    ),
+    row(
+        &[Welcome, ConfirmRecover],
+        Trigger::Restore,
+        Target::To(Guess),
+        &[Effect::StartClock],
+    ),
+    row(
+        &[Welcome],
+        Trigger::Recover,
+        Target::To(ConfirmRecover),
+        &[],
+    ),
+    row(&[ConfirmRecover], Trigger::No, Target::To(Welcome), &[]),
+    // Playing a board
+    row(
+        &[Memorize, Preview],
+        Trigger::Continue,
+        Target::To(Guess),
+        &[Effect::StartTurn],
+    ),
+    row(
+        &[Guess],
+        Trigger::Match,
+        Target::To(CorrectGuessConfirm),
+        &[],
+    ),
+    row(
+        &[Guess],
+        Trigger::Mismatch,
+        Target::To(IncorrectGuessConfirm),
+        &[],
+    ),
+    row(
+        &[Guess],
+        Trigger::TimeUp,
+        Target::To(IncorrectGuessConfirm),
+        &[],
+    ),
+    row(&[Guess], Trigger::Bomb, Target::To(BombExploded), &[]),
+    row(
+        &[CorrectGuessConfirm, IncorrectGuessConfirm, BombExploded],
+        Trigger::NextTurn,
+        Target::To(Guess),
+        &[Effect::StartTurn],
+    ),
+    row(
+        &[CorrectGuessConfirm, Guess],
+        Trigger::Clear,
+        Target::To(Victory),
+        &[],
+    ),
+    row(
+        &[CorrectGuessConfirm, Guess],
+        Trigger::ClearLevel,
+        Target::To(LevelOutro),
+        &[],
+    ),
+    row(
+        &[IncorrectGuessConfirm, BombExploded],
+        Trigger::Lose,
+        Target::To(Defeat),
+        &[],
+    ),
+    // After a board
+    row(&[Victory, Defeat], Trigger::Yes, Target::To(SizeMenu), &[]),
+    row(&[Victory, Defeat], Trigger::No, Target::To(Exit), &[]),
+    // The campaign
+    row(
+        &[Welcome, Defeat, LevelOutro],
+        Trigger::Campaign,
+        Target::To(LevelSelect),
+        &[],
+    ),
+    row(
+        &[LevelSelect],
+        Trigger::PickLevel,
+        Target::To(LevelIntro),
+        &[],
+    ),
+    row(&[LevelOutro], Trigger::Yes, Target::To(LevelIntro), &[]),
+    row(&[LevelOutro], Trigger::No, Target::To(LevelSelect), &[]),
+    // Commands
+    row(
+        IN_PROGRESS,
+        Trigger::Quit,
+        Target::To(ConfirmQuit),
+        &[Effect::Remember],
+    ),
+    row(&GameState::ALL, Trigger::Quit, Target::To(Exit), &[]),
+    row(&[ConfirmQuit], Trigger::Yes, Target::To(Exit), &[]),
+    row(
+        IN_PROGRESS,
+        Trigger::Restart,
+        Target::To(ConfirmRestart),
+        &[Effect::Remember],
+    ),
+    row(
+        &[Victory, Defeat, ConfirmRestart],
+        Trigger::Resize,
+        Target::To(SizeMenu),
+        &[],
+    ),
+    row(
+        &[ConfirmQuit, ConfirmRestart],
+        Trigger::No,
+        Target::Resume,
+        &[],
+    ),
+    row(
+        &[
+            Welcome,
+            SizeMenu,
+            SetDimensions,
+            Memorize,
+            Preview,
+            Guess,
+            CorrectGuessConfirm,
+            IncorrectGuessConfirm,
+            BombExploded,
+            Victory,
+            Defeat,
+            LevelSelect,
+            LevelIntro,
+            LevelOutro,
+        ],
+        Trigger::OpenSettings,
+        Target::To(Settings),
+        &[Effect::Remember],
+    ),
+    row(&[Settings], Trigger::Back, Target::Resume, &[]),
+    row(&GameState::ALL, Trigger::Exit, Target::To(Exit), &[]),
+];
+
+/// The transition taken from `from` on `trigger`, if there is one.
+pub fn transition(
+    from: GameState,
+    trigger: Trigger,
+) -> Option<&'static Transition> {
+    TRANSITIONS
+        .iter()
+        .find(|t| t.trigger == trigger && t.from.contains(&from))
+}
+
+/// The triggers that lead somewhere from `from`, each listed once.
+pub fn triggers(from: GameState) -> Vec<Trigger> {
+    let mut triggers = Vec::new();
+    for t in TRANSITIONS.iter().filter(|t| t.from.contains(&from)) {
+        if !triggers.contains(&t.trigger) {
+            triggers.push(t.trigger);
+        }
+    }
+    triggers
+}
@@ -1,176 +1,328 @@
 use std::{
-    io::{self, stdin, Write},
-    ops::{Index, IndexMut},
+    cmp::Ordering,
+    collections::{HashMap, VecDeque},
+    io, mem,
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
 };
 
 use bitvec::{bitvec, vec::BitVec};
-use rand::{seq::SliceRandom, thread_rng};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, RngCore, SeedableRng};
+use serde::Serialize;
+use unicode_width::UnicodeWidthStr;
 
-use self::GameState::*;
+use crate::analysis::{self, Guess, Outcome, Pick, Verdict};
+use crate::board::{Board, Card, Idx2d, Pairing, Suit, Vec2};
+use crate::campaign::{Campaign, LEVELS};
+use crate::challenge::ChallengeResult;
+use crate::chat::{self, Chat};
+use crate::clock::Instant;
+use crate::code::BoardCode;
+use crate::color::{self, ColorDepth, Rgb};
+use crate::config::{
+    self, BombEffect, CardSet, ColorMode, Config, Difficulty, Pacing,
+};
+use crate::daily::{Daily, DailyLog};
+use crate::elo::{self, MatchResult, RatingStore};
 use crate::error::{GameError, Result};
+use crate::event::{Event, Observer};
+use crate::fair::{Commitment, Sealed};
+use crate::flow::{self, Effect, GameState, GameState::*, Target, Trigger};
+use crate::graphics::Graphics;
+use crate::grid::{Grid, Layout};
+use crate::heatmap;
+use crate::input;
+use crate::keymap::{Action, Keymap};
+use crate::net::{self, Connection, Message, RoomState};
+use crate::power::{Inventory, PowerUp};
+use crate::probability::{self, InfoSet};
+use crate::profile;
+use crate::protocol::Snapshot;
+use crate::rating;
+use crate::replay::{Move, Replay};
+use crate::save::SavedGame;
+use crate::scoring::{Score, ScoringRules};
+use crate::screen::{self, Frame, Screen, Stroke};
+use crate::shape::Shape;
+use crate::solver;
+use crate::stats::{format_duration, GameRecord, StatsStore};
+use crate::style::Style;
+use crate::t;
+use crate::terminal;
+use crate::theme::Theme;
+use crate::tournament::{RoundResult, Tournament};
+use crate::view::{self, Cell, CellStatus, GameView, Prompt, ScoreView};
 
+/// A size offered in the [board size menu](`GameState::SizeMenu`).
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
-enum GameState {
-    /// Show the welcome screen
-    Welcome,
-    /// Prompt the user to set the size of the board
-    SetDimensions,
-    /// Prompt the user to pick a card to reveal
-    Guess,
-    /// Provide feedback about a correct guess
-    CorrectGuessConfirm,
-    /// Provide feedback about an incorrect guess
-    IncorrectGuessConfirm,
-    /// Show the stats and prompt for input
-    Victory,
-    /// End the game
-    Exit,
-}
-
-#[derive(Clone, Copy, PartialEq, Eq)]
-struct Card(char);
-
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-struct Vec2 {
-    pub x: i32,
-    pub y: i32,
+enum SizePreset {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
 }
 
-/// An object used to convert coordinates (2D index) into an array (1D) index.
-struct Idx2d {
-    pub size_x: i32,
-    pub size_y: i32,
-}
+impl SizePreset {
+    /// Every preset, in the order they are listed.
+    const ALL: [SizePreset; 4] = [
+        SizePreset::Easy,
+        SizePreset::Medium,
+        SizePreset::Hard,
+        SizePreset::Expert,
+    ];
 
-impl Idx2d {
-    /// Create a new indexer object with the given column/row counts.
-    pub fn new(size_x: i32, size_y: i32) -> Idx2d {
-        Idx2d { size_x, size_y }
-    }
+    /// The preset picked by pressing <Enter>.
+    const DEFAULT: SizePreset = SizePreset::Medium;
 
-    /// Convert coordinates into an array index with bounds checking. If
-    /// the coordinates don't map to an array element defined by the stored
-    /// sizes, return an `Err`.
-    pub fn of(&self, coords: Vec2) -> Result<usize> {
-        let Vec2 { x, y } = coords;
-        if x < 0 {
-            return Err(GameError::CoordinateUnderflow { axis: 'x' });
+    /// The name of the preset in the menu.
+    fn name(self) -> String {
+        match self {
+            SizePreset::Easy => t!("size-easy"),
+            SizePreset::Medium => t!("size-medium"),
+            SizePreset::Hard => t!("size-hard"),
+            SizePreset::Expert => t!("size-expert"),
         }
-        if y < 0 {
-            return Err(GameError::CoordinateUnderflow { axis: 'y' });
-        }
-        if x >= self.size_x {
-            return Err(GameError::CoordinateOverflow {
-                axis: 'x',
-                max: self.size_x,
-            });
-        }
-        if y >= self.size_y {
-            return Err(GameError::CoordinateOverflow {
-                axis: 'y',
-                max: self.size_y,
-            });
-        }
-        Ok(self.unchecked(coords))
     }
 
-    /// Convert coordinates into an array index without bounds checking.
-    pub fn unchecked(&self, coords: Vec2) -> usize {
-        let Vec2 { x, y } = coords;
-        (y * self.size_x + x) as usize
+    /// The number of columns and rows of the board.
+    fn size(self) -> Vec2 {
+        match self {
+            SizePreset::Easy => Vec2 { x: 4, y: 3 },
+            SizePreset::Medium => Vec2 { x: 6, y: 4 },
+            SizePreset::Hard => Vec2 { x: 8, y: 6 },
+            SizePreset::Expert => Vec2 { x: 10, y: 10 },
+        }
     }
+}
 
-    /// Iterate through all the possible coordinates - defined by `size_x`
-    /// and `size_y` - in row major order.
-    pub fn iter_all(&self) -> impl Iterator<Item = Vec2> + '_ {
-        let max = self.size_x * self.size_y;
-        (0..max).map(|i| {
-            let x = i % self.size_x;
-            let y = i / self.size_x;
-            Vec2 { x, y }
-        })
-    }
+/// A screen drawn over the current state, which is left as it is while the
+/// overlay is shown. The next input closes it, whatever it is.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Overlay {
+    /// How to pick cards, the commands and the rules of the game
+    Help,
+    /// The keys that stand in for commands
+    Keys,
 }
 
-/// A board of playing cards
-struct Board {
-    idx: Idx2d,
-    pub cards: Vec<Card>,
+/// An entry of the [settings menu](`GameState::Settings`).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Setting {
+    Colors,
+    CardSet,
+    Difficulty,
+    Sound,
 }
 
-impl Board {
-    /// Symbols to use as "cards"
-    const CARD_CHARS: [char; 55] = [
-        '☀', '☁', '★', '☇', '☈', '☉', '☊', '☋', '☌', '☍', '☎', '☔', '☕', '☗',
-        '☘', '☙', '☚', '☛', '☝', '☠', '☡', '☢', '☣', '☤', '☥', '☦', '☧', '☩',
-        '☫', '☬', '☭', '☮', '☯', '☼', '☿', '♀', '♁', '♂', '♃', '♄', '♅', '♆',
-        '♇', '♈', '♉', '♊', '♋', '♌', '♍', '♎', '♏', '♐', '♑', '♒',
-        '♓',
+impl Setting {
+    /// Every setting, in the order they are listed.
+    const ALL: [Setting; 4] = [
+        Setting::Colors,
+        Setting::CardSet,
+        Setting::Difficulty,
+        Setting::Sound,
     ];
 
-    /// Maximum possible board size
-    const MAX_SIZE: i32 = (Board::CARD_CHARS.len() * 2) as i32;
-
-    /// Create a new board with the given sizes and fill it randomly with cards
-    /// from the [predefined list](`Board::CARD_CHARS`).
-    pub fn new(size_x: i32, size_y: i32) -> Result<Board> {
-        debug_assert!(size_x > 0);
-        debug_assert!(size_y > 0);
-        debug_assert!((size_x * size_y) % 2 == 0);
+    /// The name of the setting in the menu.
+    fn name(self) -> String {
+        match self {
+            Setting::Colors => t!("setting-colors"),
+            Setting::CardSet => t!("setting-card-set"),
+            Setting::Difficulty => t!("setting-difficulty"),
+            Setting::Sound => t!("setting-sound"),
+        }
+    }
+}
 
-        let size = (size_x * size_y) as usize;
-        let mut board = Board {
-            idx: Idx2d::new(size_x, size_y),
-            cards: vec![Card('\0'); size],
-        };
+/// How the next board is dealt when the current one is abandoned.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Redeal {
+    /// Shuffle a new board of the same shape, with `:restart`
+    Shuffle,
+    /// Deal the same cards in the same places again, with `:rematch`
+    Rematch,
+    /// Pick another size first, with `:resize`
+    Resize,
+}
 
-        // Find all coordinates of all the available spaces
-        let mut rng = thread_rng();
-        let mut coords: Vec<_> = board.idx.iter_all().collect();
-        coords.shuffle(&mut rng);
+impl Redeal {
+    /// The name of the command that deals the board this way.
+    fn command(self) -> &'static str {
+        match self {
+            Redeal::Shuffle => "restart",
+            Redeal::Rematch => "rematch",
+            Redeal::Resize => "resize",
+        }
+    }
+}
 
-        // Length is always even, OK to split in two
-        let chunk_size = coords.len() / 2;
-        let [first_half, second_half]: [&[Vec2]; 2] = coords
-            .chunks(chunk_size)
-            .collect::<Vec<_>>()
-            .try_into()
-            .unwrap();
+/// A command typed at any prompt with a `:` prefix, e.g. `:quit`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum PromptCommand {
+    Quit,
+    Redeal(Redeal),
+    Hint,
+    Odds,
+    Save,
+    Help,
+    Theme,
+    Heatmap,
+    Settings,
+    Keys,
+    Numbers,
+    Luck,
+    Say,
+    Power(PowerUp),
+}
 
-        // Assign a card to each pair of spaces
-        for (i, (c1, c2)) in first_half.iter().zip(second_half).enumerate() {
-            let card = Card(Board::CARD_CHARS[i]);
+impl PromptCommand {
+    /// Parse a command without its `:` prefix.
+    fn parse(s: &str) -> Result<PromptCommand> {
+        match s.trim().to_lowercase().as_str() {
+            "q" | "quit" => Ok(PromptCommand::Quit),
+            "restart" => Ok(PromptCommand::Redeal(Redeal::Shuffle)),
+            "rematch" => Ok(PromptCommand::Redeal(Redeal::Rematch)),
+            "resize" => Ok(PromptCommand::Redeal(Redeal::Resize)),
+            "hint" => Ok(PromptCommand::Hint),
+            "odds" => Ok(PromptCommand::Odds),
+            "save" => Ok(PromptCommand::Save),
+            "help" => Ok(PromptCommand::Help),
+            "theme" => Ok(PromptCommand::Theme),
+            "heatmap" => Ok(PromptCommand::Heatmap),
+            "settings" => Ok(PromptCommand::Settings),
+            "keys" => Ok(PromptCommand::Keys),
+            "numbers" => Ok(PromptCommand::Numbers),
+            "luck" => Ok(PromptCommand::Luck),
+            "say" => Ok(PromptCommand::Say),
+            other => PowerUp::parse(other).map(PromptCommand::Power),
+        }
+    }
+}
 
-            let cell1 = &mut board[*c1];
-            *cell1 = card;
+/// A card being turned over.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Flip {
+    /// Position of the card.
+    at: Vec2,
+    /// `true` if the card is being turned face up, `false` if face down.
+    reveal: bool,
+    /// Set if the card was part of a mismatch, and flashes before it is
+    /// turned face down.
+    flash: bool,
+}
 
-            let cell2 = &mut board[*c2];
-            *cell2 = card;
+impl Flip {
+    /// Turn the card at `at` face up.
+    fn show(at: Vec2) -> Flip {
+        Flip {
+            at,
+            reveal: true,
+            flash: false,
         }
-
-        Ok(board)
     }
 
-    /// Create an empty board with 0 size.
-    pub fn default() -> Board {
-        Board {
-            idx: Idx2d::new(0, 0),
-            cards: Vec::new(),
+    /// Turn the card at `at` face down.
+    fn hide(at: Vec2) -> Flip {
+        Flip {
+            at,
+            reveal: false,
+            flash: false,
         }
     }
 }
 
-impl Index<Vec2> for Board {
-    type Output = Card;
+/// The state of a game shared with an opponent over the network.
+struct Multiplayer {
+    /// The connection to the opponent.
+    conn: Connection,
+    /// The index of the local player. The host is player 0.
+    local: usize,
+    /// Set if only watching the game of a room of a
+    /// [lobby](`crate::lobby`), without a player of our own.
+    spectating: bool,
+    /// The index of the player whose turn it is.
+    turn: usize,
+    /// Number of pairs found by each player.
+    pairs: [i32; 2],
+    /// The board the host committed to, to be opened once it is cleared.
+    sealed: Option<Sealed>,
+    /// The commitment of the host to the board, on the joining side.
+    commitment: Option<Commitment>,
+    /// Whether the board the host opened was the one committed to, on the
+    /// joining side, once it was opened.
+    verified: Option<bool>,
+    /// The lines of chat exchanged with the opponent.
+    chat: Chat,
+    /// Whether the local player introduced themselves to the opponent.
+    introduced: bool,
+    /// The name and rating the opponent introduced themselves with. The
+    /// name is empty if it couldn't be that of a
+    /// [profile](`crate::profile`).
+    opponent: Option<(String, u32)>,
+}
+
+impl Multiplayer {
+    fn new(conn: Connection, local: usize) -> Multiplayer {
+        Multiplayer {
+            conn,
+            local,
+            spectating: false,
+            turn: 0,
+            pairs: [0, 0],
+            sealed: None,
+            commitment: None,
+            verified: None,
+            chat: Chat::default(),
+            introduced: false,
+            opponent: None,
+        }
+    }
 
-    fn index(&self, index: Vec2) -> &Self::Output {
-        &self.cards[self.idx.unchecked(index)]
+    /// Check if the local player is the one picking cards.
+    fn is_local_turn(&self) -> bool {
+        !self.spectating && self.turn == self.local
+    }
+
+    /// Note a line of chat from the player in `seat`. A player can only
+    /// hear their opponent, whatever seat the line claims to come from.
+    fn hear(&mut self, seat: usize, text: &str) {
+        let seat = if self.spectating {
+            seat
+        } else {
+            1 - self.local
+        };
+        self.chat.add(seat, text, Instant::now());
     }
-}
 
-impl IndexMut<Vec2> for Board {
-    fn index_mut(&mut self, index: Vec2) -> &mut Self::Output {
-        &mut self.cards[self.idx.unchecked(index)]
+    /// Note a message that doesn't move the game on: a line of chat, or
+    /// the opponent introducing themselves. Any other message is handed
+    /// back.
+    fn aside(&mut self, msg: Message) -> Option<Message> {
+        match msg {
+            Message::Chat { seat, text } => self.hear(seat, &text),
+            Message::Player { name, rating } if !self.spectating => {
+                let name = if profile::is_valid_name(&name) {
+                    name
+                } else {
+                    String::new()
+                };
+                self.opponent.get_or_insert((name, rating));
+            }
+            msg => return Some(msg),
+        }
+        None
+    }
+
+    /// Block until the next message that moves the game on arrives, noting
+    /// the [other messages](`Multiplayer::aside`) that arrive before it.
+    fn recv(&mut self) -> io::Result<Message> {
+        loop {
+            let msg = self.conn.recv()?;
+            if let Some(msg) = self.aside(msg) {
+                return Ok(msg);
+            }
+        }
     }
 }
 
@@ -197,10 +349,243 @@ pub struct Game {
     revealed2: Option<Vec2>,
     /// An error encountered during user input parsing.
     error: Option<GameError>,
+    /// Source of randomness used to shuffle new boards.
+    rng: Box<dyn RngCore>,
+    /// The opponent, if playing over the network.
+    multiplayer: Option<Multiplayer>,
+    /// A card revealed by the opponent, waiting to be applied.
+    remote_reveal: Option<Vec2>,
+    /// The state of the room being watched, waiting to be applied.
+    remote_state: Option<RoomState>,
+    /// The seat whose turn ran out of time according to the
+    /// [lobby](`crate::lobby`), waiting to be applied.
+    remote_expiry: Option<usize>,
+    /// Number of hints used on the current board.
+    hints: i32,
+    /// Number of guesses added to the count for every hint.
+    hint_penalty: i32,
+    /// Cards shown by the last hint. Hidden again on the next update.
+    hinted: Vec<Vec2>,
+    /// The chance of each face-down card matching the one picked, shown by
+    /// `:odds`. Hidden again on the next update.
+    odds: HashMap<Vec2, f64>,
+    /// The rule preset.
+    difficulty: Difficulty,
+    /// Number of incorrect guesses on the current board.
+    mismatches: i32,
+    /// Set if the undiscovered cards were reshuffled after the last guess.
+    reshuffled: bool,
+    /// Set if the player is told about the last reshuffle, rather than it
+    /// happening silently.
+    reshuffle_announced: bool,
+    /// Silently reshuffle the hidden cards after every this many turns, if
+    /// set.
+    shuffle_every: Option<u32>,
+    /// Number of guesses completed on the current board, not counting hint
+    /// penalties.
+    turns: u32,
+    /// Matched cards are turned face down again after this many more
+    /// turns, unless the board is cleared first, if set.
+    decay: Option<u32>,
+    /// The number of turns since the card at each position was matched, in
+    /// row major order. `None` for cards that aren't matched, holes and
+    /// cards matched on a layer that has been removed since.
+    ages: Vec<Option<u32>>,
+    /// Matched cards turned face down again after the last guess.
+    decayed: Vec<Vec2>,
+    /// Time each guess has to be made in, if limited.
+    time_limit: Option<Duration>,
+    /// When the current guess runs out of time.
+    guess_deadline: Option<Instant>,
+    /// Set if the last guess ran out of time.
+    timed_out: bool,
+    /// The seat of a networked game whose turn ran out of time in the last
+    /// update, if any.
+    turn_expired: Option<usize>,
+    /// When the current board was dealt.
+    started: Option<Instant>,
+    /// Time taken to clear the current board.
+    finish_time: Option<Duration>,
+    /// Time spent on the current board when it was cleared or lost, once
+    /// the clock has stopped.
+    stopped: Option<Duration>,
+    /// Where finished games are recorded, if anywhere.
+    stats: Option<StatsStore>,
+    /// The [rating](`crate::elo`) of the local player, updated after every
+    /// networked game, if kept anywhere.
+    ratings: Option<RatingStore>,
+    /// The rating from before and after the last finished networked game.
+    rating_change: Option<(f64, f64)>,
+    /// Set if the last finished game was the fastest on its board size.
+    new_best: bool,
+    /// The daily challenge being played, and where its result is recorded.
+    daily: Option<(Daily, DailyLog)>,
+    /// Set while the tutorial is played.
+    tutorial: bool,
+    /// Set if the result of the daily challenge was recorded, i.e. it was
+    /// the first attempt of the day.
+    daily_recorded: bool,
+    /// Every guess on the current board, oldest first.
+    history: Vec<Guess>,
+    /// The cards the players have seen face up and not matched yet, where
+    /// they were seen.
+    seen: HashMap<Vec2, Card>,
+    /// Cards being turned over since the last update.
+    flips: Vec<Flip>,
+    /// The current frame of the flip animation.
+    flip_frame: usize,
+    /// Code reacting to the events of the game, e.g. sound effects.
+    observers: Vec<Box<dyn Observer>>,
+    /// The formula for awarding points.
+    scoring: ScoringRules,
+    /// Points earned on the current board.
+    score: Score,
+    /// Points awarded for the last pair found, or lost to the last bomb.
+    last_points: u64,
+    /// How long the board is shown before the first guess, if at all.
+    preview: Option<Duration>,
+    /// When the preview of the current board ends.
+    preview_until: Option<Instant>,
+    /// The terminal, along with what was last drawn on it.
+    screen: Screen,
+    /// Top left corner of the part of the board that is shown, if it
+    /// doesn't fit in the terminal.
+    view: Vec2,
+    /// Coordinates entered along with the last input, waiting to be applied
+    /// in order.
+    queued: VecDeque<String>,
+    /// The state to return to if the player doesn't confirm a command.
+    resume: GameState,
+    /// How the next board is dealt once the player confirms abandoning the
+    /// current one.
+    redeal: Redeal,
+    /// The screen drawn over the current state until the next input, if
+    /// any.
+    overlay: Option<Overlay>,
+    /// The keys standing in for commands.
+    keymap: Keymap,
+    /// Where changes made in the settings menu are saved, if anywhere.
+    config_file: Option<PathBuf>,
+    /// Where the game is saved when quitting, if anywhere.
+    save_file: Option<PathBuf>,
+    /// Where the board in play is kept up to date, if anywhere, to be
+    /// recovered if the session is interrupted.
+    autosave_file: Option<PathBuf>,
+    /// The board of an interrupted session offered to be continued.
+    recovered: Option<SavedGame>,
+    /// Where the moves made on the first board dealt are recorded, if
+    /// anywhere.
+    replay_file: Option<PathBuf>,
+    /// The moves recorded so far, and when the board was dealt.
+    recording: Option<(Replay, Instant)>,
+    /// Set once the recorded board is over, leaving later boards out.
+    recording_done: bool,
+    /// Set if the input is the next step of a batch of guesses, which is
+    /// not recorded on its own as the whole batch already was.
+    input_queued: bool,
+    /// Set if sound effects are turned off.
+    muted: bool,
+    /// Set if the [heatmap](`crate::heatmap`) of past games is shown in
+    /// place of the cleared board.
+    show_heatmap: bool,
+    /// Describe the board in words instead of drawing it, for screen
+    /// readers.
+    accessible: bool,
+    /// How colors and text attributes are drawn, if at all.
+    style: Style,
+    /// Set if the terminal can draw characters beyond ASCII.
+    unicode: bool,
+    /// The colors of the parts of the screen.
+    theme: Theme,
+    /// Set if the colors with the most contrast are used, whatever the
+    /// theme.
+    high_contrast: bool,
+    /// Set if nothing is animated or flashes.
+    reduced_motion: bool,
+    /// Set if the board is always drawn in the
+    /// [compact layout](`Game::set_compact`).
+    compact: bool,
+    /// Set if face-down cells show their [number](`Idx2d::number`).
+    cell_numbers: bool,
+    /// Set if the [luck meter](`Game::set_luck_meter`) is shown.
+    luck_meter: bool,
+    /// How the game moves on from the feedback about a guess.
+    pacing: Pacing,
+    /// When the feedback about the last guess moves on by itself, with
+    /// [`Pacing::Fast`].
+    feedback_until: Option<Instant>,
+    /// The protocol for drawing the cards as images, if the terminal has
+    /// one.
+    graphics: Option<Graphics>,
+    /// How the cells of the board are arranged.
+    layout: Layout,
+    /// Number of layers of cards stacked on each cell.
+    layers: usize,
+    /// The kind of cards dealt.
+    card_set: CardSet,
+    /// Number of jokers dealt on single layer boards.
+    jokers: usize,
+    /// Number of bombs dealt on single layer boards.
+    bombs: usize,
+    /// What happens when a bomb is revealed.
+    bomb_effect: BombEffect,
+    /// Number of steps the cards of a pair are kept apart when dealing.
+    min_pair_distance: i32,
+    /// The layers of cards under [`Game::board`], from the top down. A card
+    /// is exposed when the one above it is matched.
+    under: Vec<Board>,
+    /// The 0-based layer of the card on top of each cell, in row major
+    /// order.
+    depth: Vec<usize>,
+    /// The seed of [`Game::rng`], if it was seeded.
+    seed: Option<u64>,
+    /// The code of the current board as it was dealt.
+    dealt: Option<BoardCode>,
+    /// The result of a friend on the same board, to compare with.
+    rival: Option<ChallengeResult>,
+    /// The tournament being played, if any. Every board is a round.
+    tournament: Option<Tournament>,
+    /// The progress of the campaign being played, if any.
+    campaign: Option<Campaign>,
+    /// The 0-based campaign level picked or being played.
+    level: usize,
+    /// Set if clearing the last campaign level unlocked the next one.
+    level_unlocked: bool,
+    /// Number of incorrect guesses allowed on a board, if limited.
+    lives: Option<u32>,
+    /// The power-ups held for the current board.
+    power_ups: Inventory,
+    /// The power-up earned by the last guess, if any.
+    earned: Option<PowerUp>,
+    /// Cards shown by the last peek, until [`Game::peek_until`].
+    peeked: Vec<Vec2>,
+    /// When the cards shown by the last peek are hidden again.
+    peek_until: Option<Instant>,
+    /// When the last freeze of the clock ends.
+    freeze_until: Option<Instant>,
+    /// Total time the clock was frozen for on the current board, including
+    /// the rest of a freeze still in effect.
+    frozen: Duration,
 }
 
 impl Game {
+    /// Create a game with a random seed, which ends up in its
+    /// [board codes](`Game::board_code`).
     pub fn new() -> Game {
+        Game::with_seed(StdRng::from_entropy().gen())
+    }
+
+    /// Create a game whose boards are shuffled deterministically from
+    /// `seed`.
+    pub fn with_seed(seed: u64) -> Game {
+        let mut game = Game::with_rng(StdRng::seed_from_u64(seed));
+        game.seed = Some(seed);
+        game
+    }
+
+    /// Create a game whose boards are shuffled with `rng`, e.g. a seeded
+    /// one for reproducible boards.
+    pub fn with_rng(rng: impl RngCore + 'static) -> Game {
         Game {
             state: Welcome,
             user_input: String::new(),
@@ -211,318 +596,4693 @@ impl Game {
             revealed1: None,
             revealed2: None,
             error: None,
+            rng: Box::new(rng),
+            multiplayer: None,
+            remote_reveal: None,
+            remote_state: None,
+            remote_expiry: None,
+            hints: 0,
+            hint_penalty: Game::DEFAULT_HINT_PENALTY,
+            hinted: Vec::new(),
+            odds: HashMap::new(),
+            difficulty: Difficulty::Normal,
+            mismatches: 0,
+            reshuffled: false,
+            reshuffle_announced: false,
+            shuffle_every: None,
+            turns: 0,
+            decay: None,
+            ages: Vec::new(),
+            decayed: Vec::new(),
+            time_limit: None,
+            guess_deadline: None,
+            timed_out: false,
+            turn_expired: None,
+            started: None,
+            finish_time: None,
+            stopped: None,
+            stats: None,
+            ratings: None,
+            rating_change: None,
+            new_best: false,
+            daily: None,
+            tutorial: false,
+            daily_recorded: false,
+            history: Vec::new(),
+            seen: HashMap::new(),
+            flips: Vec::new(),
+            flip_frame: 0,
+            observers: Vec::new(),
+            scoring: ScoringRules::default(),
+            score: Score::new(Instant::now()),
+            last_points: 0,
+            preview: None,
+            preview_until: None,
+            screen: Screen::new(),
+            view: Vec2 { x: 0, y: 0 },
+            queued: VecDeque::new(),
+            resume: Welcome,
+            redeal: Redeal::Shuffle,
+            overlay: None,
+            keymap: Keymap::default(),
+            config_file: None,
+            save_file: None,
+            autosave_file: None,
+            recovered: None,
+            replay_file: None,
+            recording: None,
+            recording_done: false,
+            input_queued: false,
+            muted: false,
+            show_heatmap: false,
+            accessible: false,
+            style: Style::PLAIN,
+            unicode: true,
+            theme: Theme::DEFAULT,
+            high_contrast: false,
+            reduced_motion: false,
+            compact: false,
+            cell_numbers: false,
+            luck_meter: false,
+            pacing: Pacing::Enter,
+            feedback_until: None,
+            graphics: None,
+            layout: Layout::Square,
+            layers: 1,
+            card_set: CardSet::Symbols,
+            jokers: 0,
+            bombs: 0,
+            bomb_effect: BombEffect::Penalty,
+            min_pair_distance: 1,
+            seed: None,
+            dealt: None,
+            rival: None,
+            tournament: None,
+            campaign: None,
+            level: 0,
+            level_unlocked: false,
+            lives: None,
+            power_ups: Inventory::default(),
+            earned: None,
+            peeked: Vec::new(),
+            peek_until: None,
+            freeze_until: None,
+            frozen: Duration::ZERO,
+            under: Vec::new(),
+            depth: Vec::new(),
         }
     }
 
-    pub fn is_running(&self) -> bool {
-        self.state != Exit
+    /// Notify `observer` of every [`Event`] from now on.
+    pub fn subscribe(&mut self, observer: impl Observer + 'static) {
+        self.observers.push(Box::new(observer));
     }
 
-    /// Read input from `stdin`.
-    pub fn grab_input(&mut self) -> io::Result<()> {
-        self.user_input.clear();
-        stdin().read_line(&mut self.user_input)?;
-        Ok(())
+    /// Award points for found pairs according to `rules`.
+    pub fn set_scoring(&mut self, rules: ScoringRules) {
+        self.scoring = rules;
     }
 
-    /// Update the game based on the latest result from [`Game::grab_input`].
-    pub fn update(&mut self) {
-        self.error = None;
+    /// Show the whole board for `time` before the first guess of every solo
+    /// game. The preview can be ended early with <Enter>.
+    pub fn set_preview(&mut self, time: Duration) {
+        self.preview = Some(time);
+    }
 
+    /// The moment the game moves on by itself if there is no input, if it
+    /// is waiting for one. Pass an empty input to [`Game::update`] then.
+    pub fn deadline(&self) -> Option<Instant> {
         match self.state {
-            Welcome => self.state = SetDimensions,
-            SetDimensions => match self.set_dimensions() {
-                Ok(_) => self.state = Guess,
-                Err(e) => self.error = Some(e),
-            },
-            Guess => {
-                let c = match self.parse_coords(&self.user_input) {
-                    Ok(c) => c,
-                    Err(e) => {
-                        self.error = Some(e);
-                        return;
-                    }
-                };
-                if self.is_revealed(c) || self.is_discovered(c) {
-                    self.error = Some(GameError::AlreadyRevealed {
-                        x: c.x + 1,
-                        y: c.y + 1,
-                    });
-                    return;
-                }
-                if self.can_reveal() {
-                    self.set_revealed(c);
-                }
-                if !self.can_reveal() {
-                    if self.revealed_match() {
-                        self.state = CorrectGuessConfirm;
-                    } else {
-                        self.state = IncorrectGuessConfirm;
-                    }
-                }
-            }
-            CorrectGuessConfirm => {
-                self.set_discovered(self.revealed1.unwrap());
-                self.set_discovered(self.revealed2.unwrap());
-                self.inc_guesses();
-                self.clear_revealed();
-
-                if self.all_discovered() {
-                    self.state = Victory
-                } else {
-                    self.state = Guess;
-                }
-            }
-            IncorrectGuessConfirm => {
-                self.inc_guesses();
-                self.clear_revealed();
-                self.state = Guess;
+            Preview => self.preview_until,
+            Guess => self.guess_deadline,
+            CorrectGuessConfirm | IncorrectGuessConfirm | BombExploded => {
+                self.feedback_until
             }
-            Victory => match self.parse_yn(&self.user_input) {
-                Ok(true) => self.state = SetDimensions,
-                Ok(false) => self.state = Exit,
-                Err(e) => self.error = Some(e),
-            },
-            _ => {}
+            _ => None,
         }
     }
 
-    /// Render the current state.
-    pub fn render(&self) {
-        self.render_clear();
+    /// Check if the feedback about a guess is on screen.
+    fn shows_feedback(&self) -> bool {
+        matches!(
+            self.state,
+            CorrectGuessConfirm | IncorrectGuessConfirm | BombExploded
+        )
+    }
 
-        match self.state {
-            Welcome => {
-                println!("Welcome! Press <Enter> to begin.");
-            }
-            SetDimensions => {
-                self.render_error();
-                println!("Set board dimensions (x, y)");
-                print!("> ");
-                io::stdout().flush().unwrap();
-            }
-            Guess => {
-                self.render_score();
-                self.render_board();
-                self.render_error();
-                println!("Pick a card (x, y)");
-                print!("> ");
-                io::stdout().flush().unwrap();
-            }
-            CorrectGuessConfirm => {
-                self.render_score();
-                self.render_board();
-                println!("A match!");
-            }
-            IncorrectGuessConfirm => {
-                self.render_score();
-                self.render_board();
-                println!("Try again")
-            }
-            Victory => {
-                self.render_score();
-                self.render_board();
-                self.render_error();
-                println!("Congratulations! Play again? (y / N)");
-                print!("> ");
-                io::stdout().flush().unwrap();
-            }
-            _ => {}
-        }
+    /// Check if a single key press moves the game on, rather than a line
+    /// of input, because of the [pacing](`Game::set_pacing`).
+    pub fn awaits_key(&self) -> bool {
+        self.pacing != Pacing::Enter
+            && self.shows_feedback()
+            && self.queued.is_empty()
     }
 
-    /// Attempt to parse a pair of i32 numbers from the string slice.
-    /// Accepts `x,y` and `x;y` formats with any amount of whitespace.
-    fn parse_pair(s: &str) -> Result<Vec2> {
-        if s.is_empty() {
-            return Err(GameError::EmptyInput);
-        }
+    /// When the board has to be redrawn next without any input, e.g. to
+    /// hide the cards shown by a peek, if at all.
+    pub fn redraw_at(&self) -> Option<Instant> {
+        let now = Instant::now();
+        // Whenever the countdown of the guess goes down by a second. A
+        // screen reader would read out every one of them, and without
+        // escape codes every redraw is printed below the last one.
+        let ticking = !self.accessible && terminal::is_ansi();
+        let countdown = self.guess_deadline.filter(|_| ticking).map(|t| {
+            let left = t.saturating_duration_since(now);
+            let whole = left.saturating_sub(Duration::from_nanos(1)).as_secs();
+            t - Duration::from_secs(whole)
+        });
+        // Whenever the clock on the status line shows another second
+        let clock = self
+            .started
+            .filter(|_| ticking && self.stopped.is_none())
+            .map(|_| {
+                let subsec = self.elapsed().subsec_nanos() as u64;
+                now + Duration::from_nanos(1_000_000_000 - subsec)
+            });
+        [self.peek_until, self.freeze_until, countdown, clock]
+            .into_iter()
+            .flatten()
+            .filter(|t| *t > now)
+            .min()
+    }
 
-        let parts: Vec<_> = s
-            .split(|c| c == ',' || c == ';')
-            .map(|s| s.trim())
-            .collect();
+    /// Print the board as a line-by-line description and never clear the
+    /// screen, so that the output can be followed with a screen reader.
+    pub fn set_accessible(&mut self, accessible: bool) {
+        self.accessible = accessible;
+    }
 
-        if parts.len() < 2 {
-            return Err(GameError::UnparsableInput);
+    /// Draw everything in the [high contrast](`Theme::HIGH_CONTRAST`)
+    /// theme, and matched cards as bright as the others. The theme can't be
+    /// changed while this is on.
+    pub fn set_high_contrast(&mut self, high_contrast: bool) {
+        self.high_contrast = high_contrast;
+        if high_contrast {
+            self.theme = Theme::HIGH_CONTRAST;
         }
+    }
+
+    /// Let single keys stand in for commands as bound by `keymap`.
+    pub fn set_keymap(&mut self, keymap: Keymap) {
+        self.keymap = keymap;
+    }
+
+    /// Save the changes made in the settings menu to the configuration
+    /// file at `path`. Without one, they only last until the game exits.
+    pub fn set_config_file(&mut self, path: Option<PathBuf>) {
+        self.config_file = path;
+    }
+
+    /// Offer to save solo games to `path` when quitting in the middle of
+    /// them, to be continued with [`Game::resume`].
+    pub fn set_save_file(&mut self, path: Option<PathBuf>) {
+        self.save_file = path;
+    }
+
+    /// Keep an autosave of solo games at `path` after every change of
+    /// state, removed once the board is over or abandoned. One left behind
+    /// by a session that crashed or was interrupted can be offered with
+    /// [`Game::offer_recovery`].
+    pub fn set_autosave_file(&mut self, path: Option<PathBuf>) {
+        self.autosave_file = path;
+    }
+
+    /// Record the moves made on the first board dealt, and write them to
+    /// `path` as a [replay](`crate::replay`) once the board is over or
+    /// left.
+    pub fn set_replay_file(&mut self, path: Option<PathBuf>) {
+        self.replay_file = path;
+    }
+
+    /// The moves recorded on the first board dealt so far, if they are
+    /// [recorded](`Game::set_replay_file`).
+    pub fn replay(&self) -> Option<&Replay> {
+        self.recording.as_ref().map(|(replay, _)| replay)
+    }
+
+    /// Ask whether to continue `saved`, the autosave of a session that was
+    /// interrupted, instead of showing the welcome screen. Call it before
+    /// the game starts.
+    pub fn offer_recovery(&mut self, saved: SavedGame) {
+        let before = self.state;
+        self.recovered = Some(saved);
+        self.fire(Trigger::Recover);
+        self.notify_state_change(before);
+    }
+
+    /// Note whether sound effects start out turned off. Turning them on or
+    /// off in the settings menu is reported with
+    /// [`Event::SoundToggled`].
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+    }
+
+    /// Turn cards over at once instead of animating them, and don't flash
+    /// mismatched cards.
+    pub fn set_reduced_motion(&mut self, reduced_motion: bool) {
+        self.reduced_motion = reduced_motion;
+    }
+
+    /// Always draw the board in the compact layout: a single column
+    /// between cards and no blank lines or grid between the rows. Boards
+    /// that don't fit in the terminal are drawn compact either way.
+    pub fn set_compact(&mut self, compact: bool) {
+        self.compact = compact;
+    }
+
+    /// Show the [number](`Idx2d::number`) of each face-down cell in it, to
+    /// be typed instead of its coordinates. Can be toggled in game with
+    /// `:numbers`.
+    pub fn set_cell_numbers(&mut self, cell_numbers: bool) {
+        self.cell_numbers = cell_numbers;
+    }
+
+    /// Show how the guesses so far compare to what a player with perfect
+    /// memory is expected to take, given the cards seen. Can be toggled in
+    /// game with `:luck`.
+    pub fn set_luck_meter(&mut self, luck_meter: bool) {
+        self.luck_meter = luck_meter;
+    }
 
-        let x = parts[0]
-            .parse::<i32>()
-            .map_err(|_| GameError::UnparsableInput)?;
+    /// Set how the game moves on from the feedback about a guess.
+    pub fn set_pacing(&mut self, pacing: Pacing) {
+        self.pacing = pacing;
+    }
 
-        let y = parts[1]
-            .parse::<i32>()
-            .map_err(|_| GameError::UnparsableInput)?;
+    pub fn pacing(&self) -> Pacing {
+        self.pacing
+    }
 
-        Ok(Vec2 { x, y })
+    /// Draw each card symbol in a color of its own, and the suits of
+    /// playing cards in red or black, if the terminal can show `colors`.
+    /// Without them, no styles are drawn at all.
+    pub fn set_colors(&mut self, colors: Option<ColorDepth>) {
+        self.style = Style::new(colors);
     }
 
-    /// Attempt to interpret the string slice as the size of the game board.
-    fn parse_dimensions(&self, s: &str) -> Result<Vec2> {
-        let p = Game::parse_pair(s)?;
+    /// Draw the cards and the rest of the game with ASCII characters only,
+    /// unless the terminal can show `unicode`. Takes effect on the next
+    /// board dealt.
+    pub fn set_unicode(&mut self, unicode: bool) {
+        self.unicode = unicode;
+    }
 
-        if p.x <= 0 {
-            return Err(GameError::CoordinateUnderflow { axis: 'x' });
+    /// Draw the parts of the screen in the colors of `theme`, if colors
+    /// are on. Can be changed in game with `:theme`.
+    pub fn set_theme(&mut self, theme: Theme) {
+        if !self.high_contrast {
+            self.theme = theme;
         }
-        if p.y <= 0 {
-            return Err(GameError::CoordinateUnderflow { axis: 'y' });
+    }
+
+    /// Draw the faces of the cards as small images with the `graphics`
+    /// protocol, rather than as text.
+    pub fn set_graphics(&mut self, graphics: Option<Graphics>) {
+        self.graphics = graphics;
+        self.screen.set_graphics(graphics);
+    }
+
+    /// A glyph of the game, or its ASCII stand-in if the terminal can't
+    /// draw it.
+    fn glyph<'a>(&self, unicode: &'a str, ascii: &'a str) -> &'a str {
+        if self.unicode {
+            unicode
+        } else {
+            ascii
         }
+    }
 
-        // Cannot display more kinds of cards than those defined in the
-        // CARD_CHARS array
-        if p.x * p.y > Board::MAX_SIZE {
-            return Err(GameError::NotEnoughCardTypes {
-                max: Board::MAX_SIZE,
-            });
+    /// The intermediate images of a card being turned face up. Played in
+    /// reverse when turning a card face down.
+    const FLIP_FRAMES: [char; 3] = ['▐', '│', '▌'];
+
+    /// The [flip frames](`Game::FLIP_FRAMES`) drawn with ASCII characters.
+    const ASCII_FLIP_FRAMES: [char; 3] = ['[', '|', ']'];
+
+    /// Number of frames mismatched cards flash for before they are turned
+    /// face down.
+    const FLASH_FRAMES: usize = 5;
+
+    /// Number of frames the current flashes take, before the cards start
+    /// turning over. Flashes need colors to be seen.
+    fn flash_frames(&self) -> usize {
+        if self.style.is_enabled() && self.flips.iter().any(|f| f.flash) {
+            Game::FLASH_FRAMES
+        } else {
+            0
         }
+    }
+
+    /// Check if there are intermediate animation frames left to render
+    /// before the next input is needed.
+    pub fn is_animating(&self) -> bool {
+        !self.accessible
+            && !self.reduced_motion
+            && !self.flips.is_empty()
+            && self.flip_frame < self.flash_frames() + Game::FLIP_FRAMES.len()
+    }
 
-        if (p.x * p.y) % 2 != 0 {
-            return Err(GameError::OddBoardCells);
+    /// Check if the card at `c` is flashing in the current frame.
+    fn is_flashing(&self, c: Vec2) -> bool {
+        self.is_animating()
+            && self.flip_frame < self.flash_frames()
+            && self.flips.iter().any(|f| f.at == c && f.flash)
+    }
+
+    /// Advance the animations by a frame.
+    pub fn tick(&mut self) {
+        if self.is_animating() {
+            self.flip_frame += 1;
         }
+    }
 
-        Ok(p)
+    /// Play the daily challenge, recording the result in `log`. The game
+    /// ends after the board is cleared.
+    pub fn set_daily(&mut self, daily: Daily, log: DailyLog) {
+        self.daily = Some((daily, log));
     }
 
-    /// Attempt to interpret the string slice as the position of a card on
-    /// the game board.
-    fn parse_coords(&self, s: &str) -> Result<Vec2> {
-        let p = Game::parse_pair(s)?;
-        let coords = Vec2 {
-            x: p.x - 1,
-            y: p.y - 1,
-        };
-        self.idx.of(coords)?;
-        Ok(coords)
+    /// Record every finished game in `stats`.
+    pub fn set_stats(&mut self, stats: StatsStore) {
+        self.stats = Some(stats);
     }
 
-    /// Parse a yes/no response from the string slice. Defaults to `false`.
-    fn parse_yn(&self, s: &str) -> Result<bool> {
-        match s.to_lowercase().trim() {
-            "y" => Ok(true),
-            "n" => Ok(false),
-            s if s.is_empty() => Ok(false),
-            _ => Err(GameError::UnparsableInput),
+    /// Update the rating in `ratings` after every networked game, and
+    /// introduce the local player to the opponent with it.
+    pub fn set_ratings(&mut self, ratings: RatingStore) {
+        self.ratings = Some(ratings);
+        if let Err(e) = self.introduce() {
+            self.error = Some(e);
         }
     }
 
-    /// Attempt to create a new board from the latest user input and prepare
-    /// for the game to begin.
-    fn set_dimensions(&mut self) -> Result<()> {
-        debug_assert!(!self.user_input.is_empty());
+    /// On [`Difficulty::Hard`], the undiscovered cards are reshuffled after
+    /// every this many incorrect guesses.
+    pub const RESHUFFLE_INTERVAL: i32 = 3;
 
-        let Vec2 { x, y } = self.parse_dimensions(&self.user_input)?;
-        self.idx = Idx2d::new(x, y);
-        self.discovered = bitvec![0; (x * y) as usize];
-        self.board = Board::new(x, y)?;
-        Ok(())
+    /// Number of steps from the center of a bomb blast that cards are
+    /// shuffled up to, with [`BombEffect::Shuffle`].
+    pub const BLAST_RADIUS: i32 = 2;
+
+    /// How long the feedback about a guess stays on screen with
+    /// [`Pacing::Fast`].
+    pub const FEEDBACK_TIME: Duration = Duration::from_millis(800);
+
+    /// Silently reshuffle the cards that are neither matched nor revealed
+    /// after every `turns` guesses, if set, so that the player has to learn
+    /// them again. Ignored in networked games.
+    pub fn set_shuffle_every(&mut self, turns: Option<u32>) {
+        self.shuffle_every = turns.map(|turns| turns.max(1));
     }
 
-    /// Mark a position as having been correctly matched.
-    fn set_discovered(&mut self, c: Vec2) {
-        let index = self.idx.unchecked(c);
-        self.discovered.set(index, true);
+    /// Count a guess as incorrect if it isn't made within `limit`, if set.
+    /// Ignored in networked games.
+    pub fn set_time_limit(&mut self, limit: Option<Duration>) {
+        self.time_limit = limit;
     }
 
-    /// Check if a given position has been correctly matched.
-    fn is_discovered(&self, c: Vec2) -> bool {
-        let index = self.idx.unchecked(c);
-        *self.discovered.get(index).unwrap()
+    /// Turn matched cards face down again after `turns` more guesses, if
+    /// set, so that the board has to be cleared before the first matches
+    /// fade. Only cards on the bottom layer fade, since the ones above are
+    /// gone once matched. Ignored in networked games.
+    pub fn set_decay(&mut self, turns: Option<u32>) {
+        self.decay = turns;
     }
 
-    /// Check if all cards have been correctly matched.
-    fn all_discovered(&self) -> bool {
-        self.discovered.count_ones() == self.discovered.len()
+    /// Lay the board out on hexagons instead of squares.
+    pub fn set_layout(&mut self, layout: Layout) {
+        self.layout = layout;
     }
 
-    /// Check if it's possible to reveal a card during the current
-    /// guess phase.
-    fn can_reveal(&self) -> bool {
-        self.revealed1.is_none() || self.revealed2.is_none()
+    /// Stack `layers` boards on top of each other. Matching a pair exposes
+    /// the cards under it, and the game is won once every layer is cleared.
+    pub fn set_layers(&mut self, layers: usize) {
+        self.layers = layers.max(1);
     }
 
-    /// Check if the two cards revealed during the guess phase match.
-    /// # Panics
-    /// Panics if one or both of [`Game::revealed1`] and [`Game::revealed2`] was
-    /// not set.
-    fn revealed_match(&self) -> bool {
-        let r1 = self.board[self.revealed1.unwrap()];
-        let r2 = self.board[self.revealed2.unwrap()];
-        r1 == r2
+    /// Deal cards of the given set. Playing cards are matched by rank, and
+    /// only dealt on a single layer. Symbols are replaced by
+    /// [ASCII](`CardSet::Ascii`) cards if the terminal can't draw them, and
+    /// so are [emoji](`CardSet::Emoji`).
+    pub fn set_card_set(&mut self, card_set: CardSet) {
+        self.card_set = card_set;
     }
 
-    /// Mark a card as revealed during the guess phase.
-    fn set_revealed(&mut self, c: Vec2) {
-        if self.revealed1.is_none() {
-            self.revealed1 = Some(c);
-        } else {
-            self.revealed2 = Some(c);
-        }
+    /// Deal `jokers` cards that match any other card. A joker takes the
+    /// place of a card, so boards with an odd number of cells can be
+    /// played with an odd number of jokers. Ignored with several layers
+    /// and in networked games.
+    pub fn set_jokers(&mut self, jokers: usize) {
+        self.jokers = jokers;
     }
 
-    /// Clear both revealed cards.
-    fn clear_revealed(&mut self) {
-        self.revealed1 = None;
-        self.revealed2 = None;
+    /// Hide `bombs` cards that don't pair with anything. Revealing one ends
+    /// the guess with the given `effect`. Like jokers, bombs take the place
+    /// of a card, and are ignored with several layers and in networked
+    /// games.
+    pub fn set_bombs(&mut self, bombs: usize, effect: BombEffect) {
+        self.bombs = bombs;
+        self.bomb_effect = effect;
     }
 
-    /// Check if a card at a given position has been revealed during the
-    /// guessing phase.
-    fn is_revealed(&self, c: Vec2) -> bool {
-        matches!(self.revealed1, Some(x) if c == x)
-            || matches!(self.revealed2, Some(x) if c == x)
+    /// Deal the cards of each pair at least `distance` steps apart, e.g. 2
+    /// to never put them next to each other. Lowered as needed on boards
+    /// too small for it.
+    pub fn set_min_pair_distance(&mut self, distance: u32) {
+        self.min_pair_distance = distance as i32;
     }
 
-    /// Increment the number of guesses.
-    fn inc_guesses(&mut self) {
-        self.guesses += 1;
+    /// The grid of the current board, which knows which cells are next to
+    /// each other.
+    pub fn grid(&self) -> Box<dyn Grid> {
+        self.layout.grid(self.idx)
     }
 
-    /// Clear the screen.
-    fn render_clear(&self) {
-        print!("{esc}[2J{esc}[1;1H", esc = 27 as char);
+    /// Lose the game after `lives` incorrect guesses on a board, if set.
+    /// Ignored in networked games.
+    pub fn set_lives(&mut self, lives: Option<u32>) {
+        self.lives = lives.map(|lives| lives.max(1));
     }
 
-    /// Render the cards and reveal indicators.
-    fn render_board(&self) {
-        let mut board_img: Vec<char> = vec![];
-        for coords in self.idx.iter_all() {
-            if self.is_discovered(coords) {
-                board_img.push(self.board[coords].0);
-                board_img.push(' ');
-                board_img.push(' ');
-            } else if self.is_revealed(coords) {
-                board_img.push(self.board[coords].0);
-                board_img.push(' ');
-                board_img.push('<');
-            } else {
-                board_img.push('█');
-                board_img.push(' ');
-                board_img.push(' ');
-            }
+    /// Set the rule preset. Networked games are always played on
+    /// [`Difficulty::Normal`].
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.difficulty = difficulty;
+    }
 
-            if coords.x == self.idx.size_x - 1 {
-                board_img.push('\n');
-                board_img.push('\n');
-            }
-        }
-        let board_img: String = board_img.iter().collect();
-        println!("{}", board_img);
+    /// Number of guesses added to the count for every hint, unless
+    /// configured otherwise.
+    pub const DEFAULT_HINT_PENALTY: i32 = 1;
+
+    /// Set the number of guesses added to the count for every hint.
+    pub fn set_hint_penalty(&mut self, penalty: i32) {
+        self.hint_penalty = penalty;
     }
 
-    /// Render the error message, if there is one.
-    fn render_error(&self) {
-        if let Some(err) = &self.error {
-            println!("(!) {}", err.as_string());
+    /// Play against an opponent connected to `conn`, as the host. The
+    /// host picks the board size and takes the first turn.
+    pub fn host(&mut self, conn: Connection) {
+        self.multiplayer = Some(Multiplayer::new(conn, 0));
+    }
+
+    /// Play against the host connected to `conn`. Blocks until the host
+    /// has chosen the board. Its cards are only learned as they are
+    /// revealed, and checked against the host's
+    /// [commitment](`crate::fair`) once the board is cleared.
+    pub fn join(conn: Connection) -> io::Result<Game> {
+        Game::join_seat(conn, 1)
+    }
+
+    /// Play in `seat` of a room of a [lobby](`crate::lobby`) connected to
+    /// `conn`, which deals the board and plays the part of the host for
+    /// both players. Seat 0 takes the first turn. Blocks until both seats
+    /// are taken.
+    pub fn join_seat(mut conn: Connection, seat: usize) -> io::Result<Game> {
+        let (size, holes, hash) = match conn.recv()? {
+            Message::Commit { size, holes, hash } => (size, holes, hash),
+            msg => return Err(unexpected("the board", &msg)),
+        };
+
+        let mut game = Game::new();
+        game.idx = Idx2d::new(size.x, size.y);
+        game.board = Board::from_cards(
+            size.x,
+            size.y,
+            holes
+                .iter()
+                .map(|hole| (!hole).then_some(Card::UNKNOWN))
+                .collect(),
+        );
+        game.discovered =
+            game.board.cards.iter().map(Option::is_none).collect();
+        game.depth = vec![0; game.board.cards.len()];
+        game.ages = vec![None; game.board.cards.len()];
+        let mut mp = Multiplayer::new(conn, seat);
+        mp.commitment = Some(Commitment::new(hash, size));
+        game.multiplayer = Some(mp);
+        game.fire(Trigger::Restore);
+        Ok(game)
+    }
+
+    /// Watch the game of a room of a [lobby](`crate::lobby`) connected to
+    /// `conn`, without taking part in it. Blocks until both seats of the
+    /// room are taken.
+    pub fn spectate(mut conn: Connection) -> io::Result<Game> {
+        let state = match conn.recv()? {
+            Message::State(state) => state,
+            msg => return Err(unexpected("the board", &msg)),
+        };
+
+        let size = state.size;
+        input::validate_dimensions(size)
+            .map_err(|e| invalid_data(e.as_string()))?;
+        if state.cells.len() != size.x as usize * size.y as usize {
+            return Err(invalid_data(ROOM_STATE_MISFIT));
         }
+
+        let mut game = Game::new();
+        game.idx = Idx2d::new(size.x, size.y);
+        game.board = Board::from_cards(
+            size.x,
+            size.y,
+            state
+                .cells
+                .iter()
+                .map(|cell| (*cell != net::Cell::Hole).then_some(Card::UNKNOWN))
+                .collect(),
+        );
+        game.discovered =
+            game.board.cards.iter().map(Option::is_none).collect();
+        game.depth = vec![0; game.board.cards.len()];
+        game.ages = vec![None; game.board.cards.len()];
+        let mut mp = Multiplayer::new(conn, 0);
+        mp.spectating = true;
+        game.multiplayer = Some(mp);
+        game.check_room_state(&state)?;
+        game.fire(Trigger::Restore);
+        game.watch(state);
+        Ok(game)
+    }
+
+    /// Skip the welcome and board size prompts, and start guessing on a
+    /// board of the given size right away.
+    pub fn start(&mut self, size: Vec2) -> Result<()> {
+        input::validate_dimensions(size)?;
+        self.start_with_shape(&Shape::rectangle(size))
+    }
+
+    /// Skip the welcome and board size prompts, and start guessing on a
+    /// board of the given shape right away.
+    pub fn start_with_shape(&mut self, shape: &Shape) -> Result<()> {
+        self.check_trigger(Trigger::Deal)?;
+        self.create_board(shape)?;
+        let before = self.state;
+        self.fire(Trigger::Deal);
+        self.notify_state_change(before);
+        Ok(())
+    }
+
+    /// Skip the welcome and board size prompts, and start guessing on the
+    /// board of `code` with its rules.
+    pub fn start_from_code(&mut self, code: &BoardCode) -> Result<()> {
+        self.check_trigger(Trigger::Deal)?;
+        self.deal_code(code)?;
+        let before = self.state;
+        self.fire(Trigger::Deal);
+        self.notify_state_change(before);
+        Ok(())
     }
 
-    /// Render the total and correct number of guesses.
-    fn render_score(&self) {
-        let correct_guesses = self.discovered.count_ones() / 2;
-        println!(
-            "Guesses: {} | Correct guesses: {}\n",
-            self.guesses, correct_guesses
+    /// Replace the board with the one of `code`, and take on its rules.
+    fn deal_code(&mut self, code: &BoardCode) -> Result<()> {
+        let cells = code.size.x as usize * code.size.y as usize;
+        let top = code.layers.first().ok_or(GameError::InvalidBoardCode)?;
+        let shape = Shape::from_cells(
+            Idx2d::new(code.size.x, code.size.y),
+            top.iter().map(Option::is_some),
         );
+        // Every layer needs the same holes, and every card a partner on
+        // its own layer except for jokers and bombs, which are only dealt
+        // on a single layer
+        let mut counts = HashMap::new();
+        let mut paired = true;
+        for layer in &code.layers {
+            let holes_match = layer
+                .iter()
+                .zip(top)
+                .all(|(card, top)| card.is_some() == top.is_some());
+            if layer.len() != cells || !holes_match {
+                return Err(GameError::InvalidBoardCode);
+            }
+            let mut layer_counts = HashMap::new();
+            for id in layer.iter().flatten() {
+                // Playing cards pair up with any card of the same rank
+                let card = Card(*id);
+                let kind = card
+                    .rank()
+                    .map_or(card, |rank| Card::playing(rank, Suit::Spades));
+                *layer_counts.entry(kind).or_insert(0) += 1;
+            }
+            paired &=
+                layer_counts.iter().all(|(card, count)| match card.rank() {
+                    _ if card.is_joker() || card.is_bomb() => true,
+                    Some(_) => count % 2 == 0,
+                    None => *count == 2,
+                });
+            for (kind, count) in layer_counts {
+                *counts.entry(kind).or_insert(0) += count;
+            }
+        }
+        let jokers = counts.remove(&Card::JOKER).unwrap_or(0);
+        let bombs = counts.remove(&Card::BOMB).unwrap_or(0);
+        let playing = counts.keys().filter(|c| c.rank().is_some()).count();
+        let ascii = counts.keys().any(|c| c.is_ascii());
+        let emoji = counts.keys().any(|c| c.is_emoji());
+        paired &= counts.iter().all(|(card, count)| match card.rank() {
+            Some(_) => count % 2 == 0,
+            None => *count == 2,
+        });
+        let mixed = playing > 0 && playing < counts.len();
+        if !paired
+            || mixed
+            || (jokers + bombs + playing > 0 && code.layers.len() > 1)
+        {
+            return Err(GameError::InvalidBoardCode);
+        }
+
+        self.difficulty = code.difficulty;
+        self.layout = code.layout;
+        self.hint_penalty = code.hint_penalty;
+        self.min_pair_distance = code.min_pair_distance;
+        self.layers = code.layers.len();
+        self.jokers = jokers;
+        self.bombs = bombs;
+        self.card_set = match playing {
+            _ if ascii => CardSet::Ascii,
+            _ if emoji => CardSet::Emoji,
+            0 => CardSet::Symbols,
+            _ => CardSet::Playing,
+        };
+        // Dealt like the original, so that the random numbers drawn later
+        // on are the same, then replaced in case the dealing changed
+        self.create_board(&shape)?;
+        let mut layers = code.layers.iter().map(|layer| {
+            let cards = layer.iter().map(|id| id.map(Card)).collect();
+            Board::from_cards(code.size.x, code.size.y, cards)
+        });
+        self.board = layers.next().expect("checked above");
+        self.under = layers.collect();
+        // A code without a seed takes on the game's, so that recordings
+        // and codes shared from here on draw the same random numbers
+        self.dealt = Some(BoardCode {
+            seed: code.seed.or(self.seed),
+            ..code.clone()
+        });
+        Ok(())
+    }
+
+    /// Continue a game saved when quitting, at the guess it was left at.
+    pub fn resume(&mut self, saved: &SavedGame) -> Result<()> {
+        self.check_trigger(Trigger::Restore)?;
+        self.restore(saved)?;
+        let before = self.state;
+        self.fire(Trigger::Restore);
+        self.notify_state_change(before);
+        Ok(())
+    }
+
+    /// Put the board and score back the way they were saved.
+    fn restore(&mut self, saved: &SavedGame) -> Result<()> {
+        let code = BoardCode::decode(&saved.board)?;
+        self.deal_code(&code)?;
+        let cells = self.board.cards.len();
+        if saved.layers.len() != code.layers.len()
+            || saved.layers.iter().any(|layer| layer.len() != cells)
+            || saved.depth.len() != cells
+            || saved.discovered.len() != cells
+        {
+            return Err(GameError::InvalidBoardCode);
+        }
+
+        let mut layers = saved.layers.iter().map(|layer| {
+            let cards = layer.iter().map(|id| id.map(Card)).collect();
+            Board::from_cards(code.size.x, code.size.y, cards)
+        });
+        self.board = layers.next().expect("checked above");
+        self.under = layers.collect();
+        self.depth = saved.depth.clone();
+        self.discovered = saved.discovered.iter().copied().collect();
+        self.guesses = saved.guesses;
+        self.hints = saved.hints;
+        self.mismatches = saved.mismatches;
+        self.turns = saved.turns;
+        let now = Instant::now();
+        self.score = Score::new(now);
+        self.score.points = saved.points;
+        self.score.streak = saved.streak;
+        self.score.best_streak = saved.best_streak;
+        let elapsed = Duration::from_millis(saved.elapsed_ms);
+        self.started = Some(now.checked_sub(elapsed).unwrap_or(now));
+        Ok(())
+    }
+
+    /// Take on the result of a friend: play the board they played, then
+    /// compare the two results side by side.
+    pub fn challenge(&mut self, rival: ChallengeResult) -> Result<()> {
+        self.check_trigger(Trigger::Deal)?;
+        self.start_from_code(&rival.board_code()?)?;
+        self.rival = Some(rival);
+        Ok(())
+    }
+
+    /// Play a tournament, starting with its first round.
+    pub fn start_tournament(&mut self, tournament: Tournament) -> Result<()> {
+        self.check_trigger(Trigger::Deal)?;
+        if let Some(size) = tournament.next_round() {
+            self.start(size)?;
+        }
+        self.tournament = Some(tournament);
+        Ok(())
+    }
+
+    /// Walk a new player through a small board, telling them what to do
+    /// at every step and pointing out the cards to pick. The game ends
+    /// after the board is cleared.
+    pub fn start_tutorial(&mut self) -> Result<()> {
+        self.check_trigger(Trigger::Deal)?;
+        self.tutorial = true;
+        self.start(Game::TUTORIAL_SIZE)
+    }
+
+    /// Play the campaign, starting with picking a level.
+    pub fn start_campaign(&mut self, campaign: Campaign) -> Result<()> {
+        self.check_trigger(Trigger::Campaign)?;
+        self.level = campaign.furthest();
+        self.campaign = Some(campaign);
+        self.fire(Trigger::Campaign);
+        Ok(())
+    }
+
+    /// The result of the last cleared board, for comparing with friends.
+    pub fn challenge_result(&self) -> Option<ChallengeResult> {
+        Some(ChallengeResult {
+            board: self.dealt.as_ref()?.encode(),
+            guesses: self.guesses,
+            time_ms: self.finish_time?.as_millis() as u64,
+            hints: self.hints,
+            score: self.score.points,
+        })
+    }
+
+    /// The code of the current board as it was dealt, along with the rules,
+    /// for others to play the same board.
+    pub fn board_code(&self) -> Option<&BoardCode> {
+        self.dealt.as_ref()
+    }
+
+    /// The state the game is in, as in the
+    /// [transition table](`flow::TRANSITIONS`).
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.state != Exit
+    }
+
+    /// Check if the last board dealt was cleared.
+    pub fn is_won(&self) -> bool {
+        self.finish_time.is_some()
+    }
+
+    /// End the game right away, e.g. when the player interrupts it.
+    pub fn quit(&mut self) {
+        let before = self.state;
+        self.fire(Trigger::Exit);
+        self.notify_state_change(before);
+    }
+
+    /// Check if the next input comes from the opponent rather than the
+    /// local player.
+    pub fn awaits_opponent(&self) -> bool {
+        match &self.multiplayer {
+            Some(mp) => self.state == Guess && !mp.is_local_turn(),
+            None => false,
+        }
+    }
+
+    /// The connection to the opponent in a networked game, e.g. to wait
+    /// for their move on another thread while [`Game::awaits_opponent`].
+    pub fn connection(&self) -> Option<&Connection> {
+        self.multiplayer.as_ref().map(|mp| &mp.conn)
+    }
+
+    /// Check if the local player can [chat](`crate::chat`) with their
+    /// opponent, which players of networked games can, but spectators
+    /// can't.
+    pub fn can_chat(&self) -> bool {
+        self.multiplayer.as_ref().is_some_and(|mp| !mp.spectating)
+    }
+
+    /// Note a message received while waiting for the opponent's move that
+    /// doesn't move the game on, such as a line of chat. Any other message
+    /// is handed back, to be [applied](`Game::set_opponent_move`).
+    pub fn receive(&mut self, msg: Message) -> Option<Message> {
+        match &mut self.multiplayer {
+            Some(mp) => mp.aside(msg),
+            None => Some(msg),
+        }
+    }
+
+    /// Note the lines of chat the opponent sent while the local player is
+    /// the one to pick a card, without waiting for any, and apply the end
+    /// of the turn right away if it ran out of time.
+    pub fn poll_messages(&mut self) -> io::Result<()> {
+        if self.state != Guess || self.awaits_opponent() {
+            return Ok(());
+        }
+        while let Some(mp) = &mut self.multiplayer {
+            let Some(msg) = mp.conn.try_recv_unprompted()? else {
+                break;
+            };
+            if let Some(msg) = mp.aside(msg) {
+                self.set_opponent_move(msg)?;
+                self.update();
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Provide the move the opponent sent while it was their turn to pick a
+    /// card, to be applied by the next update.
+    pub fn set_opponent_move(&mut self, msg: Message) -> io::Result<()> {
+        self.user_input.clear();
+        let host = self
+            .multiplayer
+            .as_ref()
+            .is_some_and(|mp| mp.sealed.is_some());
+        let spectating =
+            self.multiplayer.as_ref().is_some_and(|mp| mp.spectating);
+        match msg {
+            Message::State(state) if spectating => {
+                self.check_room_state(&state)?;
+                self.remote_state = Some(state)
+            }
+            Message::TurnExpired { seat } if !spectating => {
+                self.remote_expiry = Some(seat)
+            }
+            Message::Reveal(c) if host => self.remote_reveal = Some(c),
+            Message::Revealed { at, card } if !host => {
+                if self.board.has_card(at) {
+                    self.learn_card(at, card);
+                }
+                self.remote_reveal = Some(at);
+            }
+            msg => return Err(unexpected("a move", &msg)),
+        }
+        Ok(())
+    }
+
+    /// Provide a line of input typed by the player, to be applied by the
+    /// next update.
+    pub fn set_input(&mut self, input: String) {
+        self.user_input = input;
+    }
+
+    /// Update the game based on the latest input.
+    pub fn update(&mut self) {
+        self.record_input();
+        // Closing an overlay leaves everything else as it was
+        if self.overlay.take().is_some() {
+            return;
+        }
+        let before = self.state;
+        self.step();
+        if self.error.is_some() {
+            self.queued.clear();
+        }
+        if self.state != Guess {
+            self.guess_deadline = None;
+        } else if before != Guess {
+            self.start_guess_timer();
+        }
+        if !self.shows_feedback() {
+            self.feedback_until = None;
+        } else if before != self.state && self.pacing == Pacing::Fast {
+            self.feedback_until = Some(Instant::now() + Game::FEEDBACK_TIME);
+        }
+        self.notify_state_change(before);
+        if self.state != before {
+            self.autosave();
+        }
+    }
+
+    /// Check if there are coordinates left from a batch of guesses, such as
+    /// `1,1 2,3 1,2 4,4`.
+    pub fn has_queued_input(&self) -> bool {
+        !self.queued.is_empty()
+    }
+
+    /// The input that applies the next step of a batch of guesses, if any
+    /// are left. Pass it to [`Game::set_input`] before the next update.
+    pub fn next_queued_input(&mut self) -> Option<String> {
+        match self.state {
+            _ if self.queued.is_empty() => None,
+            // Confirm the outcome of the previous guess first
+            CorrectGuessConfirm | IncorrectGuessConfirm | BombExploded => {
+                Some(String::new())
+            }
+            Guess => {
+                self.input_queued = true;
+                self.queued.pop_front()
+            }
+            _ => {
+                self.queued.clear();
+                None
+            }
+        }
+    }
+
+    fn step(&mut self) {
+        self.error = None;
+        self.hinted.clear();
+        self.odds.clear();
+        self.reshuffled = false;
+        self.reshuffle_announced = false;
+        self.decayed.clear();
+        self.timed_out = false;
+        self.turn_expired = None;
+        self.flips.clear();
+        self.flip_frame = 0;
+        self.earned = None;
+
+        if let Some(state) = self.remote_state.take() {
+            self.watch(state);
+            return;
+        }
+        if let Some(seat) = self.remote_expiry.take() {
+            self.expire_turn(seat);
+            return;
+        }
+        if self.remote_reveal.is_none() {
+            if let Some(input) = self.keymap.translate(&self.user_input) {
+                self.user_input = input.to_owned();
+            }
+            if let Some(command) = self.user_input.trim().strip_prefix(':') {
+                let (name, arg) =
+                    command.trim().split_once(' ').unwrap_or((command, ""));
+                match PromptCommand::parse(name) {
+                    // The opponent's move may come in at any time, and
+                    // only chatting leaves the game as it is until then
+                    Ok(command)
+                        if self.awaits_opponent()
+                            && command != PromptCommand::Say =>
+                    {
+                        self.error = Some(GameError::NotYourTurn)
+                    }
+                    Ok(command) => {
+                        let arg = arg.trim().to_owned();
+                        self.run_command(command, &arg)
+                    }
+                    Err(e) => self.error = Some(e),
+                }
+                return;
+            }
+            if self.awaits_opponent() {
+                self.error = Some(GameError::NotYourTurn);
+                return;
+            }
+        }
+
+        match self.state {
+            Welcome => self.fire(Trigger::Continue),
+            SizeMenu => match self.pick_size() {
+                Ok(Some(size)) => {
+                    match self.create_board(&Shape::rectangle(size)) {
+                        Ok(()) => self.fire(Trigger::Deal),
+                        Err(e) => self.error = Some(e),
+                    }
+                }
+                Ok(None) => self.fire(Trigger::CustomSize),
+                Err(e) => self.error = Some(e),
+            },
+            SetDimensions => match self.set_dimensions() {
+                Ok(_) => self.fire(Trigger::Deal),
+                Err(e) => self.error = Some(e),
+            },
+            Memorize | Preview => self.fire(Trigger::Continue),
+            Guess if self.is_out_of_time() => self.time_out(),
+            Guess if self.is_hint_request(&self.user_input) => {
+                if let Err(e) = self.give_hint() {
+                    self.error = Some(e);
+                }
+            }
+            Guess => {
+                if self.remote_reveal.is_none() {
+                    if let Some(direction) =
+                        input::parse_scroll(&self.user_input)
+                    {
+                        self.scroll(direction);
+                        return;
+                    }
+                    self.queue_batch();
+                }
+                let c = match self.remote_reveal.take() {
+                    Some(c) => self.idx.of(c).map(|_| c),
+                    None => input::parse_coords(&self.user_input, &self.idx),
+                };
+                let c = match c {
+                    Ok(c) => c,
+                    Err(e) => {
+                        self.error = Some(e);
+                        return;
+                    }
+                };
+                if !self.board.has_card(c) {
+                    self.error = Some(GameError::NoCard {
+                        x: c.x + 1,
+                        y: c.y + 1,
+                    });
+                    return;
+                }
+                if self.is_revealed(c) || self.is_discovered(c) {
+                    self.error = Some(GameError::AlreadyRevealed {
+                        x: c.x + 1,
+                        y: c.y + 1,
+                    });
+                    return;
+                }
+                if self.can_reveal() {
+                    self.set_revealed(c);
+                    self.follow(c);
+                    self.flips.push(Flip::show(c));
+                    if let Err(e) = self.send_reveal(c) {
+                        self.error = Some(e);
+                    }
+                    if self.turn_expired.is_some() {
+                        return;
+                    }
+                    self.emit(Event::CardRevealed {
+                        at: c,
+                        card: self.board[c],
+                        completes_guess: !self.can_reveal()
+                            || self.board[c].is_bomb(),
+                    });
+                }
+                if self.board[c].is_bomb() {
+                    self.explode();
+                    return;
+                }
+                if !self.can_reveal() {
+                    let now = Instant::now();
+                    let first = self.revealed1.unwrap();
+                    let second = self.revealed2.unwrap();
+                    if self.revealed_match() {
+                        self.last_points =
+                            self.score.record_match(&self.scoring, now);
+                        self.earn_power_up();
+                        self.emit(Event::PairMatched {
+                            first,
+                            second,
+                            card: self.board[first],
+                        });
+                        if self.is_last_pair() {
+                            if let Err(e) = self.open_board() {
+                                self.error = Some(e);
+                            }
+                        }
+                        self.fire(Trigger::Match);
+                    } else {
+                        self.score.record_mismatch(now);
+                        self.emit(Event::Mismatch { first, second });
+                        self.fire(Trigger::Mismatch);
+                    }
+                }
+            }
+            CorrectGuessConfirm => {
+                self.clear_card(self.revealed1.unwrap());
+                self.clear_card(self.revealed2.unwrap());
+                self.inc_guesses();
+                self.clear_revealed();
+                if let Some(mp) = &mut self.multiplayer {
+                    mp.pairs[mp.turn] += 1;
+                }
+                self.check_cleared();
+                if self.state == Guess {
+                    self.end_turn();
+                }
+            }
+            BombExploded => {
+                self.inc_guesses();
+                for at in [self.revealed1, self.revealed2].into_iter().flatten()
+                {
+                    if self.board[at].is_bomb() {
+                        self.set_discovered(at);
+                    } else {
+                        self.flips.push(Flip::hide(at));
+                    }
+                }
+                self.clear_revealed();
+
+                if self.bomb_effect == BombEffect::Penalty {
+                    self.mismatches += 1;
+                    if self.lives_left() == Some(0) {
+                        self.lose();
+                        self.fire(Trigger::Lose);
+                        return;
+                    }
+                }
+                self.end_turn();
+                self.fire(Trigger::NextTurn);
+            }
+            IncorrectGuessConfirm => {
+                self.inc_guesses();
+                for at in [self.revealed1, self.revealed2].into_iter().flatten()
+                {
+                    self.flips.push(Flip {
+                        flash: true,
+                        ..Flip::hide(at)
+                    });
+                }
+                self.clear_revealed();
+                if let Some(mp) = &mut self.multiplayer {
+                    mp.turn = 1 - mp.turn;
+                }
+
+                self.mismatches += 1;
+                if self.lives_left() == Some(0) {
+                    self.lose();
+                    self.fire(Trigger::Lose);
+                    return;
+                }
+                if self.effective_difficulty() == Difficulty::Hard
+                    && self.mismatches % Game::RESHUFFLE_INTERVAL == 0
+                {
+                    self.reshuffle(true);
+                }
+                self.end_turn();
+                self.fire(Trigger::NextTurn);
+            }
+            // Networked games and daily challenges end after a single board
+            Victory if self.multiplayer.is_some() || self.daily.is_some() => {
+                self.fire(Trigger::Exit)
+            }
+            Victory if self.tournament.is_some() => self.next_round(),
+            Victory if self.tutorial => self.fire(Trigger::Exit),
+            // A lost tournament or daily challenge can't be played on
+            Defeat if self.tournament.is_some() || self.daily.is_some() => {
+                self.fire(Trigger::Exit)
+            }
+            Defeat if self.campaign.is_some() => self.fire(Trigger::Campaign),
+            Victory | Defeat => match input::parse_yn(&self.user_input) {
+                Ok(true) => self.fire(Trigger::Yes),
+                Ok(false) => self.fire(Trigger::No),
+                Err(e) => self.error = Some(e),
+            },
+            LevelSelect => match self.pick_level() {
+                Ok(level) => {
+                    self.level = level;
+                    self.fire(Trigger::PickLevel);
+                }
+                Err(e) => self.error = Some(e),
+            },
+            LevelIntro => {
+                if let Err(e) = self.start_level() {
+                    self.error = Some(e);
+                }
+            }
+            LevelOutro if self.level + 1 == LEVELS.len() => {
+                self.fire(Trigger::Campaign)
+            }
+            LevelOutro => match input::parse_yn(&self.user_input) {
+                Ok(true) => {
+                    self.level += 1;
+                    self.fire(Trigger::Yes);
+                }
+                Ok(false) => self.fire(Trigger::No),
+                Err(e) => self.error = Some(e),
+            },
+            ConfirmQuit
+                if self.can_save(self.resume)
+                    && self.user_input.trim().eq_ignore_ascii_case("s") =>
+            {
+                match self.save() {
+                    Ok(()) => self.fire(Trigger::Exit),
+                    Err(e) => self.error = Some(e),
+                }
+            }
+            ConfirmQuit => match input::parse_yn(&self.user_input) {
+                Ok(true) => self.fire(Trigger::Yes),
+                Ok(false) => self.fire(Trigger::No),
+                Err(e) => self.error = Some(e),
+            },
+            ConfirmRestart => match input::parse_yn(&self.user_input) {
+                Ok(true) => self.deal_again(self.redeal),
+                Ok(false) => self.fire(Trigger::No),
+                Err(e) => self.error = Some(e),
+            },
+            ConfirmRecover => match input::parse_yn(&self.user_input) {
+                Ok(true) => {
+                    let saved = self.recovered.take().expect("offered");
+                    match self.restore(&saved) {
+                        Ok(()) => self.fire(Trigger::Restore),
+                        Err(e) => {
+                            self.error = Some(e);
+                            self.fire(Trigger::No);
+                        }
+                    }
+                }
+                Ok(false) => {
+                    self.recovered = None;
+                    self.fire(Trigger::No);
+                }
+                Err(e) => self.error = Some(e),
+            },
+            Settings => match self.pick_setting() {
+                Ok(Some(setting)) => {
+                    if let Err(e) = self.change_setting(setting) {
+                        self.error = Some(e);
+                    }
+                }
+                Ok(None) => self.fire(Trigger::Back),
+                Err(e) => self.error = Some(e),
+            },
+            _ => {}
+        }
+    }
+
+    /// Carry out a command typed with a `:` prefix, followed by `arg`.
+    fn run_command(&mut self, command: PromptCommand, arg: &str) {
+        let unavailable = |name: &str| GameError::CommandUnavailable {
+            command: name.to_owned(),
+        };
+        let in_progress = self.is_board_in_progress();
+        match command {
+            PromptCommand::Quit => self.fire(Trigger::Quit),
+            // Both sides of a networked game and everyone playing the daily
+            // challenge must see the same board, and tournament rounds
+            // can't be retried
+            PromptCommand::Redeal(redeal)
+                if self.multiplayer.is_some()
+                    || self.daily.is_some()
+                    || self.tournament.is_some() =>
+            {
+                self.error = Some(unavailable(redeal.command()));
+            }
+            // The size of campaign levels and the tutorial is given
+            PromptCommand::Redeal(Redeal::Resize)
+                if self.campaign.is_some() || self.tutorial =>
+            {
+                self.error = Some(unavailable("resize"));
+            }
+            PromptCommand::Redeal(redeal) if in_progress => {
+                self.redeal = redeal;
+                self.fire(Trigger::Restart);
+            }
+            PromptCommand::Redeal(redeal)
+                if matches!(self.state, Victory | Defeat) =>
+            {
+                self.deal_again(redeal)
+            }
+            PromptCommand::Redeal(redeal) => {
+                self.error = Some(unavailable(redeal.command()));
+            }
+            PromptCommand::Hint if self.state == Guess => {
+                if let Err(e) = self.give_hint() {
+                    self.error = Some(e);
+                }
+            }
+            PromptCommand::Hint => self.error = Some(unavailable("hint")),
+            PromptCommand::Odds if self.state == Guess => {
+                match self.match_odds() {
+                    Some(odds) => {
+                        self.odds = odds;
+                        self.hints += 1;
+                        self.guesses += self.hint_penalty;
+                    }
+                    None => self.error = Some(unavailable("odds")),
+                }
+            }
+            PromptCommand::Odds => self.error = Some(unavailable("odds")),
+            PromptCommand::Save if self.can_save(self.state) => {
+                match self.save() {
+                    Ok(()) => self.fire(Trigger::Exit),
+                    Err(e) => self.error = Some(e),
+                }
+            }
+            PromptCommand::Save => self.error = Some(unavailable("save")),
+            PromptCommand::Help => self.overlay = Some(Overlay::Help),
+            PromptCommand::Keys => self.overlay = Some(Overlay::Keys),
+            PromptCommand::Numbers => self.cell_numbers = !self.cell_numbers,
+            PromptCommand::Luck => self.luck_meter = !self.luck_meter,
+            PromptCommand::Say if self.can_chat() => {
+                if let Err(e) = self.say(arg) {
+                    self.error = Some(e);
+                }
+            }
+            PromptCommand::Say => self.error = Some(unavailable("say")),
+            PromptCommand::Theme if self.high_contrast => {
+                self.error = Some(unavailable("theme"));
+            }
+            PromptCommand::Theme if arg.is_empty() => {
+                self.theme = self.theme.next();
+            }
+            PromptCommand::Theme => match Theme::named(arg) {
+                Ok(theme) => self.theme = theme,
+                Err(e) => self.error = Some(e),
+            },
+            PromptCommand::Heatmap
+                if matches!(self.state, Victory | Defeat)
+                    && self.stats.is_some() =>
+            {
+                self.show_heatmap = !self.show_heatmap;
+            }
+            PromptCommand::Heatmap => self.error = Some(unavailable("heatmap")),
+            // The state to go back to is already taken
+            PromptCommand::Settings
+                if flow::transition(self.state, Trigger::OpenSettings)
+                    .is_none() =>
+            {
+                self.error = Some(unavailable("settings"));
+            }
+            PromptCommand::Settings => self.fire(Trigger::OpenSettings),
+            PromptCommand::Power(power) if self.state == Guess => {
+                if let Err(e) = self.use_power_up(power, arg) {
+                    self.error = Some(e);
+                }
+            }
+            PromptCommand::Power(power) => {
+                self.error = Some(unavailable(power.name()));
+            }
+        }
+    }
+
+    /// Check if the board can be saved in `state`. Only solo games are
+    /// saved, and only between guesses.
+    fn can_save(&self, state: GameState) -> bool {
+        self.save_file.is_some()
+            && matches!(state, Memorize | Preview | Guess)
+            && self.is_solo()
+    }
+
+    /// Check if the game is a solo game with no rules of its own for how it
+    /// is continued, such as the daily challenge.
+    fn is_solo(&self) -> bool {
+        self.multiplayer.is_none()
+            && self.daily.is_none()
+            && self.tournament.is_none()
+            && self.campaign.is_none()
+            && !self.tutorial
+    }
+
+    /// Save the board to the [save file](`Game::set_save_file`). A card
+    /// turned over for the guess in progress is turned back face down.
+    fn save(&self) -> Result<()> {
+        match (&self.save_file, self.saved_game()) {
+            (Some(path), Some(saved)) => saved.store(path),
+            _ => Ok(()),
+        }
+    }
+
+    /// Bring the [autosave](`Game::set_autosave_file`) up to date after a
+    /// change of state: the board is written between guesses, and removed
+    /// once there is none in play to recover. It is left alone while a
+    /// question about the board is asked, and when the game is interrupted.
+    fn autosave(&mut self) {
+        let Some(path) = &self.autosave_file else {
+            return;
+        };
+        if !self.is_solo() {
+            return;
+        }
+        let result = match self.state {
+            Memorize | Preview | Guess => {
+                self.saved_game().map_or(Ok(()), |saved| saved.store(path))
+            }
+            _ if self.is_board_in_progress() => Ok(()),
+            ConfirmQuit | ConfirmRestart | Settings | ConfirmRecover => Ok(()),
+            _ => SavedGame::remove(path),
+        };
+        if let Err(e) = result {
+            self.error.get_or_insert(e);
+        }
+    }
+
+    /// The board as it would be saved, if one was dealt.
+    fn saved_game(&self) -> Option<SavedGame> {
+        let dealt = self.dealt.as_ref()?;
+        let cards = |board: &Board| -> Vec<Option<u32>> {
+            board.cards.iter().map(|c| c.map(|c| c.0)).collect()
+        };
+        Some(SavedGame {
+            board: dealt.encode(),
+            layers: [&self.board]
+                .into_iter()
+                .chain(&self.under)
+                .map(cards)
+                .collect(),
+            depth: self.depth.clone(),
+            discovered: self.discovered.iter().by_vals().collect(),
+            guesses: self.guesses,
+            hints: self.hints,
+            mismatches: self.mismatches,
+            turns: self.turns,
+            points: self.score.points,
+            streak: self.score.streak,
+            best_streak: self.score.best_streak,
+            elapsed_ms: self.elapsed().as_millis() as u64,
+        })
+    }
+
+    /// Check if a board is being played, as opposed to not being dealt yet
+    /// or already cleared.
+    fn is_board_in_progress(&self) -> bool {
+        matches!(
+            self.state,
+            Memorize
+                | Preview
+                | Guess
+                | CorrectGuessConfirm
+                | IncorrectGuessConfirm
+                | BombExploded
+        )
+    }
+
+    /// The size picked by the latest user input, or `None` for a custom
+    /// size. Empty input picks [`SizePreset::DEFAULT`]. Dimensions such as
+    /// `6,4` or `6x4` are taken as they are, which is how bots and the
+    /// [JSON protocol](`crate::protocol`) start a game.
+    fn pick_size(&self) -> Result<Option<Vec2>> {
+        let input = self.user_input.trim();
+        if input.is_empty() {
+            return Ok(Some(SizePreset::DEFAULT.size()));
+        }
+        let max = SizePreset::ALL.len() + 1;
+        match input.parse::<usize>() {
+            Ok(choice) if (1..max).contains(&choice) => {
+                Ok(Some(SizePreset::ALL[choice - 1].size()))
+            }
+            Ok(choice) if choice == max => Ok(None),
+            Ok(_) => Err(GameError::InvalidMenuChoice { max }),
+            Err(_) => {
+                let size = config::parse_size(input)
+                    .or_else(|_| input::parse_pair(input))
+                    .map_err(|_| GameError::InvalidMenuChoice { max })?;
+                input::validate_dimensions(size)?;
+                Ok(Some(size))
+            }
+        }
+    }
+
+    /// The setting picked by the latest user input, or `None` to leave the
+    /// settings menu, which is what empty input does.
+    fn pick_setting(&self) -> Result<Option<Setting>> {
+        let input = self.user_input.trim();
+        if input.is_empty() {
+            return Ok(None);
+        }
+        let max = Setting::ALL.len();
+        match input.parse::<usize>() {
+            Ok(choice) if (1..=max).contains(&choice) => {
+                Ok(Some(Setting::ALL[choice - 1]))
+            }
+            _ => Err(GameError::InvalidMenuChoice { max }),
+        }
+    }
+
+    /// Switch `setting` to its next value and save it to the configuration
+    /// file, if there is one. The change is kept for this session even if
+    /// saving it fails.
+    fn change_setting(&mut self, setting: Setting) -> Result<()> {
+        match setting {
+            Setting::Colors => {
+                let on = !self.style.is_enabled();
+                self.style = Style::new(on.then(ColorDepth::detect));
+                let mode = if on {
+                    ColorMode::Always
+                } else {
+                    ColorMode::Never
+                };
+                self.save_setting("colors", mode)
+            }
+            Setting::CardSet => {
+                self.card_set = self.card_set.next();
+                self.save_setting("card_set", self.card_set)
+            }
+            Setting::Difficulty => {
+                self.difficulty = self.difficulty.next();
+                self.save_setting("difficulty", self.difficulty)
+            }
+            Setting::Sound => {
+                self.muted = !self.muted;
+                self.emit(Event::SoundToggled { muted: self.muted });
+                self.save_setting("mute", self.muted)
+            }
+        }
+    }
+
+    /// Save `value` as the setting `key` in the configuration file, if
+    /// there is one.
+    fn save_setting(&self, key: &str, value: impl Serialize) -> Result<()> {
+        match &self.config_file {
+            Some(path) => Config::store(path, key, value),
+            None => Ok(()),
+        }
+    }
+
+    /// The 0-based campaign level picked by the latest user input. Empty
+    /// input picks the furthest level unlocked.
+    fn pick_level(&self) -> Result<usize> {
+        let furthest = self.campaign.as_ref().map_or(0, Campaign::furthest);
+        let input = self.user_input.trim();
+        if input.is_empty() {
+            return Ok(furthest);
+        }
+        let level = match input.parse::<usize>() {
+            Ok(level) if (1..=LEVELS.len()).contains(&level) => level,
+            _ => return Err(GameError::InvalidLevel { max: LEVELS.len() }),
+        };
+        if level - 1 > furthest {
+            return Err(GameError::LevelLocked { level });
+        }
+        Ok(level - 1)
+    }
+
+    /// Deal the board of the picked campaign level with its rules.
+    fn start_level(&mut self) -> Result<()> {
+        let level = LEVELS[self.level];
+        self.set_difficulty(level.difficulty);
+        self.set_layout(level.layout);
+        self.set_layers(level.layers);
+        self.set_min_pair_distance(level.min_pair_distance);
+        self.create_board(&Shape::rectangle(level.size))?;
+        self.fire(Trigger::Deal);
+        Ok(())
+    }
+
+    /// Deal the board of the next round of the tournament, or end the game
+    /// after the last one.
+    fn next_round(&mut self) {
+        let next = self.tournament.as_ref().and_then(Tournament::next_round);
+        match next {
+            Some(size) => match self.create_board(&Shape::rectangle(size)) {
+                Ok(()) => self.fire(Trigger::Deal),
+                Err(e) => self.error = Some(e),
+            },
+            None => self.fire(Trigger::Exit),
+        }
+    }
+
+    /// Abandon the current board and deal the next one as `redeal` says.
+    fn deal_again(&mut self, redeal: Redeal) {
+        self.clear_revealed();
+        let dealt = match redeal {
+            Redeal::Shuffle => self.create_board(&self.board.shape()),
+            Redeal::Rematch => match self.dealt.clone() {
+                Some(code) => self.deal_code(&code),
+                None => Err(GameError::CommandUnavailable {
+                    command: redeal.command().to_owned(),
+                }),
+            },
+            Redeal::Resize => {
+                self.fire(Trigger::Resize);
+                return;
+            }
+        };
+        match dealt {
+            Ok(()) => self.fire(Trigger::Deal),
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    /// Redraw the whole screen on the next render, laid out for the new
+    /// size of the terminal.
+    pub fn resized(&mut self) {
+        self.screen.invalidate();
+    }
+
+    /// Render the current state, redrawing only what changed since the
+    /// last call.
+    pub fn render(&mut self) {
+        let mut frame = Frame::new();
+        self.draw(&mut frame);
+        if self.accessible {
+            // Screen readers follow the output as it is printed
+            self.screen.append(&frame);
+        } else {
+            self.screen.present(&frame);
+        }
+    }
+
+    /// Tell if the player is typing a line after the frame, which redrawing
+    /// it then leaves alone.
+    pub fn set_typing(&mut self, typing: bool) {
+        self.screen.set_typing(typing);
+    }
+
+    /// Keep every rendered frame in memory instead of drawing it on the
+    /// terminal, e.g. to compare them with the expected output in tests.
+    pub fn capture_frames(&mut self) {
+        self.screen = Screen::capture();
+    }
+
+    /// Take the frames rendered since the last call, if they are
+    /// [captured](`Game::capture_frames`).
+    pub fn take_frames(&mut self) -> Vec<String> {
+        self.screen.take_frames()
+    }
+
+    /// Draw the current state into `out`.
+    fn draw(&self, out: &mut Frame) {
+        if let Some(overlay) = self.overlay {
+            self.render_overlay(overlay, out);
+            return;
+        }
+        let view = self.view();
+        match self.state {
+            Welcome => self.render_error(out),
+            SizeMenu => {
+                self.render_size_menu(out);
+                self.render_error(out);
+            }
+            SetDimensions => {
+                self.render_ratings(out);
+                self.render_error(out);
+            }
+            Memorize | Preview => {
+                self.render_score(out);
+                self.render_board(out);
+            }
+            Guess => {
+                self.render_score(out);
+                self.render_board(out);
+                self.render_error(out);
+                view.draw_messages(out);
+                if self.awaits_opponent() {
+                    view.draw_prompt(out);
+                    return;
+                }
+                self.render_hint(out);
+                self.render_odds(out);
+                self.render_power_ups(out);
+                self.render_action(out);
+                if self.guesses == 0 && self.revealed1.is_none() {
+                    if self.has_jokers() {
+                        let joker = Card::JOKER_CHAR;
+                        out.line(t!("joker-legend", joker = joker));
+                    }
+                    let bombs = self.bombs_left();
+                    if bombs > 0 {
+                        out.line(t!("bombs-hidden", count = bombs));
+                    }
+                    self.render_board_code(out);
+                }
+                self.render_tutorial(out);
+            }
+            CorrectGuessConfirm | IncorrectGuessConfirm => {
+                self.render_score(out);
+                self.render_board(out);
+                self.render_action(out);
+                self.render_tutorial(out);
+            }
+            BombExploded => {
+                self.render_score(out);
+                self.render_board(out);
+            }
+            Victory if self.tutorial => {
+                self.render_score(out);
+                self.render_board(out);
+            }
+            Victory if self.multiplayer.is_some() => {
+                self.render_score(out);
+                self.render_board(out);
+                self.render_outcome(out);
+            }
+            Victory if self.daily.is_some() => {
+                self.render_score(out);
+                self.render_board(out);
+                self.render_final_score(out);
+                self.render_stats(out);
+                self.render_summary(out);
+                self.render_analysis(out);
+                self.render_error(out);
+                self.render_daily(out);
+            }
+            Victory if self.tournament.is_some() => {
+                self.render_score(out);
+                self.render_board(out);
+                self.render_final_score(out);
+                self.render_stats(out);
+                self.render_error(out);
+                self.render_tournament(out);
+            }
+            Victory => {
+                self.render_score(out);
+                self.render_board(out);
+                self.render_final_score(out);
+                self.render_stats(out);
+                self.render_summary(out);
+                self.render_analysis(out);
+                self.render_comparison(out);
+                self.render_board_code(out);
+                self.render_error(out);
+            }
+            LevelSelect => {
+                self.render_levels(out);
+                self.render_error(out);
+            }
+            LevelIntro => {
+                self.render_level_intro(out);
+                self.render_error(out);
+            }
+            LevelOutro => {
+                self.render_score(out);
+                self.render_board(out);
+                self.render_final_score(out);
+                self.render_stats(out);
+                self.render_error(out);
+                self.render_level_outro(out);
+            }
+            Defeat => {
+                self.render_score(out);
+                self.render_board(out);
+                self.render_stats(out);
+                self.render_analysis(out);
+                self.render_error(out);
+            }
+            ConfirmQuit | ConfirmRestart => {
+                self.render_score(out);
+                self.render_board(out);
+                self.render_error(out);
+            }
+            ConfirmRecover => self.render_error(out),
+            Settings => {
+                self.render_settings(out);
+                self.render_error(out);
+            }
+            Exit => {}
+        }
+        if self.state != Guess {
+            view.draw_messages(out);
+        }
+        view.draw_prompt(out);
+    }
+
+    /// Take a snapshot of what is shown of the game, for other frontends
+    /// and for checking the game without reading the rendered text.
+    pub fn view(&self) -> GameView {
+        GameView {
+            state: self.state,
+            size: Vec2 {
+                x: self.idx.size_x,
+                y: self.idx.size_y,
+            },
+            cells: self.idx.iter_all().map(|c| self.cell_view(c)).collect(),
+            score: ScoreView {
+                points: self.score.points,
+                streak: self.score.streak,
+                guesses: self.guesses,
+                pairs_found: self.pairs_found(),
+                pairs: self.pairs(),
+                hints: self.hints,
+                lives: self.lives_left(),
+            },
+            messages: self.messages(),
+            prompt: self.prompt(),
+        }
+    }
+
+    /// What the cell at `c` shows.
+    fn cell_view(&self, c: Vec2) -> Cell {
+        let status = if !self.board.has_card(c) {
+            CellStatus::Hole
+        } else if self.is_discovered(c) {
+            CellStatus::Matched
+        } else if self.is_revealed(c) {
+            CellStatus::Revealed
+        } else if self.is_peeked(c) || self.is_face_up_phase() {
+            CellStatus::FaceUp
+        } else {
+            CellStatus::FaceDown
+        };
+        let card = match status {
+            CellStatus::Hole | CellStatus::FaceDown => None,
+            _ => Some(self.board[c]),
+        };
+        Cell { card, status }
+    }
+
+    /// Feedback about the last update, in the order it is shown.
+    fn messages(&self) -> Vec<view::Message> {
+        let mut messages = Vec::new();
+        match self.state {
+            Guess if self.turn_expired.is_some() => {
+                let local = self.multiplayer.as_ref().map(|mp| mp.local);
+                messages.push(view::Message::TurnExpired {
+                    yours: self.turn_expired == local,
+                });
+            }
+            Guess if !self.awaits_opponent() => {
+                if self.reshuffle_announced {
+                    messages.push(view::Message::CardsShuffled);
+                }
+                if !self.decayed.is_empty() {
+                    let count = self.decayed.len() / 2;
+                    messages.push(view::Message::PairsFaded { count });
+                }
+            }
+            CorrectGuessConfirm => {
+                messages.push(view::Message::CorrectGuess);
+                if self.multiplayer.is_none() {
+                    messages.push(view::Message::PointsEarned {
+                        points: self.last_points,
+                        streak: self.score.streak,
+                    });
+                }
+                if let Some(power) = self.earned {
+                    messages.push(view::Message::PowerUpEarned {
+                        name: power.name(),
+                    });
+                }
+            }
+            IncorrectGuessConfirm if self.timed_out => {
+                messages.push(view::Message::TimeUp);
+            }
+            IncorrectGuessConfirm => {
+                messages.push(view::Message::IncorrectGuess)
+            }
+            BombExploded => messages.push(match self.bomb_effect {
+                BombEffect::Penalty => view::Message::BombPenalty {
+                    points: self.last_points,
+                },
+                BombEffect::Shuffle => view::Message::BombShuffle,
+            }),
+            Victory if self.tutorial => {
+                messages.push(view::Message::TutorialDone)
+            }
+            Defeat => messages.push(view::Message::OutOfLives),
+            _ => {}
+        }
+        messages
+    }
+
+    /// What the player is asked for in the current state, if anything.
+    fn prompt(&self) -> Option<Prompt> {
+        let prompt = match self.state {
+            Welcome => Prompt::Welcome,
+            SizeMenu => Prompt::SizeMenu {
+                max: SizePreset::ALL.len() + 1,
+                choice: SizePreset::ALL
+                    .iter()
+                    .position(|preset| *preset == SizePreset::DEFAULT)
+                    .unwrap_or_default()
+                    + 1,
+                name: SizePreset::DEFAULT.name(),
+            },
+            SetDimensions => Prompt::SetDimensions,
+            Memorize => Prompt::Memorize,
+            Preview => Prompt::Preview {
+                seconds: self.preview.unwrap_or_default().as_secs(),
+            },
+            Guess if self.awaits_opponent() => Prompt::WaitingForOpponent {
+                can_chat: self.can_chat(),
+            },
+            Guess => Prompt::PickCard {
+                seconds_left: self.guess_deadline.map(|deadline| {
+                    let left =
+                        deadline.saturating_duration_since(Instant::now());
+                    // Round up, so that the countdown ends at 1
+                    left.as_millis().div_ceil(1000)
+                }),
+            },
+            Victory if self.tutorial => Prompt::PressEnterToContinue,
+            Victory if self.multiplayer.is_some() || self.daily.is_some() => {
+                Prompt::PressEnterToExit
+            }
+            Victory if self.tournament.is_some() => return None,
+            Victory => Prompt::PlayAgain,
+            Defeat if self.tournament.is_some() || self.daily.is_some() => {
+                Prompt::PressEnterToExit
+            }
+            Defeat if self.campaign.is_some() => Prompt::PressEnterToContinue,
+            Defeat => Prompt::PlayAgainAfterDefeat,
+            LevelSelect => Prompt::LevelSelect {
+                level: self.level + 1,
+                max: LEVELS.len(),
+            },
+            LevelIntro => Prompt::LevelStart,
+            ConfirmQuit => Prompt::ConfirmQuit {
+                can_save: self.can_save(self.resume),
+            },
+            ConfirmRestart => match self.redeal {
+                Redeal::Shuffle => Prompt::ConfirmRestart,
+                Redeal::Rematch => Prompt::ConfirmRematch,
+                Redeal::Resize => Prompt::ConfirmResize,
+            },
+            Settings => Prompt::Settings {
+                max: Setting::ALL.len(),
+            },
+            ConfirmRecover => Prompt::ConfirmRecover,
+            CorrectGuessConfirm
+            | IncorrectGuessConfirm
+            | BombExploded
+            | LevelOutro
+            | Exit => return None,
+        };
+        Some(prompt)
+    }
+
+    /// Describe the current state in machine-readable form.
+    pub fn snapshot(&self) -> Snapshot {
+        let state = self.state.name();
+        let board = (0..self.idx.size_y)
+            .map(|y| {
+                (0..self.idx.size_x)
+                    .map(|x| {
+                        let c = Vec2 { x, y };
+                        if !self.board.has_card(c) {
+                            None
+                        } else if self.is_discovered(c)
+                            || self.is_revealed(c)
+                            || self.is_peeked(c)
+                            || self.is_face_up_phase()
+                        {
+                            Some(self.board[c].to_string())
+                        } else {
+                            None
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        let revealed = [self.revealed1, self.revealed2]
+            .iter()
+            .flatten()
+            .map(|c| [c.x + 1, c.y + 1])
+            .collect();
+        let layers = self
+            .depth
+            .chunks(self.idx.size_x.max(1) as usize)
+            .map(|row| row.iter().map(|z| z + 1).collect())
+            .collect();
+        let holes = self
+            .idx
+            .iter_all()
+            .filter(|c| !self.board.has_card(*c))
+            .map(|c| [c.x + 1, c.y + 1])
+            .collect();
+
+        Snapshot {
+            state,
+            size: [self.idx.size_x, self.idx.size_y],
+            board,
+            holes,
+            layout: self.layout,
+            layers,
+            revealed,
+            guesses: self.guesses,
+            pairs_found: self.pairs_found() as i32,
+            hints: self.hints,
+            lives: self.lives_left(),
+            power_ups: PowerUp::ALL
+                .into_iter()
+                .filter(|p| self.power_ups.count(*p) > 0)
+                .map(|p| (p.name(), self.power_ups.count(p)))
+                .collect(),
+            hinted: self.hinted.iter().map(|c| [c.x + 1, c.y + 1]).collect(),
+            reshuffled: self.reshuffled,
+            decayed: self.decayed.iter().map(|c| [c.x + 1, c.y + 1]).collect(),
+            score: self.score.points,
+            streak: self.score.streak,
+            error: self.error.as_ref().map(GameError::as_string),
+            error_code: self.error.as_ref().map(GameError::code),
+        }
+    }
+
+    /// If the input holds several coordinates, e.g. `1,1 2,3`, keep the
+    /// first one as the input and queue the others.
+    fn queue_batch(&mut self) {
+        // E.g. `3 4` is a single pair of coordinates, not cells 3 and 4
+        if input::parse_coords(&self.user_input, &self.idx).is_ok() {
+            return;
+        }
+        let tokens: Vec<&str> = self.user_input.split_whitespace().collect();
+        if tokens.len() < 2
+            || tokens
+                .iter()
+                .any(|t| input::parse_coords(t, &self.idx).is_err())
+        {
+            return;
+        }
+        self.queued = tokens[1..].iter().map(|t| t.to_string()).collect();
+        self.user_input = tokens[0].to_owned();
+    }
+
+    /// Attempt to create a new board from the latest user input and prepare
+    /// for the game to begin.
+    fn set_dimensions(&mut self) -> Result<()> {
+        let size = input::parse_dimensions(&self.user_input)?;
+        self.create_board(&Shape::rectangle(size))
+    }
+
+    /// Replace the board with a freshly shuffled one of the given shape.
+    fn create_board(&mut self, shape: &Shape) -> Result<()> {
+        let extra = match self.multiplayer {
+            None if self.layers == 1 => {
+                [vec![Card::JOKER; self.jokers], vec![Card::BOMB; self.bombs]]
+                    .concat()
+            }
+            _ => Vec::new(),
+        };
+        let mut layers = match self.card_set {
+            CardSet::Playing if self.layers == 1 => {
+                vec![Board::playing(shape, &extra, &mut *self.rng)?]
+            }
+            _ => {
+                // Symbols are only dealt if the terminal can draw them
+                let kinds = match self.card_set {
+                    CardSet::Ascii => Board::ascii_cards(),
+                    _ if !self.unicode => Board::ascii_cards(),
+                    CardSet::Emoji => Board::emoji_cards(),
+                    _ => Board::symbol_cards(),
+                };
+                Board::layered(
+                    shape,
+                    self.layers,
+                    &kinds,
+                    &extra,
+                    &mut *self.rng,
+                )?
+            }
+        };
+        self.idx = *shape.idx();
+        if self.min_pair_distance > 1 {
+            let grid = self.grid();
+            for layer in &mut layers {
+                layer.spread_pairs(
+                    &*grid,
+                    self.min_pair_distance,
+                    &mut *self.rng,
+                );
+            }
+        }
+        self.dealt = Some(BoardCode {
+            seed: self.seed,
+            difficulty: self.difficulty,
+            layout: self.layout,
+            hint_penalty: self.hint_penalty,
+            min_pair_distance: self.min_pair_distance,
+            size: Vec2 {
+                x: self.idx.size_x,
+                y: self.idx.size_y,
+            },
+            layers: layers
+                .iter()
+                .map(|layer| {
+                    layer.cards.iter().map(|c| c.map(|c| c.0)).collect()
+                })
+                .collect(),
+        });
+        self.board = layers.remove(0);
+        self.under = layers;
+        self.depth = vec![0; self.board.cards.len()];
+        self.ages = vec![None; self.board.cards.len()];
+        // Holes count as discovered, so that the board is cleared once
+        // every card is
+        self.discovered =
+            self.board.cards.iter().map(Option::is_none).collect();
+        self.guesses = 0;
+        self.hints = 0;
+        self.mismatches = 0;
+        self.turns = 0;
+        self.started = Some(Instant::now());
+        self.power_ups = Inventory::default();
+        self.peeked.clear();
+        self.peek_until = None;
+        self.freeze_until = None;
+        self.frozen = Duration::ZERO;
+        self.finish_time = None;
+        self.stopped = None;
+        self.history.clear();
+        self.seen.clear();
+        self.show_heatmap = false;
+        self.score = Score::new(Instant::now());
+        self.last_points = 0;
+        self.view = Vec2 { x: 0, y: 0 };
+        self.queued.clear();
+
+        if let Some(mp) = &mut self.multiplayer {
+            let size = Vec2 {
+                x: self.idx.size_x,
+                y: self.idx.size_y,
+            };
+            let cards = self.board.cards.iter().map(|c| c.map(|c| c.0));
+            let sealed = Sealed::new(size, cards.collect());
+            let msg = Message::Commit {
+                size,
+                holes: self.board.cards.iter().map(Option::is_none).collect(),
+                hash: sealed.commitment(),
+            };
+            mp.sealed = Some(sealed);
+            mp.conn.send(&msg).map_err(connection_lost)?;
+        }
+        self.introduce()
+    }
+
+    /// Send the opponent the name and rating of the local player, once the
+    /// board of a networked game is dealt, if the rating is kept.
+    fn introduce(&mut self) -> Result<()> {
+        let (Some(mp), Some(store)) = (&mut self.multiplayer, &self.ratings)
+        else {
+            return Ok(());
+        };
+        let dealt = mp.sealed.is_some() || mp.commitment.is_some();
+        if mp.spectating || mp.introduced || !dealt {
+            return Ok(());
+        }
+        mp.introduced = true;
+        let msg = Message::Player {
+            name: profile::current().unwrap_or_default().to_owned(),
+            rating: store.ratings.rating.round().max(0.0) as u32,
+        };
+        mp.conn.send(&msg).map_err(connection_lost)
+    }
+
+    /// Let the opponent know about a card revealed by either player. The
+    /// host sends the face of every card revealed, while the joining player
+    /// sends the cards it picks and learns their faces from the answer.
+    fn send_reveal(&mut self, c: Vec2) -> Result<()> {
+        let Some(mp) = &mut self.multiplayer else {
+            return Ok(());
+        };
+        if mp.sealed.is_some() {
+            let msg = Message::Revealed {
+                at: c,
+                card: self.board[c].0,
+            };
+            return mp.conn.send(&msg).map_err(connection_lost);
+        }
+        if !mp.is_local_turn() {
+            return Ok(());
+        }
+        mp.conn.send(&Message::Reveal(c)).map_err(connection_lost)?;
+        match mp.recv().map_err(connection_lost)? {
+            Message::Revealed { at, card } if at == c => {
+                self.learn_card(at, card);
+                Ok(())
+            }
+            // The card was picked too late, and is ignored
+            Message::TurnExpired { seat } => {
+                self.expire_turn(seat);
+                Ok(())
+            }
+            msg => Err(connection_lost(unexpected("the card", &msg))),
+        }
+    }
+
+    /// Send a line of chat to the opponent, and show it beside theirs.
+    fn say(&mut self, text: &str) -> Result<()> {
+        let Some(mp) = &mut self.multiplayer else {
+            return Ok(());
+        };
+        let text = chat::sanitize(text).ok_or(GameError::EmptyInput)?;
+        let line = mp
+            .chat
+            .add(mp.local, &text, Instant::now())
+            .ok_or(GameError::ChatTooFast)?;
+        let msg = Message::Chat {
+            seat: mp.local,
+            text: line.text.clone(),
+        };
+        mp.conn.send(&msg).map_err(connection_lost)
+    }
+
+    /// Pass play to the other player, as the [lobby](`crate::lobby`) says
+    /// the turn of the player in `seat` ran out of time. A card picked in
+    /// the turn is turned back over.
+    fn expire_turn(&mut self, seat: usize) {
+        let Some(mp) = &mut self.multiplayer else {
+            return;
+        };
+        mp.turn = 1 - seat;
+        self.turn_expired = Some(seat);
+        for at in [self.revealed1, self.revealed2].into_iter().flatten() {
+            self.flips.push(Flip::hide(at));
+        }
+        self.clear_revealed();
+        self.queued.clear();
+    }
+
+    /// Check that `state` fits the board being watched: the same size and
+    /// holes, with cards that can be dealt on the cells turned over.
+    fn check_room_state(&self, state: &RoomState) -> io::Result<()> {
+        let size = Vec2 {
+            x: self.idx.size_x,
+            y: self.idx.size_y,
+        };
+        let cards = &self.board.cards;
+        let fits =
+            state.size == size
+                && state.turn < 2
+                && state.cells.len() == cards.len()
+                && state.cells.iter().zip(cards).all(
+                    |(cell, card)| match cell {
+                        net::Cell::Hole => card.is_none(),
+                        net::Cell::FaceDown => card.is_some(),
+                        net::Cell::Revealed(id) | net::Cell::Found(id) => {
+                            card.is_some() && Card(*id).is_valid()
+                        }
+                    },
+                );
+        if fits {
+            Ok(())
+        } else {
+            Err(invalid_data(ROOM_STATE_MISFIT))
+        }
+    }
+
+    /// Show the state of the room being watched: the faces of the cards
+    /// picked and found, whose turn it is and how many pairs each player
+    /// found.
+    fn watch(&mut self, state: RoomState) {
+        let Some(mp) = &mut self.multiplayer else {
+            return;
+        };
+        mp.turn = state.turn;
+        mp.pairs = state.pairs.map(|pairs| pairs as i32);
+        let shown = [self.revealed1, self.revealed2];
+        self.clear_revealed();
+        for (i, cell) in state.cells.into_iter().enumerate() {
+            let at = self.idx.coords(i);
+            let was_shown = shown.contains(&Some(at));
+            match cell {
+                net::Cell::Hole => {}
+                net::Cell::FaceDown if was_shown => {
+                    self.flips.push(Flip::hide(at))
+                }
+                net::Cell::FaceDown => {}
+                net::Cell::Revealed(card) => {
+                    self.board[at] = Card(card);
+                    self.set_revealed(at);
+                    if !was_shown {
+                        self.flips.push(Flip::show(at));
+                    }
+                }
+                net::Cell::Found(card) => {
+                    self.board[at] = Card(card);
+                    if !was_shown && !self.is_discovered(at) {
+                        self.flips.push(Flip::show(at));
+                    }
+                    self.set_discovered(at);
+                }
+            }
+        }
+        if self.all_discovered() {
+            self.finish();
+            self.fire(Trigger::Clear);
+        }
+    }
+
+    /// Put the face of a card revealed by the host on the board of the
+    /// joining player, and note it to check against the commitment.
+    fn learn_card(&mut self, at: Vec2, card: u32) {
+        self.board[at] = Card(card);
+        let mp = self.multiplayer.as_mut();
+        if let Some(commitment) = mp.and_then(|mp| mp.commitment.as_mut()) {
+            commitment.saw(at, card);
+        }
+    }
+
+    /// Check if the pair just revealed is the last one left on the board.
+    fn is_last_pair(&self) -> bool {
+        self.idx.iter_all().all(|c| {
+            !self.board.has_card(c)
+                || self.is_discovered(c)
+                || self.is_revealed(c)
+        })
+    }
+
+    /// Open the commitment to the board once its last pair is found, for
+    /// the joining player to verify. Only the host has one to open.
+    fn open_board(&mut self) -> Result<()> {
+        let Some(mp) = &mut self.multiplayer else {
+            return Ok(());
+        };
+        let Some(sealed) = &mp.sealed else {
+            return Ok(());
+        };
+        let (salt, cards) = sealed.open();
+        let msg = Message::Open { salt, cards };
+        mp.conn.send(&msg).map_err(connection_lost)
+    }
+
+    /// Wait for the host to open its commitment once the board is cleared,
+    /// and check it against the cards revealed, on the joining side.
+    fn verify_board(&mut self) -> Result<()> {
+        let Some(mp) = &mut self.multiplayer else {
+            return Ok(());
+        };
+        if mp.commitment.is_none() {
+            return Ok(());
+        }
+        match mp.recv().map_err(connection_lost)? {
+            Message::Open { salt, cards } => {
+                let commitment = mp.commitment.as_ref();
+                mp.verified = commitment.map(|c| c.verify(&salt, &cards));
+                Ok(())
+            }
+            msg => Err(connection_lost(unexpected("the board", &msg))),
+        }
+    }
+
+    /// Notify every observer of `event`.
+    fn emit(&mut self, event: Event) {
+        self.log_guess(&event);
+        for observer in &mut self.observers {
+            observer.notify(&event);
+        }
+    }
+
+    /// Number of guesses kept in the history panel.
+    const HISTORY_LEN: usize = 8;
+
+    /// Number of guesses shown in the history panel when it doesn't fit
+    /// beside the board and is drawn below it.
+    const HISTORY_BELOW: usize = 3;
+
+    /// Number of lines of chat shown below the history.
+    const CHAT_LEN: usize = 5;
+
+    /// Number of lines of chat shown when the panel is drawn below the
+    /// board.
+    const CHAT_BELOW: usize = 2;
+
+    /// Add the guess that `event` ends, if any, to the history, along with
+    /// what had been seen before it, and remember its cards.
+    fn log_guess(&mut self, event: &Event) {
+        let (picks, outcome) = match *event {
+            Event::PairMatched { first, second, .. } => {
+                (vec![first, second], Outcome::Match)
+            }
+            Event::Mismatch { first, second } => {
+                (vec![first, second], Outcome::Mismatch)
+            }
+            Event::BombExploded => (self.revealed(), Outcome::Bomb),
+            Event::TimeUp => (self.revealed(), Outcome::TimeUp),
+            _ => return,
+        };
+        let number = self.history.last().map_or(0, |guess| guess.number) + 1;
+        let mut seen: Vec<_> =
+            self.seen.iter().map(|(c, k)| (*c, *k)).collect();
+        seen.sort_by_key(|(c, _)| (c.y, c.x));
+        let picks: Vec<_> = picks
+            .into_iter()
+            .map(|at| {
+                let card = self.board[at];
+                Pick {
+                    at,
+                    card,
+                    seen: self.seen.get(&at) == Some(&card),
+                    partner: seen
+                        .iter()
+                        .find(|(c, other)| *c != at && card.pairs_with(other))
+                        .map(|(c, _)| *c),
+                }
+            })
+            .collect();
+        for pick in &picks {
+            if outcome == Outcome::Match {
+                self.seen.remove(&pick.at);
+            } else {
+                self.seen.insert(pick.at, pick.card);
+            }
+        }
+        self.history.push(Guess {
+            number,
+            picks,
+            outcome,
+            known_pair: analysis::known_pair(&seen),
+        });
+    }
+
+    /// The positions of every card turned over on the current board, once
+    /// for every time.
+    fn picks(&self) -> Vec<Vec2> {
+        let picks = self.history.iter().flat_map(|guess| &guess.picks);
+        picks.map(|pick| pick.at).collect()
+    }
+
+    /// Forget the cards seen where they aren't anymore, e.g. after a
+    /// shuffle.
+    fn forget_moved(&mut self) {
+        let board = &self.board;
+        self.seen
+            .retain(|c, card| board.has_card(*c) && board[*c] == *card);
+    }
+
+    /// The cards turned face up in the current guess, in order.
+    fn revealed(&self) -> Vec<Vec2> {
+        [self.revealed1, self.revealed2]
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    /// Let the observers know if the state changed from `before`.
+    fn notify_state_change(&mut self, before: GameState) {
+        if self.state != before {
+            self.emit(Event::StateChanged {
+                from: before.name(),
+                to: self.state.name(),
+            });
+            self.update_recording();
+        }
+    }
+
+    /// Add the latest input to the [recording](`Game::set_replay_file`), if
+    /// the recorded board is in play.
+    fn record_input(&mut self) {
+        let queued = mem::take(&mut self.input_queued);
+        if queued || self.recording_done {
+            return;
+        }
+        if let Some((replay, dealt_at)) = &mut self.recording {
+            replay.moves.push(Move {
+                at_ms: dealt_at.elapsed().as_millis() as u64,
+                input: self.user_input.clone(),
+            });
+        }
+    }
+
+    /// Start the [recording](`Game::set_replay_file`) once the first board
+    /// is dealt, and write it out once that board is over or left. Moves
+    /// made while a question about the board is asked are kept.
+    fn update_recording(&mut self) {
+        let Some(path) = &self.replay_file else {
+            return;
+        };
+        if self.recording_done {
+            return;
+        }
+        let ongoing = self.is_board_in_progress()
+            || matches!(self.state, ConfirmQuit | ConfirmRestart | Settings);
+        match (&self.recording, &self.dealt) {
+            (None, Some(dealt)) if ongoing => {
+                self.recording = Some((Replay::new(dealt), Instant::now()));
+            }
+            (Some((replay, _)), _) if !ongoing => {
+                self.recording_done = true;
+                if let Err(e) = replay.store(path) {
+                    self.error.get_or_insert(e);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Move on to the next guess, or finish the game if the last pair was
+    /// just found.
+    fn check_cleared(&mut self) {
+        if self.all_discovered() {
+            self.finish();
+            self.fire(match self.campaign {
+                Some(_) => Trigger::ClearLevel,
+                None => Trigger::Clear,
+            });
+        } else if self.state != Guess {
+            self.fire(Trigger::NextTurn);
+        }
+    }
+
+    /// Time spent on the current board, not counting while the clock was
+    /// frozen.
+    fn elapsed(&self) -> Duration {
+        let Some(started) = self.started else {
+            return Duration::ZERO;
+        };
+        // The rest of a freeze in effect hasn't been spent yet
+        let unspent = self
+            .freeze_until
+            .map(|t| t.saturating_duration_since(Instant::now()))
+            .unwrap_or_default();
+        started
+            .elapsed()
+            .saturating_sub(self.frozen.saturating_sub(unspent))
+    }
+
+    /// Add a power-up to the inventory if the streak just reached a length
+    /// that earns one. Power-ups are only earned in solo games.
+    fn earn_power_up(&mut self) {
+        if self.multiplayer.is_some() {
+            return;
+        }
+        if let Some(power) = PowerUp::for_streak(self.score.streak) {
+            self.power_ups.add(power);
+            self.earned = Some(power);
+        }
+    }
+
+    /// Use a power-up from the inventory. A peek needs the card in the
+    /// middle of the area to show as `arg`.
+    fn use_power_up(&mut self, power: PowerUp, arg: &str) -> Result<()> {
+        if self.power_ups.count(power) == 0 {
+            return Err(GameError::NoPowerUp {
+                name: power.name().to_owned(),
+            });
+        }
+        let now = Instant::now();
+        match power {
+            PowerUp::Peek => {
+                let center = input::parse_coords(arg, &self.idx)?;
+                self.peeked = self
+                    .idx
+                    .iter_all()
+                    .filter(|c| {
+                        (c.x - center.x).abs() <= 1
+                            && (c.y - center.y).abs() <= 1
+                    })
+                    .filter(|c| self.board.has_card(*c))
+                    .filter(|c| !self.is_discovered(*c))
+                    .collect();
+                for c in &self.peeked {
+                    self.seen.insert(*c, self.board[*c]);
+                }
+                self.peek_until = Some(now + PowerUp::PEEK_TIME);
+            }
+            PowerUp::Freeze => {
+                // A freeze used during another one extends it
+                let from =
+                    self.freeze_until.filter(|t| *t > now).unwrap_or(now);
+                self.freeze_until = Some(from + PowerUp::FREEZE_TIME);
+                self.frozen += PowerUp::FREEZE_TIME;
+                if let Some(deadline) = &mut self.guess_deadline {
+                    *deadline += PowerUp::FREEZE_TIME;
+                }
+            }
+            PowerUp::Match => {
+                if self.revealed1.is_some() {
+                    return Err(GameError::CommandUnavailable {
+                        command: power.name().to_owned(),
+                    });
+                }
+                let pairs: Vec<_> = self
+                    .idx
+                    .iter_all()
+                    .filter(|c| !self.is_discovered(*c))
+                    .filter_map(|c| Some((c, self.hidden_partner(c)?)))
+                    .collect();
+                let (first, second) = *pairs
+                    .choose(&mut *self.rng)
+                    .ok_or(GameError::PartnerCovered)?;
+                self.emit(Event::PairMatched {
+                    first,
+                    second,
+                    card: self.board[first],
+                });
+                self.clear_card(first);
+                self.clear_card(second);
+                self.check_cleared();
+            }
+        }
+        self.power_ups.take(power)
+    }
+
+    /// Check if the card at `c` is shown by a peek still in effect.
+    fn is_peeked(&self, c: Vec2) -> bool {
+        self.peek_until.is_some_and(|t| t > Instant::now())
+            && self.peeked.contains(&c)
+    }
+
+    /// Stop the clock and record the game.
+    fn finish(&mut self) {
+        let duration = self.elapsed();
+        self.finish_time = Some(duration);
+        self.stopped = Some(duration);
+        self.new_best = false;
+        self.emit(Event::GameWon {
+            guesses: self.guesses,
+            time: duration,
+        });
+        if let Err(e) = self.verify_board() {
+            self.error = Some(e);
+        }
+
+        let pairs = self.pairs();
+        let picks = self.picks();
+        let limited = self.lives_left().is_some();
+        let mode = self.mode();
+        if let Some(stats) = &mut self.stats {
+            let record = GameRecord {
+                size: Vec2 {
+                    x: self.idx.size_x,
+                    y: self.idx.size_y,
+                },
+                guesses: self.guesses,
+                pairs: pairs as i32,
+                duration,
+                won: true,
+                limited,
+                picks,
+                seed: self.seed,
+                mode,
+            };
+            match stats.record(&record) {
+                Ok(best) => self.new_best = best,
+                Err(e) => self.error = Some(e),
+            }
+        }
+
+        self.rate_match();
+
+        if let Some(campaign) = &mut self.campaign {
+            match campaign.complete(self.level) {
+                Ok(unlocked) => self.level_unlocked = unlocked,
+                Err(e) => self.error = Some(e),
+            }
+        }
+
+        if let Some(tournament) = &mut self.tournament {
+            tournament.record(RoundResult {
+                size: Vec2 {
+                    x: self.idx.size_x,
+                    y: self.idx.size_y,
+                },
+                pairs: pairs as i32,
+                guesses: self.guesses,
+                time: duration,
+            });
+        }
+
+        if let Some((daily, log)) = &mut self.daily {
+            match log.record(&daily.date, self.guesses, duration) {
+                Ok(recorded) => self.daily_recorded = recorded,
+                Err(e) => self.error = Some(e),
+            }
+        }
+    }
+
+    /// Update the rating of the local player with the result of the
+    /// networked game that just ended. Opponents who didn't introduce
+    /// themselves are taken to be rated [`elo::INITIAL`].
+    fn rate_match(&mut self) {
+        let (Some(mp), Some(store)) = (&self.multiplayer, &mut self.ratings)
+        else {
+            return;
+        };
+        if mp.spectating {
+            return;
+        }
+        let mine = mp.pairs[mp.local];
+        let theirs = mp.pairs[1 - mp.local];
+        let result = match mine.cmp(&theirs) {
+            Ordering::Greater => MatchResult::Win,
+            Ordering::Equal => MatchResult::Draw,
+            Ordering::Less => MatchResult::Loss,
+        };
+        let (name, rating) = match &mp.opponent {
+            Some((name, rating)) => (name.as_str(), *rating as f64),
+            None => ("", elo::INITIAL),
+        };
+        match store.record(name, rating, result) {
+            Ok(before) => {
+                self.rating_change = Some((before, store.ratings.rating))
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    /// The kind of game being played, as recorded in the
+    /// [statistics](`GameRecord::mode`).
+    fn mode(&self) -> &'static str {
+        if self.tutorial {
+            "tutorial"
+        } else if self.daily.is_some() {
+            "daily"
+        } else if self.campaign.is_some() {
+            "campaign"
+        } else if self.tournament.is_some() {
+            "tournament"
+        } else if self.rival.is_some() {
+            "challenge"
+        } else if self.multiplayer.is_some() {
+            "networked"
+        } else {
+            "classic"
+        }
+    }
+
+    /// Stop the clock and record the lost game.
+    fn lose(&mut self) {
+        let duration = self.elapsed();
+        self.stopped = Some(duration);
+        self.emit(Event::GameLost {
+            guesses: self.guesses,
+            time: duration,
+        });
+
+        let pairs = self.pairs();
+        let picks = self.picks();
+        let mode = self.mode();
+        if let Some(stats) = &mut self.stats {
+            let record = GameRecord {
+                size: Vec2 {
+                    x: self.idx.size_x,
+                    y: self.idx.size_y,
+                },
+                guesses: self.guesses,
+                pairs: pairs as i32,
+                duration,
+                won: false,
+                limited: true,
+                picks,
+                seed: self.seed,
+                mode,
+            };
+            if let Err(e) = stats.record(&record) {
+                self.error = Some(e);
+            }
+        }
+    }
+
+    /// The rule preset in effect. Reshuffling or peeking at the board
+    /// would get the two sides of a networked game out of sync.
+    fn effective_difficulty(&self) -> Difficulty {
+        match self.multiplayer {
+            Some(_) => Difficulty::Normal,
+            None => self.difficulty,
+        }
+    }
+
+    /// The state to enter once a new board is ready.
+    fn first_turn_state(&self) -> GameState {
+        match self.effective_difficulty() {
+            Difficulty::Easy => Memorize,
+            _ if self.preview.is_some() && self.multiplayer.is_none() => {
+                Preview
+            }
+            _ => Guess,
+        }
+    }
+
+    /// Check that `trigger` leads somewhere from the current state, before
+    /// changing anything to fire it.
+    fn check_trigger(&self, trigger: Trigger) -> Result<()> {
+        match flow::transition(self.state, trigger) {
+            Some(_) => Ok(()),
+            None => Err(GameError::InvalidState {
+                state: self.state.name().to_owned(),
+            }),
+        }
+    }
+
+    /// Move on as the [transition table](`flow::TRANSITIONS`) says for
+    /// `trigger`, and carry out the effects of the transition. The game
+    /// stays where it is if `trigger` leads nowhere from the current state.
+    fn fire(&mut self, trigger: Trigger) {
+        let from = self.state;
+        let Some(transition) = flow::transition(from, trigger) else {
+            return;
+        };
+        self.state = match transition.to {
+            Target::To(state) => state,
+            Target::Resume => self.resume,
+            Target::FirstTurn => self.first_turn_state(),
+        };
+        for effect in transition.effects {
+            match effect {
+                Effect::Remember => self.resume = from,
+                Effect::StartTurn => self.score.start_turn(Instant::now()),
+                Effect::StartClock => {
+                    if self.state == Guess {
+                        self.start_guess_timer();
+                    }
+                    // A preview too long to tell the end of lasts until
+                    // it is ended with <Enter>
+                    self.preview_until = match self.state {
+                        Preview => self
+                            .preview
+                            .and_then(|time| Instant::now().checked_add(time)),
+                        _ => None,
+                    };
+                }
+            }
+        }
+    }
+
+    /// Check if the whole board is shown face up, either before the first
+    /// guess or after the game is lost.
+    fn is_face_up_phase(&self) -> bool {
+        matches!(self.state, Memorize | Preview | Defeat)
+    }
+
+    /// Number of incorrect guesses left before the game is lost, if
+    /// limited.
+    fn lives_left(&self) -> Option<u32> {
+        match self.multiplayer {
+            Some(_) => None,
+            None => self
+                .lives
+                .map(|lives| lives.saturating_sub(self.mismatches as u32)),
+        }
+    }
+
+    /// Set off the bomb that was just revealed, ending the guess. Depending
+    /// on [`Game::bomb_effect`], it either costs points and a life, or
+    /// shuffles the hidden cards around a random one.
+    fn explode(&mut self) {
+        let now = Instant::now();
+        // The rest of a batch of guesses was planned without the bomb
+        self.queued.clear();
+        self.emit(Event::BombExploded);
+        match self.bomb_effect {
+            BombEffect::Penalty => {
+                self.last_points = self.score.record_bomb(&self.scoring, now);
+            }
+            BombEffect::Shuffle => {
+                self.score.record_mismatch(now);
+                self.shuffle_area();
+            }
+        }
+        self.fire(Trigger::Bomb);
+    }
+
+    /// Shuffle the hidden cards up to [`Game::BLAST_RADIUS`] steps away from
+    /// a random hidden card, among themselves.
+    fn shuffle_area(&mut self) {
+        let hidden: Vec<Vec2> = self
+            .idx
+            .iter_all()
+            .filter(|c| self.board.has_card(*c) && !self.is_discovered(*c))
+            .filter(|c| !self.is_revealed(*c))
+            .collect();
+        let Some(&center) = hidden.choose(&mut *self.rng) else {
+            return;
+        };
+        let grid = self.grid();
+        self.board.reshuffle(&mut *self.rng, |c| {
+            !hidden.contains(&c)
+                || grid.distance(c, center) > Game::BLAST_RADIUS
+        });
+        self.reshuffled = true;
+        self.forget_moved();
+    }
+
+    /// Shuffle the cards that are neither matched nor revealed. Unless
+    /// `announce` is set, the player isn't told about it.
+    fn reshuffle(&mut self, announce: bool) {
+        let discovered = &self.discovered;
+        let idx = &self.idx;
+        let revealed = [self.revealed1, self.revealed2];
+        self.board.reshuffle(&mut *self.rng, |c| {
+            discovered[idx.unchecked(c)] || revealed.contains(&Some(c))
+        });
+        self.reshuffled = true;
+        self.reshuffle_announced |= announce;
+        self.forget_moved();
+    }
+
+    /// Count a completed guess, silently reshuffling the hidden cards and
+    /// turning old matches face down if it's time to.
+    fn end_turn(&mut self) {
+        self.turns += 1;
+        let due = self
+            .shuffle_every
+            .is_some_and(|n| self.turns.is_multiple_of(n));
+        if due && self.multiplayer.is_none() {
+            self.reshuffle(false);
+        }
+        if let (Some(decay), None) = (self.decay, &self.multiplayer) {
+            self.age_matches(decay);
+        }
+    }
+
+    /// Add a turn to the age of every matched card, and turn the ones older
+    /// than `decay` turns face down.
+    fn age_matches(&mut self, decay: u32) {
+        for (index, age) in self.ages.iter_mut().enumerate() {
+            let Some(turns) = age else {
+                continue;
+            };
+            *turns += 1;
+            if *turns > decay {
+                *age = None;
+                self.discovered.set(index, false);
+                let at = self.idx.coords(index);
+                self.decayed.push(at);
+                self.seen.insert(at, self.board[at]);
+                self.flips.push(Flip::hide(at));
+            }
+        }
+    }
+
+    /// Give the player [`Game::time_limit`] to make the next guess. A limit
+    /// too far off to tell the time of is no limit at all.
+    fn start_guess_timer(&mut self) {
+        self.guess_deadline = match self.multiplayer {
+            Some(_) => None,
+            None => self
+                .time_limit
+                .and_then(|limit| Instant::now().checked_add(limit)),
+        };
+    }
+
+    /// Check if the time for the current guess is up.
+    fn is_out_of_time(&self) -> bool {
+        self.guess_deadline.is_some_and(|t| t <= Instant::now())
+    }
+
+    /// Count the current guess as incorrect, since it took too long.
+    fn time_out(&mut self) {
+        self.timed_out = true;
+        self.queued.clear();
+        self.score.record_mismatch(Instant::now());
+        self.emit(Event::TimeUp);
+        self.fire(Trigger::TimeUp);
+    }
+
+    /// Check if the input asks for a hint rather than picking a card.
+    fn is_hint_request(&self, s: &str) -> bool {
+        self.remote_reveal.is_none() && s.trim().eq_ignore_ascii_case("hint")
+    }
+
+    /// Show the card matching the one revealed during the current guess or,
+    /// if none was revealed yet, a random pair of cards that haven't been
+    /// matched. Costs [`Game::hint_penalty`] guesses.
+    fn give_hint(&mut self) -> Result<()> {
+        if self.multiplayer.is_some() {
+            return Err(GameError::HintsDisabled);
+        }
+
+        let first = match self.revealed1 {
+            Some(c) => c,
+            None => {
+                // The partner of a card can be under another one when
+                // there are several layers
+                let hidden: Vec<_> = self
+                    .idx
+                    .iter_all()
+                    .filter(|c| !self.is_discovered(*c))
+                    .filter(|c| !self.board[*c].is_joker())
+                    .filter(|c| self.hidden_partner(*c).is_some())
+                    .collect();
+                *hidden
+                    .choose(&mut *self.rng)
+                    .expect("a pair is always on top while guessing")
+            }
+        };
+        let partner = self
+            .hidden_partner(first)
+            .ok_or(GameError::PartnerCovered)?;
+
+        self.hinted.push(partner);
+        if self.revealed1.is_none() {
+            self.hinted.push(first);
+        }
+        self.hints += 1;
+        self.guesses += self.hint_penalty;
+        Ok(())
+    }
+
+    /// Remove the matched card at `c`, exposing the one under it or marking
+    /// the position as discovered on the bottom layer.
+    fn clear_card(&mut self, c: Vec2) {
+        let index = self.idx.unchecked(c);
+        match self.under.get(self.depth[index]) {
+            Some(layer) => {
+                self.board[c] = layer[c];
+                self.depth[index] += 1;
+            }
+            None => {
+                self.set_discovered(c);
+                self.ages[index] = Some(0);
+            }
+        }
+    }
+
+    /// Mark a position as having been correctly matched.
+    fn set_discovered(&mut self, c: Vec2) {
+        let index = self.idx.unchecked(c);
+        self.discovered.set(index, true);
+    }
+
+    /// Check if a given position has been correctly matched.
+    fn is_discovered(&self, c: Vec2) -> bool {
+        let index = self.idx.unchecked(c);
+        *self.discovered.get(index).unwrap()
+    }
+
+    /// Number of pairs matched so far, including the ones on the layers
+    /// that were cleared.
+    fn pairs_found(&self) -> usize {
+        let cleared: usize = self.depth.iter().sum();
+        let exploded = self.bombs_dealt() - self.bombs_left();
+        (self.discovered.count_ones() - self.board.holes() - exploded + cleared)
+            / 2
+    }
+
+    /// Number of pairs on the board, in all layers.
+    fn pairs(&self) -> usize {
+        let cards = self.discovered.len() - self.board.holes();
+        (cards - self.bombs_dealt()) * self.layers / 2
+    }
+
+    /// Check if all cards have been correctly matched. With jokers, the
+    /// partners of the cards they were matched with are left over, so the
+    /// board is cleared once none of the remaining cards form a pair.
+    fn all_discovered(&self) -> bool {
+        // Bombs that haven't gone off are left on the board
+        let mut hidden = self.idx.iter_all().filter(|c| {
+            self.board.has_card(*c)
+                && !self.is_discovered(*c)
+                && !self.board[*c].is_bomb()
+        });
+        if self.has_jokers() {
+            hidden.all(|c| self.hidden_partner(c).is_none())
+        } else {
+            hidden.next().is_none()
+        }
+    }
+
+    /// Number of bombs on the board, including the ones that went off.
+    fn bombs_dealt(&self) -> usize {
+        self.board
+            .cards
+            .iter()
+            .flatten()
+            .filter(|c| c.is_bomb())
+            .count()
+    }
+
+    /// Number of bombs that haven't gone off yet.
+    fn bombs_left(&self) -> usize {
+        self.idx
+            .iter_all()
+            .filter(|c| self.board.has_card(*c) && !self.is_discovered(*c))
+            .filter(|c| self.board[*c].is_bomb())
+            .count()
+    }
+
+    /// Check if jokers were dealt on the board.
+    fn has_jokers(&self) -> bool {
+        self.board.cards.iter().flatten().any(|c| c.is_joker())
+    }
+
+    /// Find a card that hasn't been matched yet and forms a pair with the
+    /// one at `c`. Cards of its kind are preferred, so that jokers are saved
+    /// for the cards that lost their partner.
+    fn hidden_partner(&self, c: Vec2) -> Option<Vec2> {
+        let partners = || {
+            self.idx.iter_all().filter(|other| {
+                !self.is_discovered(*other) && self.board.is_match(c, *other)
+            })
+        };
+        partners()
+            .find(|other| !self.board[*other].is_wild())
+            .or_else(|| partners().next())
+    }
+
+    /// Check if it's possible to reveal a card during the current
+    /// guess phase.
+    fn can_reveal(&self) -> bool {
+        self.revealed1.is_none() || self.revealed2.is_none()
+    }
+
+    /// Check if the two cards revealed during the guess phase match.
+    /// # Panics
+    /// Panics if one or both of [`Game::revealed1`] and [`Game::revealed2`] was
+    /// not set.
+    fn revealed_match(&self) -> bool {
+        self.board
+            .is_match(self.revealed1.unwrap(), self.revealed2.unwrap())
+    }
+
+    /// Mark a card as revealed during the guess phase.
+    fn set_revealed(&mut self, c: Vec2) {
+        if self.revealed1.is_none() {
+            self.revealed1 = Some(c);
+        } else {
+            self.revealed2 = Some(c);
+        }
+    }
+
+    /// Clear both revealed cards.
+    fn clear_revealed(&mut self) {
+        self.revealed1 = None;
+        self.revealed2 = None;
+    }
+
+    /// Check if a card at a given position has been revealed during the
+    /// guessing phase.
+    fn is_revealed(&self, c: Vec2) -> bool {
+        matches!(self.revealed1, Some(x) if c == x)
+            || matches!(self.revealed2, Some(x) if c == x)
+    }
+
+    /// Increment the number of guesses.
+    fn inc_guesses(&mut self) {
+        self.guesses += 1;
+    }
+
+    /// Render the cards and reveal indicators. Square cells are drawn in a
+    /// grid of lines, with the card picked last outlined; hexagons, which
+    /// don't share their edges with a row of cells, are drawn without one.
+    /// In the [compact layout](`Game::set_compact`), both are drawn as
+    /// rows of cells with nothing between them. The history of the last
+    /// guesses, and the chat of a networked game, go beside the board if
+    /// there is room, or below it.
+    fn render_board(&self, out: &mut Frame) {
+        if self.accessible {
+            self.describe_board(out);
+            for line in self.panel_lines(true) {
+                out.line(line);
+            }
+            return;
+        }
+        if self.show_heatmap {
+            self.render_heatmap(out);
+            return;
+        }
+        let (origin, size) = self.viewport();
+        let compact = self.is_compact();
+        let mut board = Frame::new();
+        match self.layout {
+            Layout::Square if !compact => {
+                self.render_grid(&mut board, origin, size)
+            }
+            _ => self.render_cells(&mut board, origin, size, compact),
+        }
+        if self.history_beside(size.x, compact) {
+            let panel = self.panel_lines(true);
+            let gap = Game::HISTORY_GAP;
+            out.text(screen::beside(&board.contents(), &panel, gap));
+        } else {
+            out.text(board.contents());
+            for line in self.panel_lines(false) {
+                out.line(line);
+            }
+        }
+
+        if size.x < self.idx.size_x || size.y < self.idx.size_y {
+            let range = |start: i32, len: i32, total: i32| {
+                format!("{}-{}/{}", start + 1, start + len, total)
+            };
+            let message = t!(
+                "viewport",
+                columns = range(origin.x, size.x, self.idx.size_x),
+                rows = range(origin.y, size.y, self.idx.size_y),
+            );
+            out.line(message);
+        }
+    }
+
+    /// Render how often each position of boards of this size was turned
+    /// over in the games recorded, this one included.
+    fn render_heatmap(&self, out: &mut Frame) {
+        let size = Vec2 {
+            x: self.idx.size_x,
+            y: self.idx.size_y,
+        };
+        let counts = self.stats.as_ref().and_then(|s| s.stats.heatmap(size));
+        let Some(counts) = counts else {
+            return;
+        };
+        let title = format!("{}x{}", size.x, size.y);
+        out.line(t!("heatmap-title", size = title));
+        for line in heatmap::render(size, counts, self.style, self.unicode) {
+            out.line(line);
+        }
+        out.line("");
+    }
+
+    /// The column letters and numbers above the board, each over a cell
+    /// that starts after `indent` columns and has `gap` columns before it.
+    fn column_labels(&self, origin: Vec2, size: Vec2, gap: usize) -> String {
+        let indent = " ".repeat(self.row_label_width() + 1);
+        let cell = self.cell_width();
+        let mut letters = indent.clone();
+        let mut numbers = indent;
+        for x in origin.x..origin.x + size.x {
+            let letter = input::column_letters(x);
+            letters.push_str(&format!("{:gap$}{:<cell$}", "", letter));
+            numbers.push_str(&format!("{:gap$}{:<cell$}", "", x + 1));
+        }
+        format!("{}\n{}\n", letters, numbers)
+    }
+
+    /// Render the visible part of the board as rows of cells separated by
+    /// blank lines, or right below each other if `compact`.
+    fn render_cells(
+        &self,
+        out: &mut Frame,
+        origin: Vec2,
+        size: Vec2,
+        compact: bool,
+    ) {
+        let label_width = self.row_label_width();
+        let row_end = if compact { "\n" } else { "\n\n" };
+        let mut board_img = self.column_labels(origin, size, 0);
+        if !compact {
+            board_img.push('\n');
+        }
+        for y in origin.y..origin.y + size.y {
+            board_img.push_str(&format!("{:>1$} ", y + 1, label_width));
+            // Odd rows of hexagons sit between the cells of the rows above
+            // and below, half a card to the right
+            if self.layout == Layout::Hex && y % 2 == 1 {
+                board_img.push_str(&" ".repeat(self.hex_indent()));
+            }
+            for x in origin.x..origin.x + size.x {
+                board_img.push_str(&self.cell_text(Vec2 { x, y }));
+            }
+            board_img.push_str(row_end);
+        }
+        out.line(board_img);
+    }
+
+    /// Render the visible part of the board in a grid of box drawing lines.
+    /// The edges of the card picked last are drawn heavy.
+    fn render_grid(&self, out: &mut Frame, origin: Vec2, size: Vec2) {
+        let label_width = self.row_label_width();
+        let cell = self.cell_width();
+        let picked = self.revealed2.or(self.revealed1);
+        // The stroke of the edge between two cells, either of which may be
+        // off the board
+        let edge = |a: Vec2, b: Vec2| {
+            if picked == Some(a) || picked == Some(b) {
+                Stroke::Heavy
+            } else {
+                Stroke::Light
+            }
+        };
+        // The edge between `a` and `b`, if it is drawn at all
+        let edge_if = |drawn: bool, a: Vec2, b: Vec2| {
+            if drawn {
+                edge(a, b)
+            } else {
+                Stroke::None
+            }
+        };
+        let at = |x: i32, y: i32| Vec2 { x, y };
+        let end = Vec2 {
+            x: origin.x + size.x,
+            y: origin.y + size.y,
+        };
+
+        let mut board_img = self.column_labels(origin, size, 1);
+        for y in origin.y..=end.y {
+            // The line above row `y`, through the corners of its cells
+            board_img.push_str(&" ".repeat(label_width + 1));
+            let mut line = String::new();
+            for x in origin.x..=end.x {
+                let up = edge_if(y > origin.y, at(x - 1, y - 1), at(x, y - 1));
+                let down = edge_if(y < end.y, at(x - 1, y), at(x, y));
+                let left =
+                    edge_if(x > origin.x, at(x - 1, y - 1), at(x - 1, y));
+                let right = edge_if(x < end.x, at(x, y - 1), at(x, y));
+                line.push(screen::box_char(
+                    up,
+                    right,
+                    down,
+                    left,
+                    self.unicode,
+                ));
+                if x < end.x {
+                    let edge = screen::box_char(
+                        Stroke::None,
+                        right,
+                        Stroke::None,
+                        right,
+                        self.unicode,
+                    );
+                    line.extend(std::iter::repeat_n(edge, cell));
+                }
+            }
+            board_img.push_str(&self.paint(&line, self.theme.board));
+            board_img.push('\n');
+            if y == end.y {
+                break;
+            }
+
+            board_img.push_str(&format!("{:>1$} ", y + 1, label_width));
+            for x in origin.x..=end.x {
+                let stroke = edge(at(x - 1, y), at(x, y));
+                let line = screen::box_char(
+                    stroke,
+                    Stroke::None,
+                    stroke,
+                    Stroke::None,
+                    self.unicode,
+                );
+                let line = self.paint(&line.to_string(), self.theme.board);
+                board_img.push_str(&line);
+                if x < end.x {
+                    board_img.push_str(&self.cell_text(at(x, y)));
+                }
+            }
+            board_img.push('\n');
+        }
+        out.line(board_img);
+    }
+
+    /// The text of the cell at `c`, as wide as a cell. Cards that are out
+    /// of play are dimmed unless [high contrast](`Game::set_high_contrast`)
+    /// is on, the picked ones drawn in inverse video and mismatched ones
+    /// flash red, if the terminal shows colors.
+    fn cell_text(&self, c: Vec2) -> String {
+        let cell = self.cell_width();
+        if !self.board.has_card(c) {
+            " ".repeat(cell)
+        } else if self.is_flashing(c) {
+            let face = screen::pad(&self.face(self.board[c]), cell);
+            self.style.inverse(&self.paint(&face, self.theme.error))
+        } else if let Some(frame) = self.flip_image(c) {
+            screen::pad(&frame.to_string(), cell)
+        } else if self.is_discovered(c) && !self.high_contrast {
+            self.style.dim(&self.card_cell(c, ' '))
+        } else if self.is_discovered(c) || self.is_face_up_phase() {
+            self.card_cell(c, ' ')
+        } else if self.is_revealed(c) {
+            // The highlight takes the place of the color of the card
+            let text = match self.theme.highlight {
+                Some(highlight) if self.graphics.is_none() => {
+                    let face = self.face(self.board[c]);
+                    let face = screen::pad(&face, self.face_width());
+                    self.paint(&format!("{}<", face), Some(highlight))
+                }
+                _ => self.card_cell(c, '<'),
+            };
+            self.style.inverse(&text)
+        } else if self.is_peeked(c) {
+            self.card_cell(c, ' ')
+        } else if self.hinted.contains(&c) {
+            self.card_cell(c, '?')
+        } else if let Some(odds) = self.odds.get(&c) {
+            let percent = screen::pad(&percent(*odds), cell);
+            if *odds >= 1.0 {
+                self.paint(&percent, self.theme.highlight)
+            } else {
+                percent
+            }
+        } else if self.tutorial_step().is_some_and(|(_, at)| at == Some(c)) {
+            let hidden = screen::pad(self.glyph("█", "#"), self.face_width());
+            let text =
+                self.paint(&format!("{}?", hidden), self.theme.highlight);
+            self.style.inverse(&text)
+        } else if self.layers > 1 {
+            // The layer of the card, counted from the top
+            let z = self.depth[self.idx.unchecked(c)] + 1;
+            let hidden = format!("{}{}", self.glyph("█", "#"), z);
+            screen::pad(&hidden, cell)
+        } else if self.cell_numbers
+            && self.idx.number(c).to_string().len() <= cell
+        {
+            screen::pad(&self.idx.number(c).to_string(), cell)
+        } else {
+            screen::pad(self.glyph("█", "#"), cell)
+        }
+    }
+
+    /// Lines of the frame taken up by everything but the board, including
+    /// the column labels.
+    const RESERVED_LINES: i32 = 13;
+
+    /// Width of the row numbers left of the board.
+    fn row_label_width(&self) -> usize {
+        self.idx.size_y.to_string().len()
+    }
+
+    /// Check if the board is drawn in the compact layout, either because
+    /// it was [asked for](`Game::set_compact`) or because the board
+    /// doesn't fit in the terminal otherwise.
+    fn is_compact(&self) -> bool {
+        let full = Vec2 {
+            x: self.idx.size_x,
+            y: self.idx.size_y,
+        };
+        self.compact || self.fitting_size(false) != full
+    }
+
+    /// The number of columns and rows of cards that fit in the terminal,
+    /// in the compact layout or not.
+    fn fitting_size(&self, compact: bool) -> Vec2 {
+        let full = Vec2 {
+            x: self.idx.size_x,
+            y: self.idx.size_y,
+        };
+        let Some((columns, rows)) = self.screen.size() else {
+            return full;
+        };
+        // Every card takes a cell and two lines, or one in the compact
+        // layout, except for the spacing after the last column. The row
+        // numbers come first.
+        let indent = match self.layout {
+            Layout::Square => 0,
+            Layout::Hex => self.hex_indent() as i32,
+        };
+        let columns =
+            columns as i32 - self.row_label_width() as i32 - 1 - indent;
+        let cell = self.cell_width() as i32;
+        let x = match self.layout {
+            // A line before every cell and after the last one
+            Layout::Square if !compact => (columns - 1) / (cell + 1),
+            _ => (columns + cell - 1) / cell,
+        };
+        let x = x.max(1).min(full.x);
+        let lines = if compact { 1 } else { 2 };
+        let mut rows = rows as i32 - Game::RESERVED_LINES;
+        if !self.history_beside(x, compact) {
+            rows -= self.panel_lines(false).len() as i32;
+        }
+        Vec2 {
+            x,
+            y: (rows / lines).max(1).min(full.y),
+        }
+    }
+
+    /// Number of terminal columns taken up by `columns` columns of cards,
+    /// including the row numbers.
+    fn board_width(&self, columns: i32, compact: bool) -> usize {
+        let cell = self.cell_width();
+        let columns = columns as usize;
+        let cells = match self.layout {
+            Layout::Square if !compact => columns * (cell + 1) + 1,
+            Layout::Square => columns * cell,
+            Layout::Hex => self.hex_indent() + columns * cell,
+        };
+        self.row_label_width() + 1 + cells
+    }
+
+    /// Columns between the board and the history beside it.
+    const HISTORY_GAP: usize = 3;
+
+    /// Columns the history beside the board takes up at least, so that it
+    /// doesn't move below the board as soon as a wider guess is made.
+    const HISTORY_WIDTH: usize = 24;
+
+    /// Check if the history and the chat fit beside `columns` columns of
+    /// cards in the terminal. Images could be cut by the lines of the
+    /// panel, so it goes below a board of them.
+    fn history_beside(&self, columns: i32, compact: bool) -> bool {
+        let Some((width, _)) = self.screen.size() else {
+            return false;
+        };
+        let history = self
+            .panel_lines(true)
+            .iter()
+            .map(|line| line.width())
+            .fold(Game::HISTORY_WIDTH, usize::max);
+        self.graphics.is_none()
+            && self.board_width(columns, compact) + Game::HISTORY_GAP + history
+                <= width as usize
+    }
+
+    /// The lines of the panel beside the board, or below it if it doesn't
+    /// fit: the history of the last guesses, followed by the last lines of
+    /// chat in a networked game.
+    fn panel_lines(&self, beside: bool) -> Vec<String> {
+        let (history, chat) = if beside {
+            (Game::HISTORY_LEN, Game::CHAT_LEN)
+        } else {
+            (Game::HISTORY_BELOW, Game::CHAT_BELOW)
+        };
+        let mut lines = self.history_lines(history);
+        let chat = self.chat_lines(chat);
+        if !lines.is_empty() && !chat.is_empty() {
+            lines.push(String::new());
+        }
+        lines.extend(chat);
+        lines
+    }
+
+    /// The lines of the chat panel: a heading and up to `count` of the last
+    /// lines of chat, the latest last. Empty until something is said.
+    fn chat_lines(&self, count: usize) -> Vec<String> {
+        let Some(mp) = &self.multiplayer else {
+            return Vec::new();
+        };
+        let said: Vec<_> = mp.chat.lines().rev().take(count).collect();
+        if said.is_empty() {
+            return Vec::new();
+        }
+        let mut lines = vec![t!("chat")];
+        for line in said.into_iter().rev() {
+            let name = if mp.spectating {
+                t!("chat-player", player = line.seat + 1)
+            } else if line.seat == mp.local {
+                t!("chat-you")
+            } else {
+                t!("chat-opponent")
+            };
+            lines.push(t!("chat-line", name = name, text = line.text.as_str()));
+        }
+        lines
+    }
+
+    /// The lines of the history panel: a heading and up to `count` of the
+    /// last guesses, the latest first. Empty before the first guess.
+    fn history_lines(&self, count: usize) -> Vec<String> {
+        if self.history.is_empty() {
+            return Vec::new();
+        }
+        let mut lines = vec![t!("history")];
+        for guess in self.history.iter().rev().take(count) {
+            let cards: Vec<_> = guess
+                .picks
+                .iter()
+                .map(|pick| {
+                    let face = self.face(pick.card);
+                    format!("{} {}", input::cell_name(pick.at), face)
+                })
+                .collect();
+            let outcome = match guess.outcome {
+                Outcome::Match => t!("history-match"),
+                Outcome::Mismatch => t!("history-mismatch"),
+                Outcome::Bomb => t!("history-bomb"),
+                Outcome::TimeUp => t!("history-time-up"),
+            };
+            lines.push(t!(
+                "history-guess",
+                number = guess.number,
+                cards = cards.join(", "),
+                outcome = outcome,
+            ));
+        }
+        lines
+    }
+
+    /// The part of the board that fits in the terminal, as its top left
+    /// corner and size.
+    fn viewport(&self) -> (Vec2, Vec2) {
+        let full = Vec2 {
+            x: self.idx.size_x,
+            y: self.idx.size_y,
+        };
+        let size = self.fitting_size(self.is_compact());
+        let origin = Vec2 {
+            x: self.view.x.min(full.x - size.x).max(0),
+            y: self.view.y.min(full.y - size.y).max(0),
+        };
+        (origin, size)
+    }
+
+    /// Scroll the viewport by half its size in `direction`.
+    fn scroll(&mut self, direction: Vec2) {
+        let (origin, size) = self.viewport();
+        self.view = Vec2 {
+            x: origin.x + direction.x * (size.x / 2).max(1),
+            y: origin.y + direction.y * (size.y / 2).max(1),
+        };
+        self.view = self.viewport().0;
+    }
+
+    /// Scroll the viewport just enough to show the card at `c`.
+    fn follow(&mut self, c: Vec2) {
+        let (origin, size) = self.viewport();
+        let follow = |pos: i32, start: i32, len: i32| {
+            if pos < start {
+                pos
+            } else if pos >= start + len {
+                pos - len + 1
+            } else {
+                start
+            }
+        };
+        self.view = Vec2 {
+            x: follow(c.x, origin.x, size.x),
+            y: follow(c.y, origin.y, size.y),
+        };
+    }
+
+    /// Describe the board one row per line, e.g.
+    /// `row 1: hidden, matched ★, revealed ☺`.
+    fn describe_board(&self, out: &mut Frame) {
+        for y in 0..self.idx.size_y {
+            let cards: Vec<_> = (0..self.idx.size_x)
+                .map(|x| self.describe_card(Vec2 { x, y }))
+                .collect();
+            let cards = cards.join(", ");
+            out.line(t!("board-row", row = y + 1, cards = cards));
+        }
+        out.line("");
+    }
+
+    /// Describe the card at `c` as it is currently seen by the player.
+    fn describe_card(&self, c: Vec2) -> String {
+        if !self.board.has_card(c) {
+            return t!("card-hole");
+        }
+        let card = self.card_name(c);
+        if self.is_face_up_phase() {
+            t!("card-face-up", card = card)
+        } else if self.is_discovered(c) {
+            t!("card-matched", card = card)
+        } else if self.is_revealed(c) {
+            t!("card-revealed", card = card)
+        } else if self.is_peeked(c) {
+            t!("card-peeked", card = card)
+        } else if self.hinted.contains(&c) {
+            t!("card-hinted", card = card)
+        } else if let Some(odds) = self.odds.get(&c) {
+            t!("card-odds", percent = percent(*odds))
+        } else if self.layers > 1 {
+            let z = self.depth[self.idx.unchecked(c)] + 1;
+            t!("card-hidden-layer", layer = z)
+        } else {
+            t!("card-hidden")
+        }
+    }
+
+    /// The card at `c` in words, naming jokers and bombs since their
+    /// symbols don't tell a screen reader user much.
+    fn card_name(&self, c: Vec2) -> String {
+        match self.board[c] {
+            card if card.is_joker() => t!("card-joker"),
+            card if card.is_bomb() => t!("card-bomb"),
+            card => card.to_string(),
+        }
+    }
+
+    /// Spell out the result of the last reveal, since screen reader users
+    /// can't glance at the board to see what changed.
+    fn render_action(&self, out: &mut Frame) {
+        if !self.accessible {
+            return;
+        }
+        let describe = |c: Vec2| {
+            let position = format!("({},{})", c.x + 1, c.y + 1);
+            t!("card-at", card = self.card_name(c), position = position)
+        };
+        match (self.revealed1, self.revealed2) {
+            (Some(first), None) => {
+                out.line(t!("action-revealed", card = describe(first)));
+            }
+            (Some(first), Some(second)) => {
+                let (first, second) = (describe(first), describe(second));
+                let message = if self.state == CorrectGuessConfirm {
+                    t!("action-match", first = first, second = second)
+                } else {
+                    t!("action-mismatch", first = first, second = second)
+                };
+                out.line(message);
+            }
+            _ => {}
+        }
+    }
+
+    /// The text drawn for `card`, with ASCII characters only if the
+    /// terminal can't draw the others.
+    fn face(&self, card: Card) -> String {
+        if self.unicode {
+            card.to_string()
+        } else {
+            card.ascii_face()
+        }
+    }
+
+    /// Number of terminal columns taken up by the widest card on the
+    /// board, or any layer under it. At least two, so that the board keeps
+    /// its shape as the cards are cleared.
+    fn face_width(&self) -> usize {
+        std::iter::once(&self.board)
+            .chain(&self.under)
+            .flat_map(|layer| layer.cards.iter().flatten())
+            .map(|card| self.face(*card).width())
+            .fold(2, usize::max)
+    }
+
+    /// Number of terminal columns taken up by each cell of the board: the
+    /// widest card, followed by a column for markers.
+    fn cell_width(&self) -> usize {
+        self.face_width() + 1
+    }
+
+    /// Columns odd rows are shifted right by on a [`Layout::Hex`] board,
+    /// about half the width of a cell.
+    fn hex_indent(&self) -> usize {
+        self.cell_width().div_ceil(2)
+    }
+
+    /// The face of the card at `c` followed by `marker`, as wide as a cell,
+    /// in the [color of the card](`color::card_color`) if colors are on
+    /// and the theme has them.
+    /// With [graphics](`Game::set_graphics`), the face is an image two
+    /// columns wide.
+    fn card_cell(&self, c: Vec2, marker: char) -> String {
+        let card = self.board[c];
+        if let Some(graphics) = self.graphics {
+            let spacing = " ".repeat(self.face_width() - 2);
+            return format!("{}{}{}", graphics.draw(card), spacing, marker);
+        }
+        let face = screen::pad(&self.face(card), self.face_width());
+        let color = color::card_color(card).filter(|_| self.theme.card_colors);
+        format!("{}{}", self.paint(&face, color), marker)
+    }
+
+    /// The image of the card at `c` in the current frame, if it is being
+    /// turned over.
+    fn flip_image(&self, c: Vec2) -> Option<char> {
+        if !self.is_animating() {
+            return None;
+        }
+        let flip = self.flips.iter().find(|f| f.at == c)?;
+        // Every card waits for the flashes to end before it turns over
+        let frame = self.flip_frame.checked_sub(self.flash_frames())?;
+        let frame = if flip.reveal {
+            frame
+        } else {
+            Game::FLIP_FRAMES.len() - 1 - frame
+        };
+        if self.unicode {
+            Some(Game::FLIP_FRAMES[frame])
+        } else {
+            Some(Game::ASCII_FLIP_FRAMES[frame])
+        }
+    }
+
+    /// Render the code for sharing the current board, unless it is shared
+    /// with an opponent already. Once it is cleared, the result code is
+    /// shown too.
+    fn render_board_code(&self, out: &mut Frame) {
+        if let (Some(code), None) = (&self.dealt, &self.multiplayer) {
+            out.line(t!("board-code", code = code.encode()));
+            if let Some(result) = self.challenge_result() {
+                out.line(t!("result-code", code = result.encode()));
+            }
+        }
+    }
+
+    /// Render the player's result next to the one of the friend they
+    /// challenged.
+    fn render_comparison(&self, out: &mut Frame) {
+        let (Some(rival), Some(mine)) = (&self.rival, self.challenge_result())
+        else {
+            return;
+        };
+        let rows = [
+            (
+                t!("compare-guesses"),
+                mine.guesses.to_string(),
+                rival.guesses.to_string(),
+            ),
+            (
+                t!("compare-time"),
+                format_duration(mine.time()),
+                format_duration(rival.time()),
+            ),
+            (
+                t!("compare-hints"),
+                mine.hints.to_string(),
+                rival.hints.to_string(),
+            ),
+            (
+                t!("compare-score"),
+                mine.score.to_string(),
+                rival.score.to_string(),
+            ),
+        ];
+        let width = rows
+            .iter()
+            .map(|(label, ..)| label.chars().count())
+            .max()
+            .unwrap_or(0);
+        out.line("");
+        out.line(format_args!(
+            "{:width$}  {:>8}  {:>8}",
+            "",
+            t!("compare-you"),
+            t!("compare-friend")
+        ));
+        for (label, mine, theirs) in rows {
+            out.line(format_args!(
+                "{:width$}  {:>8}  {:>8}",
+                label, mine, theirs
+            ));
+        }
+        // Fewer guesses win, and the time breaks ties
+        let verdict =
+            (mine.guesses, mine.time_ms).cmp(&(rival.guesses, rival.time_ms));
+        out.line(match verdict {
+            Ordering::Less => t!("compare-won"),
+            Ordering::Greater => t!("compare-lost"),
+            Ordering::Equal => t!("compare-tied"),
+        });
+        out.line("");
+    }
+
+    /// Render the positions shown by the last hint, if there are any.
+    fn render_hint(&self, out: &mut Frame) {
+        if self.hinted.is_empty() {
+            return;
+        }
+        let positions: Vec<_> = self
+            .hinted
+            .iter()
+            .map(|c| format!("({},{})", c.x + 1, c.y + 1))
+            .collect();
+        match positions.as_slice() {
+            [first] => out.line(t!("hint-one", first = first)),
+            [first, second, ..] => {
+                out.line(t!("hint-two", first = first, second = second))
+            }
+            [] => {}
+        }
+    }
+
+    /// Render what the numbers on the face-down cards stand for, if the
+    /// chances of them matching the card picked are shown.
+    fn render_odds(&self, out: &mut Frame) {
+        if let Some(first) = self.revealed1.filter(|_| !self.odds.is_empty()) {
+            let first = format!("({},{})", first.x + 1, first.y + 1);
+            out.line(t!("odds", first = first));
+        }
+    }
+
+    /// Render the time taken to clear the board, and how it compares to
+    /// previous games.
+    fn render_stats(&self, out: &mut Frame) {
+        if let Some(time) = self.finish_time {
+            out.line(t!("cleared-in", time = format_duration(time)));
+        }
+        let stats = match &self.stats {
+            Some(store) => &store.stats,
+            None => return,
+        };
+
+        let size = Vec2 {
+            x: self.idx.size_x,
+            y: self.idx.size_y,
+        };
+        if let Some(best) = stats.best_time(size) {
+            let size = format!("{}x{}", size.x, size.y);
+            let time = format_duration(best);
+            let line = if self.new_best {
+                t!("best-time-new-record", size = size, time = time)
+            } else {
+                t!("best-time", size = size, time = time)
+            };
+            out.line(line);
+        }
+        out.text(t!("games-played", count = stats.games_played));
+        if let Some(avg) = stats.guesses_per_pair() {
+            let average = format!("{:.2}", avg);
+            out.text(format_args!(
+                " | {}",
+                t!("guesses-per-pair", average = average)
+            ));
+        }
+        out.line("");
+        out.line("");
+    }
+
+    /// Render the score of the cleared board.
+    fn render_final_score(&self, out: &mut Frame) {
+        let message = t!(
+            "final-score",
+            points = self.score.points,
+            streak = self.score.best_streak,
+        );
+        out.line(message);
+    }
+
+    /// Render how the board was cleared: the time, the guesses and how many
+    /// of them found a pair, the longest streak, and the guesses a player
+    /// with perfect memory would take on average.
+    fn render_summary(&self, out: &mut Frame) {
+        let time = self.finish_time.unwrap_or_default();
+        let pairs = self.pairs_found();
+        // Pairs found with a power-up took no guess at all
+        let accuracy = (pairs * 100 / self.turns.max(1) as usize).min(100);
+        let expected = solver::expected_guesses(self.pairs());
+        let rows = [
+            (t!("summary-time"), format_duration(time)),
+            (t!("summary-guesses"), self.guesses.to_string()),
+            (t!("summary-accuracy"), format!("{}%", accuracy)),
+            (t!("summary-streak"), self.score.best_streak.to_string()),
+            (t!("summary-expected"), format!("{:.1}", expected)),
+            (t!("summary-par"), rating::par(self.pairs()).to_string()),
+        ];
+        let width = rows
+            .iter()
+            .map(|(label, _)| label.width())
+            .max()
+            .unwrap_or(0);
+        out.line("");
+        out.line(t!("summary-title"));
+        for (label, value) in rows {
+            out.line(format_args!(
+                "{}  {:>8}",
+                screen::pad(&label, width),
+                value
+            ));
+        }
+        let extra = self.guesses as f64 - expected;
+        if extra <= 0.0 {
+            out.line(t!("summary-beat-expected"));
+        } else {
+            let extra = format!("{:.1}", extra);
+            out.line(t!("summary-above-expected", extra = extra));
+        }
+        let stars = rating::stars(self.guesses, self.pairs());
+        let stars = rating::display(stars, self.unicode);
+        out.line(self.paint(&t!("rating", stars = stars), self.theme.score));
+        let size = Vec2 {
+            x: self.idx.size_x,
+            y: self.idx.size_y,
+        };
+        let best = self.stats.as_ref().and_then(|s| s.stats.best_stars(size));
+        if let Some(best) = best {
+            let best = rating::display(best, self.unicode);
+            out.line(t!("rating-best", stars = best));
+        }
+    }
+
+    /// Render the most stars earned on each board size played, under the
+    /// prompt for the size of the next board.
+    fn render_ratings(&self, out: &mut Frame) {
+        let Some(store) = &self.stats else {
+            return;
+        };
+        if store.stats.best_stars.is_empty() {
+            return;
+        }
+        out.line(t!("ratings-title"));
+        for (size, stars) in &store.stats.best_stars {
+            let stars = rating::display(*stars, self.unicode);
+            out.line(format_args!("  {:>7}  {}", size, stars));
+        }
+        out.line("");
+    }
+
+    /// Number of guesses the analysis after a game goes into.
+    const ANALYSIS_LINES: usize = 5;
+
+    /// Render how many guesses didn't make use of the cards seen before
+    /// them, and what they missed for the first few.
+    fn render_analysis(&self, out: &mut Frame) {
+        if self.history.is_empty() {
+            return;
+        }
+        let flagged: Vec<_> = self
+            .history
+            .iter()
+            .map(|guess| (guess.number, guess.verdict()))
+            .filter(|(_, verdict)| *verdict != Verdict::Good)
+            .collect();
+        out.line("");
+        if flagged.is_empty() {
+            out.line(t!("analysis-clean"));
+            return;
+        }
+        let count = flagged.len();
+        let total = self.history.len();
+        out.line(t!("analysis-flagged", count = count, total = total));
+        for (number, verdict) in flagged.iter().take(Game::ANALYSIS_LINES) {
+            let number = *number;
+            let line = match *verdict {
+                Verdict::Wasted { first, second } => t!(
+                    "analysis-wasted",
+                    number = number,
+                    first = input::cell_name(first),
+                    second = input::cell_name(second),
+                ),
+                Verdict::MissedPartner { first, partner } => t!(
+                    "analysis-missed-partner",
+                    number = number,
+                    first = input::cell_name(first),
+                    partner = input::cell_name(partner),
+                ),
+                Verdict::Repeated { at } => t!(
+                    "analysis-repeated",
+                    number = number,
+                    at = input::cell_name(at),
+                ),
+                Verdict::Good => continue,
+            };
+            out.line(format_args!("  {}", line));
+        }
+        if count > Game::ANALYSIS_LINES {
+            let more = count - Game::ANALYSIS_LINES;
+            out.line(t!("analysis-more", count = more));
+        }
+    }
+
+    /// Render the outcome of the daily challenge.
+    fn render_daily(&self, out: &mut Frame) {
+        if let Some((daily, log)) = &self.daily {
+            out.line(t!("daily-complete", date = daily.date));
+            if !self.daily_recorded {
+                if let Some(first) = log.get(&daily.date) {
+                    let time = Duration::from_millis(first.time_ms);
+                    let message = t!(
+                        "daily-first-attempt-only",
+                        guesses = first.guesses,
+                        time = format_duration(time),
+                    );
+                    out.line(message);
+                }
+            }
+        }
+    }
+
+    /// Render the preset board sizes with the most stars earned on each,
+    /// followed by the custom size.
+    fn render_size_menu(&self, out: &mut Frame) {
+        out.line(t!("size-menu"));
+        let names: Vec<_> = SizePreset::ALL.map(SizePreset::name).into();
+        let width = names.iter().map(|name| name.width()).max();
+        let width = width.unwrap_or_default();
+        for (i, preset) in SizePreset::ALL.iter().enumerate() {
+            let size = preset.size();
+            let size = format!("{}x{}", size.x, size.y);
+            let name = screen::pad(&names[i], width);
+            out.text(format_args!("  {}. {}  {:>5}", i + 1, name, size));
+            let stats = self.stats.as_ref().map(|store| &store.stats);
+            if let Some(stars) = stats.and_then(|s| s.best_stars(preset.size()))
+            {
+                let stars = rating::display(stars, self.unicode);
+                out.text(format_args!("  {}", stars));
+            }
+            out.line("");
+        }
+        let custom = SizePreset::ALL.len() + 1;
+        out.line(format_args!("  {}. {}", custom, t!("size-custom")));
+        out.line("");
+    }
+
+    /// Render the settings menu with the current value of each setting.
+    /// Changes to the card set and the difficulty apply from the next
+    /// board dealt.
+    fn render_settings(&self, out: &mut Frame) {
+        out.line(t!("settings"));
+        let names: Vec<_> = Setting::ALL.map(Setting::name).into();
+        let width = names.iter().map(|name| name.width()).max();
+        let width = width.unwrap_or_default();
+        for (i, setting) in Setting::ALL.iter().enumerate() {
+            let on_off = |on: bool| match on {
+                true => t!("setting-on"),
+                false => t!("setting-off"),
+            };
+            let value = match setting {
+                Setting::Colors => on_off(self.style.is_enabled()),
+                Setting::CardSet => card_set_name(self.card_set),
+                Setting::Difficulty => difficulty_name(self.difficulty),
+                Setting::Sound => on_off(!self.muted),
+            };
+            let name = screen::pad(&names[i], width);
+            out.line(format_args!("  {}. {}  {}", i + 1, name, value));
+        }
+        out.line(t!("settings-next-board"));
+        out.line("");
+    }
+
+    /// Render the list of campaign levels, marking the cleared and the
+    /// locked ones.
+    fn render_levels(&self, out: &mut Frame) {
+        let Some(campaign) = &self.campaign else {
+            return;
+        };
+        out.line(t!("campaign-levels"));
+        for (i, level) in LEVELS.iter().enumerate() {
+            let mark = if campaign.is_cleared(i) {
+                self.glyph("✓", "x")
+            } else {
+                " "
+            };
+            let size = format!("{}x{}", level.size.x, level.size.y);
+            out.text(format_args!("  {} {:>2}. {:>5}", mark, i + 1, size));
+            if !campaign.is_unlocked(i) {
+                out.text(format_args!("  {}", t!("level-locked")));
+            }
+            out.line("");
+        }
+        out.line("");
+    }
+
+    /// Render the board size and the rules of the picked campaign level.
+    fn render_level_intro(&self, out: &mut Frame) {
+        let level = LEVELS[self.level];
+        let size = format!("{}x{}", level.size.x, level.size.y);
+        out.line(t!("level-intro", level = self.level + 1, size = size));
+        let mut rules = Vec::new();
+        match level.difficulty {
+            Difficulty::Easy => rules.push(t!("level-rule-easy")),
+            Difficulty::Normal => {}
+            Difficulty::Hard => rules.push(t!(
+                "level-rule-hard",
+                interval = Game::RESHUFFLE_INTERVAL
+            )),
+        }
+        if level.layout == Layout::Hex {
+            rules.push(t!("level-rule-hex"));
+        }
+        if level.layers > 1 {
+            rules.push(t!("level-rule-layers", layers = level.layers));
+        }
+        if level.min_pair_distance > 1 {
+            let steps = level.min_pair_distance;
+            rules.push(t!("level-rule-spread", steps = steps));
+        }
+        for rule in rules {
+            out.line(format_args!("  - {}", rule));
+        }
+        out.line("");
+    }
+
+    /// Render the outcome of the cleared campaign level, and what comes
+    /// next.
+    fn render_level_outro(&self, out: &mut Frame) {
+        out.line(t!("level-complete", level = self.level + 1));
+        if self.level + 1 == LEVELS.len() {
+            out.line(t!("campaign-complete"));
+            out.line(t!("press-enter-to-continue"));
+            return;
+        }
+        if self.level_unlocked {
+            out.line(t!("level-unlocked", level = self.level + 2));
+        }
+        out.line(t!("level-next", level = self.level + 2));
+    }
+
+    /// Render the results of the tournament rounds played so far, and what
+    /// comes next.
+    fn render_tournament(&self, out: &mut Frame) {
+        let Some(tournament) = &self.tournament else {
+            return;
+        };
+        let played = tournament.results().len();
+        out.line(t!(
+            "tournament-round-complete",
+            round = played,
+            rounds = tournament.len()
+        ));
+        for (i, result) in tournament.results().iter().enumerate() {
+            let size = format!("{}x{}", result.size.x, result.size.y);
+            out.line(format_args!(
+                "  {:>2}. {:>5}  {}",
+                i + 1,
+                size,
+                t!(
+                    "tournament-round",
+                    guesses = result.guesses,
+                    time = format_duration(result.time),
+                    points = result.score(),
+                )
+            ));
+        }
+        let total = t!(
+            "tournament-total",
+            guesses = tournament.guesses(),
+            time = format_duration(tournament.time()),
+            points = tournament.score(),
+        );
+        out.line(format_args!("      {:>5}  {}", "", total));
+        out.line("");
+        match tournament.next_round() {
+            Some(size) => {
+                let size = format!("{}x{}", size.x, size.y);
+                out.line(t!(
+                    "tournament-next-round",
+                    round = played + 1,
+                    size = size
+                ));
+            }
+            None => {
+                out.line(t!("tournament-over", points = tournament.score()));
+                out.line(t!("press-enter-to-exit"));
+            }
+        }
+    }
+
+    /// Render the power-ups held and the effects in progress.
+    fn render_power_ups(&self, out: &mut Frame) {
+        if let Some(until) = self.freeze_until {
+            let left = until.saturating_duration_since(Instant::now());
+            if !left.is_zero() {
+                let seconds = left.as_secs() + 1;
+                out.line(t!("clock-frozen", seconds = seconds));
+            }
+        }
+        if self.power_ups.is_empty() {
+            return;
+        }
+        let held: Vec<String> = PowerUp::ALL
+            .into_iter()
+            .filter(|p| self.power_ups.count(*p) > 0)
+            .map(|p| {
+                let times = self.glyph("×", "x");
+                format!(":{} {}{}", p.name(), times, self.power_ups.count(p))
+            })
+            .collect();
+        out.line(t!("power-ups", list = held.join(", ")));
+    }
+
+    /// Render the overlay in place of the current state.
+    fn render_overlay(&self, overlay: Overlay, out: &mut Frame) {
+        match overlay {
+            Overlay::Help => self.render_help(out),
+            Overlay::Keys => self.render_keys(out),
+        }
+        out.line("");
+        out.line(t!("overlay-close"));
+    }
+
+    /// Render how to pick cards, the list of commands and the rules of the
+    /// current game.
+    fn render_help(&self, out: &mut Frame) {
+        out.line(t!("help-input"));
+        for line in [
+            t!("help-input-coords"),
+            t!("help-input-formats"),
+            t!("help-input-number"),
+            t!("help-input-batch"),
+            t!("help-input-scroll"),
+        ] {
+            out.line(format_args!("  {}", line));
+        }
+        out.line("");
+        out.line(t!("help-commands"));
+        for line in [
+            t!("help-quit"),
+            t!("help-restart"),
+            t!("help-rematch"),
+            t!("help-resize"),
+            t!("help-hint"),
+            t!("help-odds"),
+            t!("help-save"),
+            t!("help-help"),
+            t!("help-theme"),
+            t!("help-heatmap"),
+            t!("help-settings"),
+            t!("help-keys"),
+            t!("help-numbers"),
+            t!("help-luck"),
+            t!("help-say"),
+            t!("help-peek"),
+            t!("help-freeze"),
+            t!("help-match"),
+        ] {
+            out.line(format_args!("  {}", line));
+        }
+        out.line("");
+        out.line(t!("help-rules"));
+        let rules = self.rules();
+        if rules.is_empty() {
+            out.line(format_args!("  - {}", t!("rule-standard")));
+        }
+        for rule in rules {
+            out.line(format_args!("  - {}", rule));
+        }
+    }
+
+    /// Describe the rules of the current game that differ from the
+    /// standard ones.
+    fn rules(&self) -> Vec<String> {
+        let mut rules = Vec::new();
+        match self.effective_difficulty() {
+            Difficulty::Easy => rules.push(t!("level-rule-easy")),
+            Difficulty::Normal => {}
+            Difficulty::Hard => rules.push(t!(
+                "level-rule-hard",
+                interval = Game::RESHUFFLE_INTERVAL
+            )),
+        }
+        if self.layout == Layout::Hex {
+            rules.push(t!("level-rule-hex"));
+        }
+        if self.layers > 1 {
+            rules.push(t!("level-rule-layers", layers = self.layers));
+        }
+        if self.min_pair_distance > 1 {
+            let steps = self.min_pair_distance;
+            rules.push(t!("level-rule-spread", steps = steps));
+        }
+        if let Some(limit) = self.time_limit {
+            rules.push(t!("rule-time-limit", seconds = limit.as_secs()));
+        }
+        if let Some(lives) = self.lives.filter(|_| self.multiplayer.is_none()) {
+            rules.push(t!("rule-lives", lives = lives));
+        }
+        if self.multiplayer.is_none() && self.layers == 1 {
+            if self.jokers > 0 {
+                rules.push(t!("rule-jokers", count = self.jokers));
+            }
+            if self.bombs > 0 {
+                rules.push(match self.bomb_effect {
+                    BombEffect::Penalty => {
+                        t!("rule-bombs-penalty", count = self.bombs)
+                    }
+                    BombEffect::Shuffle => {
+                        t!("rule-bombs-shuffle", count = self.bombs)
+                    }
+                });
+            }
+        }
+        if let Some(turns) = self.decay {
+            rules.push(t!("rule-decay", turns = turns));
+        }
+        if let Some(turns) = self.shuffle_every {
+            rules.push(t!("rule-shuffle-every", turns = turns));
+        }
+        if self.multiplayer.is_none() {
+            rules.push(t!("rule-hint-penalty", guesses = self.hint_penalty));
+        }
+        rules
+    }
+
+    /// Render the key bound to each action.
+    fn render_keys(&self, out: &mut Frame) {
+        out.line(t!("keys-title"));
+        for action in Action::ALL {
+            let key = match self.keymap.key(action) {
+                Some(key) => key.to_string(),
+                None => "-".to_owned(),
+            };
+            out.line(format_args!("  {:<3}{}", key, action.name()));
+        }
+    }
+
+    /// Number of columns and rows of the tutorial board.
+    const TUTORIAL_SIZE: Vec2 = Vec2 { x: 2, y: 2 };
+
+    /// What the tutorial tells the player to do next, and the card it
+    /// points out, if any. The first guess is steered towards a mismatch,
+    /// so that the player sees both outcomes.
+    fn tutorial_step(&self) -> Option<(String, Option<Vec2>)> {
+        if !self.tutorial {
+            return None;
+        }
+        let hidden: Vec<_> = self
+            .idx
+            .iter_all()
+            .filter(|c| self.board.has_card(*c) && !self.is_discovered(*c))
+            .filter(|c| !self.is_revealed(*c))
+            .collect();
+        let unseen =
+            hidden.iter().copied().find(|c| !self.seen.contains_key(c));
+        let step = match self.state {
+            CorrectGuessConfirm => (t!("tutorial-match"), None),
+            IncorrectGuessConfirm => (t!("tutorial-mismatch"), None),
+            Guess => match self.revealed1 {
+                None if self.history.is_empty() => {
+                    (t!("tutorial-first"), hidden.first().copied())
+                }
+                None => {
+                    let mut seen: Vec<_> =
+                        self.seen.iter().map(|(c, k)| (*c, *k)).collect();
+                    seen.sort_by_key(|(c, _)| (c.y, c.x));
+                    match analysis::known_pair(&seen) {
+                        Some((first, _)) => {
+                            (t!("tutorial-known-pair"), Some(first))
+                        }
+                        None => (t!("tutorial-explore"), unseen),
+                    }
+                }
+                Some(first) if self.history.is_empty() => {
+                    let card = self.board[first];
+                    let other = hidden
+                        .iter()
+                        .copied()
+                        .find(|c| !card.pairs_with(&self.board[*c]));
+                    (t!("tutorial-second"), other.or(hidden.first().copied()))
+                }
+                Some(first) => {
+                    let card = self.board[first];
+                    let partner = self
+                        .seen
+                        .iter()
+                        .find(|(c, other)| {
+                            **c != first && card.pairs_with(other)
+                        })
+                        .map(|(c, _)| *c);
+                    match partner {
+                        Some(partner) => {
+                            (t!("tutorial-partner"), Some(partner))
+                        }
+                        None => (t!("tutorial-explore"), unseen),
+                    }
+                }
+            },
+            _ => return None,
+        };
+        Some(step)
+    }
+
+    /// Render what the tutorial tells the player to do next.
+    fn render_tutorial(&self, out: &mut Frame) {
+        if let Some((message, _)) = self.tutorial_step() {
+            out.line(self.paint(&message, self.theme.highlight));
+        }
+    }
+
+    /// Render the error message, if there is one.
+    fn render_error(&self, out: &mut Frame) {
+        if let Some(err) = &self.error {
+            let message = format!("(!) {}", err.as_string());
+            out.line(self.paint(&message, self.theme.error));
+        }
+    }
+
+    /// Draw `text` in `color`, which is one of the colors of the
+    /// [theme](`Game::set_theme`), if colors are on.
+    fn paint(&self, text: &str, color: Option<Rgb>) -> String {
+        self.style.paint(text, color)
+    }
+
+    /// Render the status line: the kind of game, its difficulty, the time
+    /// spent on the board, the pairs left and, against an opponent, whose
+    /// turn it is.
+    fn render_status(&self, out: &mut Frame) {
+        let mode = if self.multiplayer.is_some() {
+            t!("mode-versus")
+        } else if self.daily.is_some() {
+            t!("mode-daily")
+        } else if self.tournament.is_some() {
+            t!("mode-tournament")
+        } else if self.campaign.is_some() {
+            t!("mode-campaign", level = self.level + 1)
+        } else {
+            t!("mode-solo")
+        };
+        let difficulty = difficulty_name(self.effective_difficulty());
+        let time = self.stopped.unwrap_or_else(|| self.elapsed());
+        let total = self.pairs();
+        let mut parts = vec![
+            mode,
+            difficulty,
+            format_duration(time),
+            t!(
+                "pairs-left",
+                left = total - self.pairs_found(),
+                total = total
+            ),
+        ];
+        if let Some(mp) = &self.multiplayer {
+            parts.push(if mp.spectating {
+                t!("turn-player", player = mp.turn + 1)
+            } else if mp.is_local_turn() {
+                t!("turn-yours")
+            } else {
+                t!("turn-theirs")
+            });
+        }
+        out.status(self.style.inverse(&format!(" {} ", parts.join(" | "))));
+    }
+
+    /// Render the status line, and the total and correct number of guesses.
+    fn render_score(&self, out: &mut Frame) {
+        self.render_status(out);
+        if let Some(mp) = &self.multiplayer {
+            let mine = mp.pairs[mp.local];
+            let theirs = mp.pairs[1 - mp.local];
+            let score = if mp.spectating {
+                t!(
+                    "score-players",
+                    player = mp.turn + 1,
+                    first = mp.pairs[0],
+                    second = mp.pairs[1]
+                )
+            } else if mp.is_local_turn() {
+                t!("score-your-turn", mine = mine, theirs = theirs)
+            } else {
+                t!("score-their-turn", mine = mine, theirs = theirs)
+            };
+            out.line(score);
+            out.line("");
+            return;
+        }
+
+        if let Some(lives) = self.lives_left() {
+            let total = self.lives.unwrap_or(lives);
+            let hearts = if self.accessible {
+                lives.to_string()
+            } else {
+                self.glyph("♥", "O").repeat(lives as usize)
+                    + &self
+                        .glyph("♡", ".")
+                        .repeat(total.saturating_sub(lives) as usize)
+            };
+            out.line(
+                self.paint(&t!("lives", hearts = hearts), self.theme.score),
+            );
+        }
+
+        let correct_guesses = self.pairs_found();
+        let score = if self.hints > 0 {
+            t!(
+                "score-with-hints",
+                guesses = self.guesses,
+                correct = correct_guesses,
+                hints = self.hints,
+            )
+        } else {
+            t!("score", guesses = self.guesses, correct = correct_guesses,)
+        };
+        out.line(self.paint(&score, self.theme.score));
+        if self.luck_meter {
+            self.render_luck(out);
+        }
+        out.line("");
+    }
+
+    /// Render how the guesses so far compare to what a player with perfect
+    /// memory is expected to take: the guesses made and the ones expected
+    /// to be left given the cards seen, against the ones expected for the
+    /// whole board.
+    fn render_luck(&self, out: &mut Frame) {
+        let Some(info) = self.info_set() else {
+            return;
+        };
+        // A guess waiting to be confirmed is counted once it is
+        let pending =
+            matches!(self.state, CorrectGuessConfirm | IncorrectGuessConfirm);
+        let guesses = self.guesses + pending as i32;
+        let expected = solver::expected_guesses(self.pairs());
+        let projected = guesses as f64 + info.guesses_left();
+        let luck = t!(
+            "luck",
+            luck = format!("{:+.1}", expected - projected),
+            projected = format!("{:.1}", projected),
+            expected = format!("{:.1}", expected)
+        );
+        out.line(self.paint(&luck, self.theme.score));
+    }
+
+    /// What the players have seen of the cards left face down, if the board
+    /// is one the [probabilities](`crate::probability`) cover.
+    fn info_set(&self) -> Option<InfoSet> {
+        InfoSet::new(&self.seen_cards(&self.face_down()?))
+    }
+
+    /// The positions of the cards left face down. A pair waiting to be
+    /// taken off the board isn't left. `None` for networked games and
+    /// boards of several layers, which the
+    /// [probabilities](`crate::probability`) don't cover.
+    fn face_down(&self) -> Option<Vec<Vec2>> {
+        if self.multiplayer.is_some() || self.layers > 1 {
+            return None;
+        }
+        let cells = self
+            .idx
+            .iter_all()
+            .filter(|c| self.board.has_card(*c) && !self.is_discovered(*c))
+            .filter(|c| {
+                self.state != CorrectGuessConfirm || !self.is_revealed(*c)
+            });
+        Some(cells.collect())
+    }
+
+    /// The cards at `cells`, each with whether the players have seen it.
+    fn seen_cards(&self, cells: &[Vec2]) -> Vec<(Card, bool)> {
+        let cards = cells.iter().map(|c| {
+            let card = self.board[*c];
+            (card, self.seen.get(c) == Some(&card))
+        });
+        cards.collect()
+    }
+
+    /// The chance of each card left face down matching the one picked first
+    /// in the current guess, given the cards seen, if the board is one the
+    /// [probabilities](`crate::probability`) cover.
+    fn match_odds(&self) -> Option<HashMap<Vec2, f64>> {
+        let first = self.revealed1?;
+        let cells = self.face_down()?;
+        let revealed = cells.iter().position(|c| *c == first)?;
+        let cards = self.seen_cards(&cells);
+        let chances = probability::match_chances(&cards, revealed)?;
+        let odds = cells
+            .into_iter()
+            .zip(chances)
+            .filter_map(|(c, chance)| Some((c, chance?)));
+        Some(odds.collect())
+    }
+
+    /// Render who won a networked game.
+    fn render_outcome(&self, out: &mut Frame) {
+        if let Some(mp) = &self.multiplayer {
+            let mine = mp.pairs[mp.local];
+            let theirs = mp.pairs[1 - mp.local];
+            if mp.spectating && mine != theirs {
+                let winner = if mine > theirs {
+                    mp.local
+                } else {
+                    1 - mp.local
+                };
+                out.line(t!("outcome-player-wins", player = winner + 1));
+            } else if mine > theirs {
+                out.line(t!("outcome-win"));
+            } else if mine < theirs {
+                out.line(t!("outcome-lose"));
+            } else {
+                out.line(t!("outcome-draw"));
+            }
+            if let Some((before, after)) = self.rating_change {
+                let change = after.round() - before.round();
+                out.line(t!(
+                    "elo-change",
+                    rating = after.round() as i64,
+                    change = format!("{:+}", change as i64)
+                ));
+            }
+            match (&mp.commitment, mp.verified) {
+                (None, _) => {}
+                (Some(_), Some(true)) => out.line(t!("board-verified")),
+                (Some(_), Some(false)) => out.line(t!("board-tampered")),
+                (Some(_), None) => out.line(t!("board-unverified")),
+            }
+        }
+    }
+}
+
+impl Default for Game {
+    fn default() -> Game {
+        Game::new()
+    }
+}
+
+/// A chance as a whole percentage, without the sign.
+fn percent(chance: f64) -> String {
+    format!("{:.0}", chance * 100.0)
+}
+
+fn connection_lost(e: io::Error) -> GameError {
+    GameError::ConnectionLost {
+        reason: e.to_string(),
+        source: Some(Arc::new(e)),
+    }
+}
+
+/// The error for a message other than the one `expected` from the
+/// opponent.
+fn unexpected(expected: &str, msg: &Message) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("expected {}, got {:?}", expected, msg),
+    )
+}
+
+/// The reason a state of a watched room is turned down.
+const ROOM_STATE_MISFIT: &str = "the state of the room doesn't fit the board";
+
+fn invalid_data(reason: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason.to_string())
+}
+
+/// The name of a rule preset, as shown to the player.
+fn difficulty_name(difficulty: Difficulty) -> String {
+    match difficulty {
+        Difficulty::Easy => t!("difficulty-easy"),
+        Difficulty::Normal => t!("difficulty-normal"),
+        Difficulty::Hard => t!("difficulty-hard"),
+    }
+}
+
+/// The name of a card set, as shown to the player.
+fn card_set_name(card_set: CardSet) -> String {
+    match card_set {
+        CardSet::Symbols => t!("card-set-symbols"),
+        CardSet::Playing => t!("card-set-playing"),
+        CardSet::Ascii => t!("card-set-ascii"),
+        CardSet::Emoji => t!("card-set-emoji"),
     }
 }
@@ -0,0 +1,222 @@
+use std::env;
+
+use crate::board::Card;
+use crate::color::{self, Rgb};
+
+/// A protocol for drawing raster images in the terminal.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Graphics {
+    /// The graphics protocol of kitty, also spoken by WezTerm and Ghostty.
+    Kitty,
+    /// DEC Sixel, supported by xterm, foot, mlterm and others.
+    Sixel,
+}
+
+impl Graphics {
+    /// Guess the protocol from the environment variables terminals set, or
+    /// `None` if images can't be shown and the cards should be drawn as
+    /// text.
+    pub fn detect() -> Option<Graphics> {
+        let term = env::var("TERM").unwrap_or_default();
+        let program = env::var("TERM_PROGRAM").unwrap_or_default();
+        if env::var_os("KITTY_WINDOW_ID").is_some()
+            || term == "xterm-kitty"
+            || matches!(program.as_str(), "WezTerm" | "ghostty")
+        {
+            Some(Graphics::Kitty)
+        } else if ["foot", "mlterm", "yaft", "contour"]
+            .iter()
+            .any(|name| term.starts_with(name))
+        {
+            Some(Graphics::Sixel)
+        } else {
+            None
+        }
+    }
+
+    /// The escape codes that draw the face of `card` two columns wide and
+    /// a line high at the cursor, leaving the cursor right after it.
+    pub fn draw(self, card: Card) -> String {
+        let face = Face::of(card);
+        match self {
+            Graphics::Kitty => face.kitty(),
+            Graphics::Sixel => face.sixel(),
+        }
+    }
+
+    /// The escape codes that remove the images on the given 1-based line,
+    /// before it is drawn again. Sixel images are part of the text, and
+    /// are overwritten along with it.
+    pub fn clear_line(self, line: usize) -> String {
+        match self {
+            Graphics::Kitty => format!("{}_Ga=d,d=y,y={},q=2{}", ESC, line, ST),
+            Graphics::Sixel => String::new(),
+        }
+    }
+
+    /// The escape codes that remove every image on the screen.
+    pub fn clear_screen(self) -> String {
+        match self {
+            Graphics::Kitty => format!("{}_Ga=d,d=a,q=2{}", ESC, ST),
+            Graphics::Sixel => String::new(),
+        }
+    }
+}
+
+const ESC: char = 27 as char;
+
+/// The string terminator that ends the escape codes of both protocols.
+const ST: &str = "\x1b\\";
+
+/// The picture on a card: a pattern of blocks, mirrored left to right so
+/// that it is easy to recognize, in the color of the card.
+struct Face {
+    blocks: [[bool; Face::SIZE]; Face::SIZE],
+    color: Rgb,
+}
+
+impl Face {
+    /// Number of blocks along each side of the pattern.
+    const SIZE: usize = 5;
+
+    /// The color of cards that have none of their own, light enough to
+    /// stand out on a dark background.
+    const DEFAULT_COLOR: Rgb = Rgb(220, 220, 220);
+
+    /// Draw the face of `card`. Cards that pair up get the same pattern, so
+    /// playing cards are drawn by rank, in the color of their suit.
+    fn of(card: Card) -> Face {
+        let color = color::card_color(card).unwrap_or(Face::DEFAULT_COLOR);
+        let pattern = match (card, card.rank()) {
+            // A star and a cross, which no hashed pattern is mistaken for
+            (Card::JOKER, _) => 0b00100_01110_11111_01110_00100,
+            (Card::BOMB, _) => 0b10001_01010_00100_01010_10001,
+            (_, Some(rank)) => Face::hashed(rank as u64),
+            _ => Face::hashed((1 << 32) + card.0 as u64),
+        };
+        let mut blocks = [[false; Face::SIZE]; Face::SIZE];
+        for (y, row) in blocks.iter_mut().enumerate() {
+            for (x, block) in row.iter_mut().enumerate() {
+                *block = pattern >> (y * Face::SIZE + x) & 1 == 1;
+            }
+        }
+        Face { blocks, color }
+    }
+
+    /// A mirrored pattern picked by `key`, with some blocks set in every
+    /// row.
+    fn hashed(key: u64) -> u32 {
+        // SplitMix64, so that neighboring keys give unrelated patterns
+        let mut z = key.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+
+        let half = Face::SIZE.div_ceil(2);
+        let mut pattern = 0;
+        for y in 0..Face::SIZE {
+            let mut row = (z >> (y * half)) as u32 & ((1 << half) - 1);
+            if row == 0 {
+                row = 1 << (y % half);
+            }
+            for x in 0..half {
+                if row >> x & 1 == 1 {
+                    pattern |= 1 << (y * Face::SIZE + x);
+                    pattern |= 1 << (y * Face::SIZE + Face::SIZE - 1 - x);
+                }
+            }
+        }
+        pattern
+    }
+
+    /// Check if the pixel at `x, y` of an image `width` by `height` pixels
+    /// falls on a block of the pattern.
+    fn is_set(&self, x: usize, y: usize, width: usize, height: usize) -> bool {
+        self.blocks[y * Face::SIZE / height][x * Face::SIZE / width]
+    }
+
+    /// Draw the face with the kitty protocol. The terminal scales the image
+    /// to the two cells it is placed on, and `q=2` keeps it from replying.
+    fn kitty(&self) -> String {
+        const SIDE: usize = 20;
+        let Rgb(r, g, b) = self.color;
+        let mut pixels = Vec::with_capacity(SIDE * SIDE * 4);
+        for y in 0..SIDE {
+            for x in 0..SIDE {
+                let alpha = if self.is_set(x, y, SIDE, SIDE) {
+                    255
+                } else {
+                    0
+                };
+                pixels.extend([r, g, b, alpha]);
+            }
+        }
+        format!(
+            "{}_Ga=T,f=32,s={},v={},c=2,r=1,q=2;{}{}",
+            ESC,
+            SIDE,
+            SIDE,
+            base64(&pixels),
+            ST
+        )
+    }
+
+    /// Draw the face with Sixel, sized for the common cell of 8 by 16
+    /// pixels. Blank pixels are left transparent, and mode 8452 leaves the
+    /// cursor right of the image rather than below it.
+    fn sixel(&self) -> String {
+        const WIDTH: usize = 16;
+        const HEIGHT: usize = 12;
+        let percent = |c: u8| c as u32 * 100 / 255;
+        let Rgb(r, g, b) = self.color;
+        let mut out = format!(
+            "{}[?8452h{}P0;1;0q\"1;1;{};{}#1;2;{};{};{}",
+            ESC,
+            ESC,
+            WIDTH,
+            HEIGHT,
+            percent(r),
+            percent(g),
+            percent(b)
+        );
+        // Each character sets a column of six pixels, one bit per pixel, and
+        // `-` starts the next six rows
+        for band in (0..HEIGHT).step_by(6) {
+            if band > 0 {
+                out.push('-');
+            }
+            out.push_str("#1");
+            for x in 0..WIDTH {
+                let bits = (0..6)
+                    .filter(|dy| band + dy < HEIGHT)
+                    .filter(|dy| self.is_set(x, band + dy, WIDTH, HEIGHT))
+                    .fold(0u8, |bits, dy| bits | 1 << dy);
+                out.push((63 + bits) as char);
+            }
+        }
+        out.push_str(ST);
+        out
+    }
+}
+
+/// Encode bytes as standard base64 with padding, as the kitty protocol
+/// expects.
+fn base64(bytes: &[u8]) -> String {
+    const CHARS: &[u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let mut group = [0u8; 3];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let bits = u32::from_be_bytes([0, group[0], group[1], group[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (bits >> (18 - 6 * i)) & 63;
+                out.push(CHARS[index as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
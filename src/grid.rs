@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+
+use crate::board::{Idx2d, Vec2};
+
+/// How the cells of a board are arranged, which decides the cells next to
+/// each other. Cards are always stored and typed in as columns and rows;
+/// only the neighbors and the drawing of the board depend on the layout.
+pub trait Grid {
+    /// The indexer of the rows and columns the cells are stored in.
+    fn idx(&self) -> &Idx2d;
+
+    /// The cells sharing an edge with `c` that are on the board.
+    fn neighbors(&self, c: Vec2) -> Vec<Vec2>;
+
+    /// The number of steps between neighbors it takes to get from `a` to
+    /// `b`.
+    fn distance(&self, a: Vec2, b: Vec2) -> i32;
+}
+
+/// Square cells with four neighbors each.
+impl Grid for Idx2d {
+    fn idx(&self) -> &Idx2d {
+        self
+    }
+
+    fn neighbors(&self, c: Vec2) -> Vec<Vec2> {
+        [(1, 0), (-1, 0), (0, 1), (0, -1)]
+            .iter()
+            .map(|(dx, dy)| Vec2 {
+                x: c.x + dx,
+                y: c.y + dy,
+            })
+            .filter(|n| self.of(*n).is_ok())
+            .collect()
+    }
+
+    fn distance(&self, a: Vec2, b: Vec2) -> i32 {
+        (a.x - b.x).abs() + (a.y - b.y).abs()
+    }
+}
+
+/// Hexagonal cells with six neighbors each, stored in rows where every odd
+/// row is shifted right by half a cell.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct HexGrid {
+    idx: Idx2d,
+}
+
+impl HexGrid {
+    /// Directions to the neighbors of a cell in axial coordinates.
+    const DIRECTIONS: [(i32, i32); 6] =
+        [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)];
+
+    pub fn new(idx: Idx2d) -> HexGrid {
+        HexGrid { idx }
+    }
+
+    /// Convert the column and row of a cell into axial coordinates, where
+    /// `x` runs along the row and `y` along a diagonal.
+    pub fn to_axial(c: Vec2) -> Vec2 {
+        Vec2 {
+            x: c.x - (c.y - (c.y & 1)) / 2,
+            y: c.y,
+        }
+    }
+
+    /// Convert axial coordinates back into the column and row of a cell.
+    pub fn from_axial(a: Vec2) -> Vec2 {
+        Vec2 {
+            x: a.x + (a.y - (a.y & 1)) / 2,
+            y: a.y,
+        }
+    }
+}
+
+impl Grid for HexGrid {
+    fn idx(&self) -> &Idx2d {
+        &self.idx
+    }
+
+    fn neighbors(&self, c: Vec2) -> Vec<Vec2> {
+        let a = HexGrid::to_axial(c);
+        HexGrid::DIRECTIONS
+            .iter()
+            .map(|(dx, dy)| {
+                HexGrid::from_axial(Vec2 {
+                    x: a.x + dx,
+                    y: a.y + dy,
+                })
+            })
+            .filter(|n| self.idx.of(*n).is_ok())
+            .collect()
+    }
+
+    fn distance(&self, a: Vec2, b: Vec2) -> i32 {
+        let (a, b) = (HexGrid::to_axial(a), HexGrid::to_axial(b));
+        let (dx, dy) = (a.x - b.x, a.y - b.y);
+        (dx.abs() + dy.abs() + (dx + dy).abs()) / 2
+    }
+}
+
+/// The kinds of [`Grid`] a board can be laid out on.
+#[derive(
+    Clone, Copy, PartialEq, Eq, Debug, Default, Deserialize, Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Layout {
+    /// Square cells in rows and columns.
+    #[default]
+    Square,
+    /// Hexagonal cells in offset rows.
+    Hex,
+}
+
+impl Layout {
+    /// The grid of this layout for a board indexed by `idx`.
+    pub fn grid(self, idx: Idx2d) -> Box<dyn Grid> {
+        match self {
+            Layout::Square => Box::new(idx),
+            Layout::Hex => Box::new(HexGrid::new(idx)),
+        }
+    }
+}
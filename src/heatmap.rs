@@ -0,0 +1,77 @@
+use crate::board::Vec2;
+use crate::color::Rgb;
+use crate::input;
+use crate::style::Style;
+
+/// Characters for the levels of a heatmap, from positions never turned
+/// over to the ones turned over most.
+const SHADES: [&str; 5] = [" ", "░", "▒", "▓", "█"];
+
+/// [`SHADES`] for terminals that can't draw them.
+const ASCII_SHADES: [&str; 5] = [" ", ".", ":", "+", "#"];
+
+/// The colors of the lowest and the highest level.
+const COLD: Rgb = Rgb(40, 90, 220);
+const HOT: Rgb = Rgb(230, 40, 40);
+
+/// Draw how often each position of a board of `size` was turned over,
+/// given the `counts` in row major order, with the column letters above
+/// and the row numbers left of it. The last line is a legend.
+pub fn render(
+    size: Vec2,
+    counts: &[u32],
+    style: Style,
+    unicode: bool,
+) -> Vec<String> {
+    let shades = if unicode { SHADES } else { ASCII_SHADES };
+    let top = SHADES.len() - 1;
+    let max = counts.iter().copied().max().unwrap_or(0);
+    // Positions turned over at all get at least the first level
+    let level = |count: u32| match max {
+        0 => 0,
+        _ => (count as usize * top).div_ceil(max as usize),
+    };
+    let shade = |level: usize| {
+        let text = shades[level].repeat(2);
+        match level {
+            0 => text,
+            _ => style.paint(&text, Some(gradient(level, top))),
+        }
+    };
+
+    let label_width = size.y.to_string().len();
+    let mut lines = Vec::new();
+    let mut letters = " ".repeat(label_width + 1);
+    for x in 0..size.x {
+        letters.push_str(&format!("{:<3}", input::column_letters(x)));
+    }
+    lines.push(letters.trim_end().to_string());
+    for y in 0..size.y {
+        let mut line = format!("{:>1$} ", y + 1, label_width);
+        for x in 0..size.x {
+            let index = (y * size.x + x) as usize;
+            let count = counts.get(index).copied().unwrap_or(0);
+            line.push_str(&shade(level(count)));
+            line.push(' ');
+        }
+        lines.push(line.trim_end().to_string());
+    }
+    let scale: Vec<_> = (1..=top).map(shade).collect();
+    lines.push(format!(
+        "{} 1 {} {}",
+        " ".repeat(label_width),
+        scale.join(""),
+        max
+    ));
+    lines
+}
+
+/// The color of `level` out of `top`, from [`COLD`] to [`HOT`].
+fn gradient(level: usize, top: usize) -> Rgb {
+    let mix = |cold: u8, hot: u8| {
+        let cold = cold as usize * (top - level);
+        let hot = hot as usize * level;
+        ((cold + hot) / top) as u8
+    };
+    Rgb(mix(COLD.0, HOT.0), mix(COLD.1, HOT.1), mix(COLD.2, HOT.2))
+}
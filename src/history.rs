@@ -0,0 +1,282 @@
+//! Every finished game, kept in a local SQLite database to be queried by
+//! the `stats` subcommand. Only built with the `history` feature; the
+//! aggregated [statistics](`crate::stats`) are kept either way.
+
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use rusqlite::{params, Connection};
+
+use crate::board::Vec2;
+use crate::daily::civil_from_days;
+use crate::error::{GameError, Result};
+use crate::export::ExportedGame;
+use crate::stats::{data_dir, GameRecord};
+
+/// The columns an [`Entry`] is read from, in the order [`entry`] expects.
+const ENTRY_COLUMNS: &str = "finished_at, mode, columns, rows, seed, \
+                             guesses, pairs, duration_ms, won";
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS games (
+        id INTEGER PRIMARY KEY,
+        finished_at INTEGER NOT NULL,
+        mode TEXT NOT NULL,
+        columns INTEGER NOT NULL,
+        rows INTEGER NOT NULL,
+        seed INTEGER,
+        guesses INTEGER NOT NULL,
+        pairs INTEGER NOT NULL,
+        duration_ms INTEGER NOT NULL,
+        won INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS games_by_mode ON games (mode);
+";
+
+/// A finished game as it was recorded.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Entry {
+    /// Seconds since 1970-01-01 when the game ended.
+    pub finished_at: u64,
+    pub mode: String,
+    /// Number of columns and rows of the board.
+    pub size: Vec2,
+    /// The seed the board was shuffled with, if it was seeded.
+    pub seed: Option<u64>,
+    pub guesses: i32,
+    pub pairs: i32,
+    pub duration: Duration,
+    pub won: bool,
+}
+
+impl Entry {
+    /// The day the game ended as `YYYY-MM-DD`, in UTC.
+    pub fn date(&self) -> String {
+        let (y, m, d) = civil_from_days((self.finished_at / 86_400) as i64);
+        format!("{:04}-{:02}-{:02}", y, m, d)
+    }
+}
+
+impl From<&Entry> for ExportedGame {
+    fn from(entry: &Entry) -> Self {
+        ExportedGame {
+            finished_at: entry.finished_at,
+            date: entry.date(),
+            mode: entry.mode.clone(),
+            columns: entry.size.x,
+            rows: entry.size.y,
+            seed: entry.seed,
+            guesses: entry.guesses,
+            pairs: entry.pairs,
+            duration_ms: entry.duration.as_millis() as u64,
+            won: entry.won,
+        }
+    }
+}
+
+/// The games of a mode taken together.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ModeSummary {
+    pub mode: String,
+    pub played: u32,
+    pub won: u32,
+    /// Average number of guesses in the games won, if any.
+    pub average_guesses: Option<f64>,
+    /// The fastest win, if any.
+    pub best_time: Option<Duration>,
+}
+
+/// The history of games backed by a database file.
+pub struct History {
+    path: PathBuf,
+    conn: Connection,
+}
+
+impl History {
+    /// Location of the database:
+    /// `$XDG_DATA_HOME/card-matching/history.sqlite3`, falling back to
+    /// `~/.local/share/card-matching/history.sqlite3`.
+    pub fn default_path() -> Option<PathBuf> {
+        Some(data_dir()?.join("history.sqlite3"))
+    }
+
+    /// Open the database at `path`, creating it if needed.
+    pub fn open(path: &Path) -> Result<History> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| history_error(path, e))?;
+        }
+        let conn =
+            Connection::open(path).map_err(|e| history_error(path, e))?;
+        conn.execute_batch(SCHEMA)
+            .map_err(|e| history_error(path, e))?;
+        Ok(History {
+            path: path.to_owned(),
+            conn,
+        })
+    }
+
+    /// Add a finished game.
+    pub fn record(&self, game: &GameRecord) -> Result<()> {
+        let finished_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as i64;
+        self.conn
+            .execute(
+                "INSERT INTO games (finished_at, mode, columns, rows, seed, \
+                 guesses, pairs, duration_ms, won) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    finished_at,
+                    game.mode,
+                    game.size.x,
+                    game.size.y,
+                    // SQLite integers are signed, so seeds are stored with
+                    // the same bits
+                    game.seed.map(|seed| seed as i64),
+                    game.guesses,
+                    game.pairs,
+                    game.duration.as_millis() as i64,
+                    game.won,
+                ],
+            )
+            .map_err(|e| history_error(&self.path, e))?;
+        Ok(())
+    }
+
+    /// The games of each mode taken together, or only of `mode` if given,
+    /// in alphabetical order of the modes.
+    pub fn summaries(&self, mode: Option<&str>) -> Result<Vec<ModeSummary>> {
+        let error = |e| history_error(&self.path, e);
+        let mut statement = self
+            .conn
+            .prepare(
+                "SELECT mode, COUNT(*), SUM(won), \
+                 AVG(CASE WHEN won THEN guesses END), \
+                 MIN(CASE WHEN won THEN duration_ms END) \
+                 FROM games WHERE ?1 IS NULL OR mode = ?1 \
+                 GROUP BY mode ORDER BY mode",
+            )
+            .map_err(error)?;
+        let rows = statement
+            .query_map(params![mode], |row| {
+                Ok(ModeSummary {
+                    mode: row.get(0)?,
+                    played: row.get(1)?,
+                    won: row.get(2)?,
+                    average_guesses: row.get(3)?,
+                    best_time: row
+                        .get::<_, Option<i64>>(4)?
+                        .map(|millis| Duration::from_millis(millis as u64)),
+                })
+            })
+            .map_err(error)?;
+        rows.collect::<rusqlite::Result<_>>().map_err(error)
+    }
+
+    /// The last `limit` games, or only those of `mode` if given, the latest
+    /// first.
+    pub fn recent(
+        &self,
+        mode: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Entry>> {
+        self.latest(mode, "1", limit)
+    }
+
+    /// The last `limit` games won with at least a pair on the board, or
+    /// only those of `mode` if given, the latest first.
+    pub fn recent_wins(
+        &self,
+        mode: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Entry>> {
+        self.latest(mode, "won AND pairs > 0", limit)
+    }
+
+    /// The number of games won in a row up to the latest, and the most
+    /// ever won in a row, only counting games of `mode` if given.
+    pub fn win_streaks(&self, mode: Option<&str>) -> Result<(u32, u32)> {
+        let (mut run, mut longest) = (0, 0);
+        self.each(mode, |entry| {
+            run = if entry.won { run + 1 } else { 0 };
+            longest = longest.max(run);
+            Ok(())
+        })?;
+        Ok((run, longest))
+    }
+
+    /// The last `limit` games matching the SQL `filter`, or only those of
+    /// `mode` if given, the latest first.
+    fn latest(
+        &self,
+        mode: Option<&str>,
+        filter: &str,
+        limit: usize,
+    ) -> Result<Vec<Entry>> {
+        let error = |e| history_error(&self.path, e);
+        let mut statement = self
+            .conn
+            .prepare(&format!(
+                "SELECT {} FROM games WHERE (?1 IS NULL OR mode = ?1) \
+                 AND {} ORDER BY id DESC LIMIT ?2",
+                ENTRY_COLUMNS, filter
+            ))
+            .map_err(error)?;
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let rows = statement
+            .query_map(params![mode, limit], entry)
+            .map_err(error)?;
+        rows.collect::<rusqlite::Result<_>>().map_err(error)
+    }
+
+    /// Call `f` with every game, or only those of `mode` if given, the
+    /// oldest first. The games are read one at a time, stopping at the
+    /// first error `f` returns.
+    pub fn each(
+        &self,
+        mode: Option<&str>,
+        mut f: impl FnMut(&Entry) -> Result<()>,
+    ) -> Result<()> {
+        let error = |e| history_error(&self.path, e);
+        let mut statement = self
+            .conn
+            .prepare(&format!(
+                "SELECT {} FROM games WHERE ?1 IS NULL OR mode = ?1 \
+                 ORDER BY id",
+                ENTRY_COLUMNS
+            ))
+            .map_err(error)?;
+        let rows = statement.query_map(params![mode], entry).map_err(error)?;
+        for row in rows {
+            f(&row.map_err(error)?)?;
+        }
+        Ok(())
+    }
+}
+
+/// Read an [`Entry`] from a row of [`ENTRY_COLUMNS`].
+fn entry(row: &rusqlite::Row) -> rusqlite::Result<Entry> {
+    Ok(Entry {
+        finished_at: row.get::<_, i64>(0)? as u64,
+        mode: row.get(1)?,
+        size: Vec2 {
+            x: row.get(2)?,
+            y: row.get(3)?,
+        },
+        seed: row.get::<_, Option<i64>>(4)?.map(|seed| seed as u64),
+        guesses: row.get(5)?,
+        pairs: row.get(6)?,
+        duration: Duration::from_millis(row.get::<_, i64>(7)? as u64),
+        won: row.get(8)?,
+    })
+}
+
+fn history_error(path: &Path, e: impl ToString) -> GameError {
+    GameError::HistoryIo {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    }
+}
@@ -0,0 +1,116 @@
+use std::{collections::HashMap, env, fmt::Display, sync::OnceLock};
+
+/// Message catalogs for every supported locale, in a subset of the Fluent
+/// syntax: one `key = message` per line, with `{ $name }` placeholders.
+const CATALOGS: [(&str, &str); 2] = [
+    ("en", include_str!("../locales/en.ftl")),
+    ("hu", include_str!("../locales/hu.ftl")),
+];
+
+/// Locale used when the requested one is unavailable, or lacks a message.
+const FALLBACK_LOCALE: &str = "en";
+
+static CATALOG: OnceLock<Catalog> = OnceLock::new();
+
+/// The messages of a locale, along with the fallback messages.
+struct Catalog {
+    messages: HashMap<&'static str, &'static str>,
+    fallback: HashMap<&'static str, &'static str>,
+}
+
+impl Catalog {
+    fn new(locale: &str) -> Catalog {
+        let source = |locale: &str| {
+            CATALOGS
+                .iter()
+                .find(|(name, _)| *name == locale)
+                .map(|(_, source)| *source)
+        };
+        let fallback = source(FALLBACK_LOCALE).expect("fallback is bundled");
+        Catalog {
+            messages: parse(source(locale).unwrap_or(fallback)),
+            fallback: parse(fallback),
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&'static str> {
+        self.messages
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .copied()
+    }
+}
+
+/// Select the language of all messages. If `lang` is `None`, it is taken
+/// from the `LC_ALL`, `LC_MESSAGES` and `LANG` environment variables. Only
+/// the first call has an effect, and it must happen before any message is
+/// looked up.
+pub fn set_locale(lang: Option<&str>) {
+    let locale = lang.map(str::to_owned).or_else(env_locale);
+    let locale = locale.as_deref().map(language).unwrap_or(FALLBACK_LOCALE);
+    let _ = CATALOG.set(Catalog::new(locale));
+}
+
+/// Look up the message `key` and fill in its placeholders. Prefer the
+/// [`t!`](crate::t) macro.
+pub fn message(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    let catalog = CATALOG.get_or_init(|| {
+        let locale = env_locale();
+        Catalog::new(locale.as_deref().map(language).unwrap_or(""))
+    });
+    let template = match catalog.get(key) {
+        Some(template) => template,
+        None => return key.to_owned(),
+    };
+
+    let mut message = template.to_owned();
+    for (name, value) in args {
+        let value = value.to_string();
+        message = message
+            .replace(&format!("{{ ${} }}", name), &value)
+            .replace(&format!("{{${}}}", name), &value);
+    }
+    message
+}
+
+/// Look up a translated message, filling in the named placeholders.
+///
+/// ```ignore
+/// t!("games-played", count = stats.games_played)
+/// ```
+#[macro_export]
+macro_rules! t {
+    ($key:literal) => {
+        $crate::i18n::message($key, &[])
+    };
+    ($key:literal, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::message(
+            $key,
+            &[$((stringify!($name), &$value as &dyn ::std::fmt::Display)),+],
+        )
+    };
+}
+
+/// The locale requested through the environment, e.g. `hu_HU.UTF-8`.
+fn env_locale() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty())
+}
+
+/// Strip the region and encoding from a locale, e.g. `hu_HU.UTF-8` -> `hu`.
+fn language(locale: &str) -> &str {
+    locale.split(['_', '-', '.', '@']).next().unwrap_or(locale)
+}
+
+/// Parse `key = message` lines, skipping blank lines and `#` comments.
+fn parse(source: &'static str) -> HashMap<&'static str, &'static str> {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, message)| (key.trim(), message.trim()))
+        .collect()
+}
@@ -0,0 +1,239 @@
+//! Parsers for the text typed by players. They only depend on their
+//! arguments, so they can be fuzzed and tested without a [`Game`].
+//!
+//! [`Game`]: crate::game::Game
+
+use crate::board::{Board, Idx2d, Vec2};
+use crate::error::{GameError, Result};
+
+/// Splits the input into two numbers, if it is written in a certain way.
+type PairFormat = fn(&str) -> Option<[&str; 2]>;
+
+/// The ways a pair of numbers can be typed, tried in order.
+const PAIR_FORMATS: &[PairFormat] = &[separated, spaced, parenthesized, named];
+
+/// `3,4` or `3;4`.
+fn separated(s: &str) -> Option<[&str; 2]> {
+    let mut parts = s.split([',', ';']);
+    Some([parts.next()?, parts.next()?])
+}
+
+/// `3 4`.
+fn spaced(s: &str) -> Option<[&str; 2]> {
+    let mut parts = s.split_whitespace();
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(x), Some(y), None) => Some([x, y]),
+        _ => None,
+    }
+}
+
+/// `(3,4)` or `(3 4)`.
+fn parenthesized(s: &str) -> Option<[&str; 2]> {
+    let inner = s.strip_prefix('(')?.strip_suffix(')')?;
+    separated(inner).or_else(|| spaced(inner))
+}
+
+/// `x=3 y=4`, in either order.
+fn named(s: &str) -> Option<[&str; 2]> {
+    let (mut x, mut y) = (None, None);
+    for part in s.split([' ', ',', ';']).filter(|part| !part.is_empty()) {
+        let (name, value) = part.split_once('=')?;
+        let slot = match name {
+            "x" | "X" => &mut x,
+            "y" | "Y" => &mut y,
+            _ => return None,
+        };
+        if slot.replace(value).is_some() {
+            return None;
+        }
+    }
+    Some([x?, y?])
+}
+
+/// Attempt to parse a pair of i32 numbers from the string slice, in any of
+/// the [formats](`PAIR_FORMATS`) with any amount of whitespace around the
+/// numbers.
+pub fn parse_pair(s: &str) -> Result<Vec2> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err(GameError::EmptyInput);
+    }
+
+    for split in PAIR_FORMATS {
+        if let Some([x, y]) = split(s) {
+            if let (Ok(x), Ok(y)) = (x.trim().parse(), y.trim().parse()) {
+                return Ok(Vec2 { x, y });
+            }
+        }
+    }
+
+    let numbers = s
+        .split(|c: char| !c.is_ascii_digit() && c != '-')
+        .filter(|part| part.parse::<i32>().is_ok())
+        .count();
+    match numbers {
+        1 => Err(GameError::SingleNumber {
+            input: s.to_owned(),
+        }),
+        _ => Err(GameError::UnparsableInput),
+    }
+}
+
+/// Attempt to interpret the string slice as the size of the game board.
+pub fn parse_dimensions(s: &str) -> Result<Vec2> {
+    let p = parse_pair(s).map_err(|e| match e {
+        GameError::UnparsableInput => GameError::UnreadableDimensions {
+            input: s.trim().to_owned(),
+        },
+        e => e,
+    })?;
+    validate_dimensions(p)?;
+    Ok(p)
+}
+
+/// Check that a board of the given size can be created.
+pub fn validate_dimensions(p: Vec2) -> Result<()> {
+    if p.x <= 0 {
+        return Err(GameError::CoordinateUnderflow { axis: 'x' });
+    }
+    if p.y <= 0 {
+        return Err(GameError::CoordinateUnderflow { axis: 'y' });
+    }
+
+    let cells = Idx2d::new(p.x, p.y)
+        .cells()
+        .ok_or(GameError::BoardTooLarge { x: p.x, y: p.y })?;
+
+    // Symbols can only be repeated so many times before the cards become
+    // hard to tell apart. The card sets with fewer cards are checked when
+    // the board is dealt.
+    if cells > Board::MAX_ASCII_SIZE {
+        return Err(GameError::NotEnoughCardTypes {
+            max: Board::MAX_ASCII_SIZE / 2,
+        });
+    }
+
+    Ok(())
+}
+
+/// Attempt to interpret the string slice as the position of a card on a
+/// board indexed by `idx`, either as a pair of coordinates or as the
+/// [number](`Idx2d::number`) of the cell. Returns 0-based coordinates. The
+/// errors repeat the input, and say which format or range was expected.
+pub fn parse_coords(s: &str, idx: &Idx2d) -> Result<Vec2> {
+    let input = s.trim();
+    if let Ok(number) = input.parse::<i32>() {
+        return idx
+            .from_number(number)
+            .ok_or_else(|| GameError::NoSuchCell {
+                input: input.to_owned(),
+                max: idx.cells().unwrap_or(i32::MAX),
+            });
+    }
+    let unreadable = || GameError::UnreadableCoords {
+        input: input.to_owned(),
+    };
+    let p = match split_chess_coords(input) {
+        Some((column, row)) => Vec2 {
+            x: parse_column(column, idx.size_x)?,
+            y: row.parse().map_err(|_| unreadable())?,
+        },
+        None => parse_pair(s).map_err(|e| match e {
+            GameError::UnparsableInput => unreadable(),
+            e => e,
+        })?,
+    };
+    let off_board = |axis, size| GameError::OffBoard {
+        input: input.to_owned(),
+        axis,
+        size,
+    };
+    if !(1..=idx.size_x).contains(&p.x) {
+        return Err(off_board('x', idx.size_x));
+    }
+    if !(1..=idx.size_y).contains(&p.y) {
+        return Err(off_board('y', idx.size_y));
+    }
+    Ok(Vec2 {
+        x: p.x - 1,
+        y: p.y - 1,
+    })
+}
+
+/// Split coordinates in the `c4` format into the column letters and the
+/// row number.
+pub fn split_chess_coords(s: &str) -> Option<(&str, &str)> {
+    let digits = s.find(|c: char| c.is_ascii_digit())?;
+    let (column, row) = s.split_at(digits);
+    let column = column.trim();
+    if column.is_empty() || !row.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    // Not an attempt at one of the pair formats either
+    if column.contains([',', ';', '=', '(']) {
+        return None;
+    }
+    Some((column, row))
+}
+
+/// Parse column letters (`a` to `z`, then `aa`, `ab`, ...) into a 1-based
+/// column number, which can't be more than `columns`.
+pub fn parse_column(letters: &str, columns: i32) -> Result<i32> {
+    let mut column = 0i32;
+    for c in letters.chars() {
+        if !c.is_ascii_alphabetic() {
+            return Err(GameError::InvalidColumnLetter { letter: c });
+        }
+        let digit = (c.to_ascii_lowercase() as u8 - b'a') as i32 + 1;
+        column = column.saturating_mul(26).saturating_add(digit);
+    }
+    if column > columns {
+        return Err(GameError::ColumnOutOfRange {
+            column: letters.to_lowercase(),
+            last: column_letters(columns - 1),
+        });
+    }
+    Ok(column)
+}
+
+/// The letters of the column at 0-based index `x`: `a` to `z`, then `aa`,
+/// `ab`, ...
+pub fn column_letters(x: i32) -> String {
+    let mut letters = Vec::new();
+    let mut n = x + 1;
+    while n > 0 {
+        n -= 1;
+        letters.push((b'a' + (n % 26) as u8) as char);
+        n /= 26;
+    }
+    letters.iter().rev().collect()
+}
+
+/// The name of the cell at `c` as typed to pick it, e.g. `c4`.
+pub fn cell_name(c: Vec2) -> String {
+    format!("{}{}", column_letters(c.x), c.y + 1)
+}
+
+/// Parse a yes/no response from the string slice. Defaults to `false`.
+pub fn parse_yn(s: &str) -> Result<bool> {
+    match s.to_lowercase().trim() {
+        "y" => Ok(true),
+        "n" => Ok(false),
+        "" => Ok(false),
+        s => Err(GameError::UnreadableAnswer {
+            input: s.to_owned(),
+        }),
+    }
+}
+
+/// Parse a request to scroll the board, e.g. `left`, into the direction to
+/// scroll in.
+pub fn parse_scroll(s: &str) -> Option<Vec2> {
+    match s.trim().to_lowercase().as_str() {
+        "up" => Some(Vec2 { x: 0, y: -1 }),
+        "down" => Some(Vec2 { x: 0, y: 1 }),
+        "left" => Some(Vec2 { x: -1, y: 0 }),
+        "right" => Some(Vec2 { x: 1, y: 0 }),
+        _ => None,
+    }
+}
@@ -0,0 +1,178 @@
+//! Single keys standing in for longer inputs, e.g. `h` for `:hint`. A key
+//! only counts when it is typed on a line of its own, so it never gets in
+//! the way of coordinates.
+
+use std::collections::BTreeMap;
+
+use crate::error::{GameError, Result};
+
+/// Something the player can do with a single key.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Action {
+    Hint,
+    Help,
+    Keys,
+    Settings,
+    Restart,
+    Quit,
+    ScrollUp,
+    ScrollDown,
+    ScrollLeft,
+    ScrollRight,
+}
+
+impl Action {
+    /// Every action, in the order they are listed.
+    pub const ALL: [Action; 10] = [
+        Action::Hint,
+        Action::Help,
+        Action::Keys,
+        Action::Settings,
+        Action::Restart,
+        Action::Quit,
+        Action::ScrollUp,
+        Action::ScrollDown,
+        Action::ScrollLeft,
+        Action::ScrollRight,
+    ];
+
+    /// The name of the action in the `[key_bindings]` table of the
+    /// configuration file.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Hint => "hint",
+            Action::Help => "help",
+            Action::Keys => "keys",
+            Action::Settings => "settings",
+            Action::Restart => "restart",
+            Action::Quit => "quit",
+            Action::ScrollUp => "scroll_up",
+            Action::ScrollDown => "scroll_down",
+            Action::ScrollLeft => "scroll_left",
+            Action::ScrollRight => "scroll_right",
+        }
+    }
+
+    /// Parse the name of an action, as in the configuration file.
+    pub fn parse(name: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|action| action.name() == name)
+    }
+
+    /// The input the key of the action is replaced with.
+    fn input(self) -> &'static str {
+        match self {
+            Action::Hint => ":hint",
+            Action::Help => ":help",
+            Action::Keys => ":keys",
+            Action::Settings => ":settings",
+            Action::Restart => ":restart",
+            Action::Quit => ":quit",
+            Action::ScrollUp => "up",
+            Action::ScrollDown => "down",
+            Action::ScrollLeft => "left",
+            Action::ScrollRight => "right",
+        }
+    }
+
+    /// The key bound to the action unless configured otherwise, if any.
+    fn default_key(self) -> Option<char> {
+        match self {
+            Action::Hint => Some('h'),
+            Action::Help => Some('?'),
+            Action::Keys => Some('k'),
+            Action::Quit => Some('q'),
+            _ => None,
+        }
+    }
+}
+
+/// Characters that mean something on their own at some prompt: menu
+/// choices, answers to yes/no questions, saving at the quit prompt and the
+/// separators of coordinates.
+fn is_reserved(key: char) -> bool {
+    key.is_ascii_digit()
+        || key.is_whitespace()
+        || matches!(key.to_ascii_lowercase(), 'y' | 'n' | 's' | ',' | ';' | ':')
+}
+
+/// The key bound to each action.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Keymap {
+    keys: BTreeMap<char, Action>,
+}
+
+impl Keymap {
+    /// Bind keys to actions as given by the `[key_bindings]` table of the
+    /// configuration file, on top of the default keys. A default key taken
+    /// by another action is unbound from its own one. Fails on unknown
+    /// actions, keys that are more than one character or reserved, and
+    /// keys bound to more than one action.
+    pub fn new(bindings: &BTreeMap<String, String>) -> Result<Keymap> {
+        let invalid = |reason: String| GameError::InvalidConfig { reason };
+        let mut configured = BTreeMap::new();
+        for (name, key) in bindings {
+            let action = Action::parse(name).ok_or_else(|| {
+                invalid(format!("there is no action called `{}`", name))
+            })?;
+            let mut chars = key.chars();
+            let key = match (chars.next(), chars.next()) {
+                (Some(key), None) => key,
+                _ => {
+                    return Err(invalid(format!(
+                        "key binding for `{}` must be a single character",
+                        name
+                    )))
+                }
+            };
+            if is_reserved(key) {
+                return Err(invalid(format!(
+                    "`{}` can't be bound to `{}`, it is used by the prompts",
+                    key, name
+                )));
+            }
+            if let Some(other) = configured.insert(key, action) {
+                return Err(invalid(format!(
+                    "`{}` is bound to both `{}` and `{}`",
+                    key,
+                    other.name(),
+                    name
+                )));
+            }
+        }
+
+        let mut keys = configured.clone();
+        for action in Action::ALL {
+            let rebound = configured.values().any(|a| *a == action);
+            match action.default_key() {
+                Some(key) if !rebound && !configured.contains_key(&key) => {
+                    keys.insert(key, action);
+                }
+                _ => {}
+            }
+        }
+        Ok(Keymap { keys })
+    }
+
+    /// The input that `line` stands for, if it is a bound key.
+    pub fn translate(&self, line: &str) -> Option<&'static str> {
+        let mut chars = line.trim().chars();
+        match (chars.next(), chars.next()) {
+            (Some(key), None) => self.keys.get(&key).map(|a| a.input()),
+            _ => None,
+        }
+    }
+
+    /// The key bound to `action`, if any.
+    pub fn key(&self, action: Action) -> Option<char> {
+        self.keys
+            .iter()
+            .find(|(_, a)| **a == action)
+            .map(|(key, _)| *key)
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Keymap {
+        Keymap::new(&BTreeMap::new()).expect("default keys don't conflict")
+    }
+}
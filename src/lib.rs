@@ -0,0 +1,49 @@
+pub mod analysis;
+pub mod audio;
+pub mod board;
+pub mod campaign;
+pub mod challenge;
+pub mod chart;
+pub mod chat;
+pub mod clock;
+pub mod code;
+pub mod color;
+pub mod config;
+pub mod daily;
+pub mod elo;
+pub mod error;
+pub mod event;
+pub mod export;
+pub mod fair;
+pub mod flow;
+pub mod game;
+pub mod graphics;
+pub mod grid;
+pub mod heatmap;
+#[cfg(feature = "history")]
+pub mod history;
+pub mod i18n;
+pub mod input;
+pub mod keymap;
+pub mod lobby;
+pub mod net;
+pub mod persistence;
+pub mod power;
+pub mod probability;
+pub mod profile;
+pub mod protocol;
+pub mod rating;
+pub mod replay;
+pub mod save;
+pub mod scoring;
+pub mod screen;
+pub mod shape;
+pub mod solver;
+pub mod stats;
+pub mod style;
+pub mod terminal;
+pub mod theme;
+pub mod tournament;
+pub mod view;
+#[cfg(target_arch = "wasm32")]
+pub mod wasm;
@@ -0,0 +1,649 @@
+//! A server where players meet to play networked games against each
+//! other, and the client side of it.
+//!
+//! Players connect to the lobby and either open a room with a board of
+//! their chosen size, take the second seat of a room listed as waiting for
+//! one, or let the lobby pair them with anyone waiting for a board of the
+//! same size. Once both seats of a room are taken, the lobby deals the
+//! board and plays the part of the host for both players: it
+//! [commits](`crate::fair`) to the board, answers every card picked with
+//! its face, and keeps track of whose turn it is. A player picking a card
+//! out of turn, or one that can't be picked, is disconnected, as their
+//! game can't have sent it.
+//!
+//! Anyone else can watch the game of a room as a spectator. Spectators
+//! are sent the whole [state](`RoomState`) of the room whenever it changes,
+//! and can't pick cards.
+//!
+//! The lobby can limit the time each turn takes. A player who runs out of
+//! time loses the rest of their turn to the other player, and both are
+//! told, so that no one can stall a game by never picking a card.
+//!
+//! The players of a room can [chat](`crate::chat`). The lobby cleans up and
+//! rate limits their messages before passing them on to everyone else in
+//! the room, spectators included.
+//!
+//! Each connection is read on a thread of its own, while the rooms are
+//! only ever touched by the thread running [`serve`].
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{self, ErrorKind},
+    net::{TcpListener, ToSocketAddrs},
+    sync::mpsc::{self, RecvTimeoutError, Sender},
+    thread,
+    time::Duration,
+};
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::board::{Board, Vec2};
+use crate::chat::{self, RateLimit};
+use crate::clock::Instant;
+use crate::fair::Sealed;
+use crate::game::Game;
+use crate::input;
+use crate::net::{
+    Cell, Connection, Message, Refusal, Role, RoomInfo, RoomState,
+};
+use crate::t;
+
+/// Identifies a connection to the lobby.
+type SessionId = u64;
+
+/// What the thread reading a connection passes on.
+enum Incoming {
+    Joined(SessionId, Connection),
+    Message(SessionId, Message),
+    Left(SessionId),
+}
+
+/// A room, and the board played in it once both seats are taken.
+struct Room {
+    /// The sessions in each seat, in turn order.
+    seats: Vec<SessionId>,
+    board: Board,
+    sealed: Sealed,
+    /// Set for the cards of the pairs found so far.
+    found: Vec<bool>,
+    /// The cards picked in the current turn, or in the one before it if no
+    /// card was picked since.
+    revealed: Vec<Vec2>,
+    /// The seat whose turn it is.
+    turn: usize,
+    /// Number of pairs found by each seat.
+    pairs: [u32; 2],
+    /// The sessions watching the game.
+    spectators: Vec<SessionId>,
+    /// How much the player in each seat chats.
+    chat: [RateLimit; 2],
+    /// How long each turn may take, if limited.
+    turn_time: Option<Duration>,
+    /// When the current turn runs out, once the game has started.
+    deadline: Option<Instant>,
+    /// The seat whose turn last ran out, until the turn comes back to it.
+    /// A card it picked before learning about it is ignored.
+    expired: Option<usize>,
+    /// Whether the player in each seat introduced themselves already.
+    introduced: [bool; 2],
+}
+
+impl Room {
+    /// Deal a board of `size` for a room opened by `session`, with turns
+    /// limited to `turn_time`, if set.
+    fn open(
+        session: SessionId,
+        size: Vec2,
+        turn_time: Option<Duration>,
+    ) -> Option<Room> {
+        input::validate_dimensions(size).ok()?;
+        let board =
+            Board::new(size.x, size.y, &mut StdRng::from_entropy()).ok()?;
+        let cards = board.cards.iter().map(|card| card.map(|card| card.0));
+        Some(Room {
+            seats: vec![session],
+            sealed: Sealed::new(size, cards.collect()),
+            found: board.cards.iter().map(Option::is_none).collect(),
+            board,
+            revealed: Vec::new(),
+            turn: 0,
+            pairs: [0, 0],
+            spectators: Vec::new(),
+            chat: Default::default(),
+            turn_time,
+            deadline: None,
+            expired: None,
+            introduced: [false; 2],
+        })
+    }
+
+    fn size(&self) -> Vec2 {
+        let idx = self.board.idx();
+        Vec2 {
+            x: idx.size_x,
+            y: idx.size_y,
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.seats.len() == 2
+    }
+
+    /// The message committing to the board, for both players.
+    fn commit(&self) -> Message {
+        Message::Commit {
+            size: self.size(),
+            holes: self.board.cards.iter().map(Option::is_none).collect(),
+            hash: self.sealed.commitment(),
+        }
+    }
+
+    /// The state of the game, for the spectators.
+    fn state(&self) -> Message {
+        let cells = self.board.cards.iter().enumerate().map(|(i, card)| {
+            let at = self.board.idx().coords(i);
+            match card {
+                None => Cell::Hole,
+                Some(card) if self.found[i] => Cell::Found(card.0),
+                Some(card) if self.revealed.contains(&at) => {
+                    Cell::Revealed(card.0)
+                }
+                Some(_) => Cell::FaceDown,
+            }
+        });
+        Message::State(RoomState {
+            size: self.size(),
+            cells: cells.collect(),
+            turn: self.turn,
+            pairs: self.pairs,
+        })
+    }
+
+    /// Start the clock on the next turn, if turns are limited. A turn too
+    /// long to tell the end of isn't limited.
+    fn start_turn(&mut self) {
+        self.deadline = self
+            .turn_time
+            .and_then(|time| Instant::now().checked_add(time));
+        if self.expired == Some(self.turn) {
+            self.expired = None;
+        }
+    }
+
+    /// End the current turn, which ran out of time, and pass play to the
+    /// other seat. Returns the message for both players.
+    fn expire(&mut self) -> Message {
+        let seat = self.turn;
+        self.revealed.clear();
+        self.expired = Some(seat);
+        self.turn = 1 - seat;
+        self.start_turn();
+        Message::TurnExpired { seat }
+    }
+
+    /// Pick the card at `at` for the player in `seat`. Returns the messages
+    /// for both players, or `None` if the move isn't allowed.
+    fn reveal(&mut self, seat: usize, at: Vec2) -> Option<Vec<Message>> {
+        let i = self.board.idx().of(at).ok()?;
+        if self.revealed.len() == 2 {
+            self.revealed.clear();
+        }
+        // Sent before the player heard that their turn ran out
+        if seat != self.turn && self.expired == Some(seat) {
+            self.expired = None;
+            return Some(Vec::new());
+        }
+        if !self.is_full()
+            || seat != self.turn
+            || self.found[i]
+            || self.revealed.contains(&at)
+        {
+            return None;
+        }
+        let mut messages = vec![Message::Revealed {
+            at,
+            card: self.board[at].0,
+        }];
+        self.revealed.push(at);
+        let &[first, second] = self.revealed.as_slice() else {
+            return Some(messages);
+        };
+        if self.board.is_match(first, second) {
+            self.found[i] = true;
+            self.found[self.board.idx().unchecked(first)] = true;
+            self.pairs[seat] += 1;
+            if self.is_over() {
+                let (salt, cards) = self.sealed.open();
+                messages.push(Message::Open { salt, cards });
+            }
+        } else {
+            self.turn = 1 - self.turn;
+        }
+        self.start_turn();
+        Some(messages)
+    }
+
+    fn is_over(&self) -> bool {
+        self.found.iter().all(|found| *found)
+    }
+}
+
+/// The rooms and the players connected to the lobby.
+struct Lobby {
+    sessions: HashMap<SessionId, Connection>,
+    /// The room each seated player is in.
+    seated: HashMap<SessionId, u32>,
+    /// The room each spectator is watching.
+    watching: HashMap<SessionId, u32>,
+    rooms: BTreeMap<u32, Room>,
+    next_room: u32,
+    /// How long each turn may take, if limited.
+    turn_time: Option<Duration>,
+}
+
+/// Listen for players on `port` and pair them up until the process is
+/// stopped. Turns are limited to `turn_time`, if set.
+pub fn serve(port: u16, turn_time: Option<Duration>) -> io::Result<()> {
+    let listener = TcpListener::bind(("0.0.0.0", port))?;
+    let (events, incoming) = mpsc::channel();
+    thread::spawn(move || accept(listener, events));
+
+    let mut lobby = Lobby {
+        sessions: HashMap::new(),
+        seated: HashMap::new(),
+        watching: HashMap::new(),
+        rooms: BTreeMap::new(),
+        next_room: 1,
+        turn_time,
+    };
+    loop {
+        // Wake up for the first turn to run out, if any
+        let deadline = lobby.rooms.values().filter_map(|room| room.deadline);
+        let event = match deadline.min() {
+            Some(deadline) => {
+                let timeout =
+                    deadline.saturating_duration_since(Instant::now());
+                incoming.recv_timeout(timeout)
+            }
+            None => incoming.recv().map_err(RecvTimeoutError::from),
+        };
+        match event {
+            Ok(Incoming::Joined(session, conn)) => {
+                lobby.sessions.insert(session, conn);
+            }
+            Ok(Incoming::Message(session, msg)) => lobby.handle(session, msg),
+            Ok(Incoming::Left(session)) => lobby.leave(session),
+            Err(RecvTimeoutError::Timeout) => lobby.expire_turns(),
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+    }
+}
+
+/// Accept connections, and read each on a thread of its own.
+fn accept(listener: TcpListener, events: Sender<Incoming>) {
+    for (session, stream) in (1..).zip(listener.incoming()) {
+        let Ok(mut conn) = stream.and_then(Connection::from_stream) else {
+            continue;
+        };
+        let Ok(writer) = conn.try_clone() else {
+            continue;
+        };
+        if events.send(Incoming::Joined(session, writer)).is_err() {
+            return;
+        }
+        let events = events.clone();
+        thread::spawn(move || {
+            while let Ok(msg) = conn.recv() {
+                if events.send(Incoming::Message(session, msg)).is_err() {
+                    return;
+                }
+            }
+            let _ = events.send(Incoming::Left(session));
+        });
+    }
+}
+
+impl Lobby {
+    fn handle(&mut self, session: SessionId, msg: Message) {
+        if let Some(&room) = self.seated.get(&session) {
+            match msg {
+                Message::Reveal(at) => self.reveal(session, room, at),
+                Message::Chat { text, .. } => self.chat(session, room, &text),
+                Message::Player { name, rating } => {
+                    self.introduce(session, room, name, rating)
+                }
+                _ => self.leave(session),
+            }
+            return;
+        }
+        if self.watching.contains_key(&session) {
+            // Spectators have nothing to say
+            return self.leave(session);
+        }
+        match msg {
+            Message::ListRooms => {
+                let rooms = self.rooms.iter().map(|(id, room)| RoomInfo {
+                    id: *id,
+                    size: room.size(),
+                    full: room.is_full(),
+                });
+                self.send(session, &Message::Rooms(rooms.collect()));
+            }
+            Message::CreateRoom(size) => self.create(session, size),
+            Message::JoinRoom(id) => self.join(session, id),
+            Message::Watch(id) => self.watch(session, id),
+            Message::FindMatch(size) => {
+                let waiting = self
+                    .rooms
+                    .iter()
+                    .find(|(_, room)| !room.is_full() && room.size() == size);
+                match waiting {
+                    Some((&id, _)) => self.join(session, id),
+                    None => self.create(session, size),
+                }
+            }
+            _ => self.leave(session),
+        }
+    }
+
+    fn create(&mut self, session: SessionId, size: Vec2) {
+        let Some(room) = Room::open(session, size, self.turn_time) else {
+            let refusal = Message::Refused(Refusal::InvalidSize);
+            return self.send(session, &refusal);
+        };
+        let id = self.next_room;
+        self.next_room += 1;
+        self.rooms.insert(id, room);
+        self.seated.insert(session, id);
+        let role = Role::Player(0);
+        self.send(session, &Message::Seated { room: id, role });
+    }
+
+    fn join(&mut self, session: SessionId, id: u32) {
+        let refusal = match self.rooms.get_mut(&id) {
+            None => Some(Refusal::NoSuchRoom),
+            Some(room) if room.is_full() => Some(Refusal::RoomFull),
+            Some(room) => {
+                room.seats.push(session);
+                room.start_turn();
+                None
+            }
+        };
+        if let Some(refusal) = refusal {
+            return self.send(session, &Message::Refused(refusal));
+        }
+        self.seated.insert(session, id);
+        let role = Role::Player(1);
+        self.send(session, &Message::Seated { room: id, role });
+        let Some(room) = self.rooms.get(&id) else {
+            return;
+        };
+        let commit = room.commit();
+        let (seats, spectators) = (room.seats.clone(), room.spectators.clone());
+        let state = room.state();
+        for seat in seats {
+            self.send(seat, &commit);
+        }
+        for spectator in spectators {
+            self.send(spectator, &state);
+        }
+    }
+
+    /// Let `session` watch the game of room `id`, from its current state if
+    /// it has started.
+    fn watch(&mut self, session: SessionId, id: u32) {
+        let Some(room) = self.rooms.get_mut(&id) else {
+            let refusal = Message::Refused(Refusal::NoSuchRoom);
+            return self.send(session, &refusal);
+        };
+        room.spectators.push(session);
+        let state = room.is_full().then(|| room.state());
+        self.watching.insert(session, id);
+        let role = Role::Spectator;
+        self.send(session, &Message::Seated { room: id, role });
+        if let Some(state) = state {
+            self.send(session, &state);
+        }
+    }
+
+    /// Pick a card for the player of `session` in room `id`, let both
+    /// players know its face, and show the spectators.
+    fn reveal(&mut self, session: SessionId, id: u32, at: Vec2) {
+        let Some(room) = self.rooms.get_mut(&id) else {
+            return;
+        };
+        let seat = room.seats.iter().position(|seat| *seat == session);
+        let Some(messages) = seat.and_then(|seat| room.reveal(seat, at)) else {
+            return self.leave(session);
+        };
+        let (seats, spectators) = (room.seats.clone(), room.spectators.clone());
+        let state = room.state();
+        let over = room.is_over();
+        for msg in &messages {
+            for seat in &seats {
+                self.send(*seat, msg);
+            }
+        }
+        for spectator in &spectators {
+            self.send(*spectator, &state);
+        }
+        if over {
+            // Everyone goes on to show the outcome, and leaves by themselves
+            self.rooms.remove(&id);
+            for session in seats.iter().chain(&spectators) {
+                self.seated.remove(session);
+                self.watching.remove(session);
+                self.sessions.remove(session);
+            }
+        }
+    }
+
+    /// Pass a line of chat from the player of `session` on to everyone else
+    /// in room `id`, unless nothing is left of it once cleaned up or they
+    /// are saying too much.
+    fn chat(&mut self, session: SessionId, id: u32, text: &str) {
+        let Some(room) = self.rooms.get_mut(&id).filter(|r| r.is_full()) else {
+            return;
+        };
+        let Some(seat) = room.seats.iter().position(|seat| *seat == session)
+        else {
+            return;
+        };
+        let Some(text) = chat::sanitize(text) else {
+            return;
+        };
+        if !room.chat[seat].allow(Instant::now()) {
+            return;
+        }
+        let others: Vec<_> = room
+            .seats
+            .iter()
+            .chain(&room.spectators)
+            .copied()
+            .filter(|other| *other != session)
+            .collect();
+        let msg = Message::Chat { seat, text };
+        for other in others {
+            self.send(other, &msg);
+        }
+    }
+
+    /// Pass the name and rating of the player of `session` on to the other
+    /// player of room `id`, the first time they introduce themselves.
+    fn introduce(
+        &mut self,
+        session: SessionId,
+        id: u32,
+        name: String,
+        rating: u32,
+    ) {
+        let Some(room) = self.rooms.get_mut(&id).filter(|r| r.is_full()) else {
+            return;
+        };
+        let Some(seat) = room.seats.iter().position(|seat| *seat == session)
+        else {
+            return;
+        };
+        if room.introduced[seat] {
+            return;
+        }
+        room.introduced[seat] = true;
+        let other = room.seats[1 - seat];
+        self.send(other, &Message::Player { name, rating });
+    }
+
+    /// Pass play on in every room whose current turn ran out of time, and
+    /// let everyone in it know.
+    fn expire_turns(&mut self) {
+        let now = Instant::now();
+        let expired: Vec<_> = self
+            .rooms
+            .iter_mut()
+            .filter(|(_, room)| room.deadline.is_some_and(|t| t <= now))
+            .map(|(_, room)| {
+                let msg = room.expire();
+                (
+                    room.seats.clone(),
+                    room.spectators.clone(),
+                    msg,
+                    room.state(),
+                )
+            })
+            .collect();
+        for (seats, spectators, msg, state) in expired {
+            for seat in seats {
+                self.send(seat, &msg);
+            }
+            for spectator in spectators {
+                self.send(spectator, &state);
+            }
+        }
+    }
+
+    /// Send `msg` to the player of `session`, dropping them if it fails.
+    fn send(&mut self, session: SessionId, msg: &Message) {
+        let Some(conn) = self.sessions.get_mut(&session) else {
+            return;
+        };
+        if conn.send(msg).is_err() {
+            self.leave(session);
+        }
+    }
+
+    /// Disconnect the player of `session`, along with everyone else in
+    /// their room if they were playing, as the game can't go on without
+    /// them.
+    fn leave(&mut self, session: SessionId) {
+        if let Some(conn) = self.sessions.remove(&session) {
+            conn.close();
+        }
+        if let Some(id) = self.watching.remove(&session) {
+            if let Some(room) = self.rooms.get_mut(&id) {
+                room.spectators.retain(|spectator| *spectator != session);
+            }
+            return;
+        }
+        let Some(id) = self.seated.remove(&session) else {
+            return;
+        };
+        if let Some(room) = self.rooms.remove(&id) {
+            for session in room.seats.into_iter().chain(room.spectators) {
+                self.leave(session);
+            }
+        }
+    }
+}
+
+/// A connection to a lobby, before taking a seat in a room.
+pub struct LobbyClient {
+    conn: Connection,
+}
+
+/// A place taken in a room of a lobby.
+pub struct Seat {
+    pub room: u32,
+    pub role: Role,
+    conn: Connection,
+}
+
+impl LobbyClient {
+    /// Connect to the lobby at `addr`.
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> io::Result<LobbyClient> {
+        Ok(LobbyClient {
+            conn: Connection::join(addr)?,
+        })
+    }
+
+    /// The rooms of the lobby, both those waiting for a second player and
+    /// those that can only be watched.
+    pub fn rooms(&mut self) -> io::Result<Vec<RoomInfo>> {
+        self.conn.send(&Message::ListRooms)?;
+        match self.conn.recv()? {
+            Message::Rooms(rooms) => Ok(rooms),
+            msg => Err(unexpected(&msg)),
+        }
+    }
+
+    /// Open a room with a board of `size`, and take its first seat.
+    pub fn create(self, size: Vec2) -> io::Result<Seat> {
+        self.take_seat(&Message::CreateRoom(size))
+    }
+
+    /// Take the second seat of room `room`.
+    pub fn join(self, room: u32) -> io::Result<Seat> {
+        self.take_seat(&Message::JoinRoom(room))
+    }
+
+    /// Take a seat in any room waiting for a player with a board of `size`,
+    /// or open one.
+    pub fn find_match(self, size: Vec2) -> io::Result<Seat> {
+        self.take_seat(&Message::FindMatch(size))
+    }
+
+    /// Watch the game of room `room`.
+    pub fn watch(self, room: u32) -> io::Result<Seat> {
+        self.take_seat(&Message::Watch(room))
+    }
+
+    fn take_seat(mut self, request: &Message) -> io::Result<Seat> {
+        self.conn.send(request)?;
+        match self.conn.recv()? {
+            Message::Seated { room, role } => Ok(Seat {
+                room,
+                role,
+                conn: self.conn,
+            }),
+            Message::Refused(refusal) => {
+                Err(io::Error::other(refusal_message(refusal)))
+            }
+            msg => Err(unexpected(&msg)),
+        }
+    }
+}
+
+impl Seat {
+    /// Play or watch the game of the room. Blocks until both seats are
+    /// taken.
+    pub fn game(self) -> io::Result<Game> {
+        match self.role {
+            Role::Player(seat) => Game::join_seat(self.conn, seat),
+            Role::Spectator => Game::spectate(self.conn),
+        }
+    }
+}
+
+/// The message shown for `refusal`.
+fn refusal_message(refusal: Refusal) -> String {
+    match refusal {
+        Refusal::NoSuchRoom => t!("lobby-no-such-room"),
+        Refusal::RoomFull => t!("lobby-room-full"),
+        Refusal::InvalidSize => t!("lobby-invalid-size"),
+    }
+}
+
+fn unexpected(msg: &Message) -> io::Error {
+    io::Error::new(
+        ErrorKind::InvalidData,
+        format!("unexpected message from the lobby: {:?}", msg),
+    )
+}
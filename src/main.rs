@@ -1,23 +1,1143 @@
-use std::process;
+use std::{
+    fs,
+    io::{self, IsTerminal, Write},
+    path::{Path, PathBuf},
+    process, thread,
+    time::{Duration, Instant},
+};
 
-mod game;
-use game::Game;
+use clap::Parser;
+use rs_card_matching::{
+    audio::Audio,
+    board::{Card, Idx2d, Vec2},
+    campaign::Campaign,
+    challenge::ChallengeResult,
+    chart::{self, Bar},
+    color::ColorDepth,
+    config::{CardSet, Config, Pacing},
+    daily::{Daily, DailyLog},
+    elo::RatingStore,
+    error::Result,
+    export::{Exporter, Format},
+    game::Game,
+    graphics::Graphics,
+    heatmap, i18n,
+    keymap::Keymap,
+    lobby::{self, LobbyClient},
+    net::{Connection, Role},
+    profile, protocol, rating,
+    replay::Replay,
+    save::SavedGame,
+    shape::Shape,
+    solver::Solver,
+    stats::{self, format_duration, StatsStore},
+    style::Style,
+    t,
+    terminal::{self, Capabilities},
+    theme::Theme,
+    tournament::Tournament,
+};
 
-mod error;
+mod cli;
+mod event_loop;
+mod serve;
+#[cfg(feature = "history")]
+use rs_card_matching::history::History;
+
+use cli::{Cli, Command, LobbyCommand, PlayArgs, SolveArgs, StatsCommand};
+use event_loop::{EventLoop, FRAME_TIME};
 
 fn main() {
+    let cli = Cli::parse();
+    i18n::set_locale(cli.lang.as_deref());
+    match &cli.profile {
+        Some(name) => {
+            if let Err(e) = profile::select(name) {
+                eprintln!("{}", e.as_string());
+                process::exit(2);
+            }
+        }
+        None if cli.command.is_none() => pick_profile(),
+        None => {}
+    }
+    let config = match &cli.config {
+        Some(path) => Config::load(path),
+        None => Config::load_default(),
+    };
+    let config = config.unwrap_or_else(|e| {
+        eprintln!("{}", e.as_string());
+        process::exit(2);
+    });
+
+    let caps = Capabilities::detect();
+    terminal::set_ansi(caps.ansi);
+    let output = Output {
+        mute: cli.mute || config.mute,
+        config_file: cli.config.clone().or_else(Config::default_path),
+        // Already checked when the configuration was loaded
+        keymap: config.keymap().unwrap_or_default(),
+        accessible: cli.accessible,
+        unicode: caps.unicode,
+        graphics: caps.graphics.filter(|_| !cli.no_images && !cli.accessible),
+        high_contrast: cli.high_contrast || config.high_contrast,
+        reduced_motion: cli.reduced_motion || config.reduced_motion,
+        compact: cli.compact,
+        cell_numbers: cli.cell_numbers || config.cell_numbers,
+        luck_meter: cli.luck_meter || config.luck_meter,
+        pacing: cli.pacing.unwrap_or(config.pacing),
+        theme: match (cli.theme, &config.theme) {
+            (Some(theme), _) => theme,
+            (None, Some(name)) => Theme::named(name).unwrap_or_default(),
+            (None, None) => Theme::DEFAULT,
+        },
+        colors: match cli.color {
+            _ if cli.no_color => None,
+            Some(mode) => mode.colors(caps.colors),
+            None => config.colors.colors(caps.colors),
+        },
+    };
+    match cli.command {
+        Some(Command::Play(args)) => play(args, &config, &output),
+        None => {
+            // Only for a player at the keyboard, who can leave it by
+            // quitting rather than having to clear it
+            if !tutorial_done()
+                && io::stdin().is_terminal()
+                && !tutorial(&output)
+            {
+                return;
+            }
+            play(PlayArgs::default(), &config, &output)
+        }
+        Some(Command::Replay { file }) => replay(&file, &output),
+        Some(Command::Export { file, out }) => {
+            export_cast(&file, &out, &output)
+        }
+        Some(Command::Daily) => daily(&output),
+        Some(Command::Tutorial) => {
+            tutorial(&output);
+        }
+        Some(Command::Resume) => resume(&output),
+        Some(Command::Stats {
+            action: Some(StatsCommand::Export { format, out }),
+            mode,
+            ..
+        }) => export_stats(format, &out, mode.as_deref()),
+        Some(Command::Stats { recent, mode, .. }) => {
+            stats(recent, mode.as_deref(), &output)
+        }
+        Some(Command::Solve(args)) => solve(args, &config),
+        Some(Command::Challenge { result }) => challenge(result, &output),
+        Some(Command::Tournament { seed }) => tournament(seed, &output),
+        Some(Command::Campaign) => campaign(&output),
+        Some(Command::Serve {
+            http: Some(port), ..
+        }) => serve_http(port, &config),
+        Some(Command::Serve {
+            lobby, turn_time, ..
+        }) => serve_lobby(
+            lobby.expect("either --http or --lobby is required"),
+            turn_time.map(Duration::from_secs),
+        ),
+        Some(Command::Lobby { addr, action }) => {
+            play_lobby(&addr, action, &output)
+        }
+    }
+}
+
+/// How the game is presented, shared by the interactive subcommands.
+struct Output {
+    mute: bool,
+    /// Where changes made in the settings menu are saved.
+    config_file: Option<PathBuf>,
+    keymap: Keymap,
+    accessible: bool,
+    unicode: bool,
+    graphics: Option<Graphics>,
+    theme: Theme,
+    high_contrast: bool,
+    reduced_motion: bool,
+    compact: bool,
+    cell_numbers: bool,
+    luck_meter: bool,
+    pacing: Pacing,
+    colors: Option<ColorDepth>,
+}
+
+impl Output {
+    fn apply(&self, game: &mut Game) {
+        game.subscribe(Audio::new(self.mute));
+        game.set_muted(self.mute);
+        game.set_config_file(self.config_file.clone());
+        game.set_save_file(SavedGame::default_path());
+        game.set_autosave_file(SavedGame::autosave_path());
+        self.apply_view(game);
+    }
+
+    /// Apply only how the game is shown, e.g. to play back a recording
+    /// without saving anything.
+    fn apply_view(&self, game: &mut Game) {
+        game.set_keymap(self.keymap.clone());
+        game.set_accessible(self.accessible);
+        game.set_unicode(self.unicode);
+        game.set_colors(self.colors);
+        game.set_theme(self.theme);
+        game.set_high_contrast(self.high_contrast);
+        game.set_reduced_motion(self.reduced_motion);
+        game.set_compact(self.compact);
+        game.set_cell_numbers(self.cell_numbers);
+        game.set_luck_meter(self.luck_meter);
+        game.set_pacing(self.pacing);
+        game.set_graphics(self.graphics);
+    }
+}
+
+/// Run an interactive game in the terminal.
+fn play(args: PlayArgs, config: &Config, output: &Output) {
+    if let Some(addr) = &args.join {
+        println!("{}", t!("join-waiting"));
+        let game = Connection::join(addr.as_str()).and_then(Game::join);
+        match game {
+            Ok(mut game) => {
+                if let Some(ratings) = open_ratings() {
+                    game.set_ratings(ratings);
+                }
+                output.apply(&mut game);
+                run(&mut game, output)
+            }
+            Err(e) => {
+                eprintln!("{}", t!("join-failed", addr = addr, reason = e));
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let seed = match &args.board_code {
+        Some(code) => code.seed,
+        None => args.seed,
+    };
+    let mut game = match seed {
+        Some(seed) => Game::with_seed(seed),
+        None => Game::new(),
+    };
+    game.set_replay_file(args.record.clone());
+    if let Some(penalty) = config.hint_penalty {
+        game.set_hint_penalty(penalty as i32);
+    }
+    game.set_difficulty(args.difficulty.unwrap_or(config.difficulty));
+    game.set_layout(args.layout.unwrap_or(config.layout));
+    game.set_layers(args.layers as usize);
+    let card_set = if args.ascii {
+        CardSet::Ascii
+    } else {
+        args.card_set.unwrap_or(config.card_set)
+    };
+    game.set_card_set(card_set);
+    game.set_jokers(args.jokers.or(config.jokers).unwrap_or(0) as usize);
+    game.set_bombs(
+        args.bombs.or(config.bombs).unwrap_or(0) as usize,
+        args.bomb_effect.unwrap_or(config.bomb_effect),
+    );
+    game.set_scoring(config.scoring);
+    if let Some(steps) = args.min_distance.or(config.min_pair_distance) {
+        game.set_min_pair_distance(steps);
+    }
+    game.set_lives(args.lives.or(config.lives));
+    game.set_shuffle_every(args.shuffle_every.or(config.shuffle_every));
+    game.set_decay(args.decay.or(config.decay));
+    let time_limit = args.time_limit.or(config.time_limit);
+    game.set_time_limit(time_limit.map(Duration::from_secs));
+    if let Some(secs) = args.preview.or(config.preview) {
+        game.set_preview(Duration::from_secs(secs));
+    }
+    if let Some(port) = args.host {
+        println!("{}", t!("host-waiting", port = port));
+        match Connection::host(port) {
+            Ok(conn) => {
+                game.host(conn);
+                if let Some(ratings) = open_ratings() {
+                    game.set_ratings(ratings);
+                }
+            }
+            Err(e) => {
+                eprintln!("{}", t!("host-failed", port = port, reason = e));
+                process::exit(1);
+            }
+        }
+    }
+    // A board left in play by a session that was interrupted is offered
+    // before dealing another one, unless a particular board was asked for
+    let interactive = !(args.json || args.autoplay || args.script.is_some());
+    let recovered = match SavedGame::autosave_path() {
+        Some(path)
+            if interactive
+                && args.host.is_none()
+                && args.board_code.is_none()
+                && args.shape.is_none() =>
+        {
+            SavedGame::load(&path).ok().flatten()
+        }
+        _ => None,
+    };
+    // Already validated while loading the configuration
+    let size = args.size.or(config.board_size().unwrap_or_default());
+    let started = match (&args.shape, size) {
+        _ if recovered.is_some() => None,
+        _ if args.board_code.is_some() => args
+            .board_code
+            .as_ref()
+            .map(|code| game.start_from_code(code)),
+        (Some(path), _) => Some(
+            Shape::load(path).and_then(|shape| game.start_with_shape(&shape)),
+        ),
+        (None, Some(size)) => Some(game.start(size)),
+        (None, None) => None,
+    };
+    if let Some(Err(e)) = started {
+        eprintln!("{}", e.as_string());
+        process::exit(2);
+    }
+    if !args.json {
+        output.apply(&mut game);
+    }
+    if let Some(saved) = recovered {
+        game.offer_recovery(saved);
+    }
+    if args.autoplay {
+        if started.is_none() {
+            eprintln!("{}", t!("size-required"));
+            process::exit(2);
+        }
+        let delay = args.delay.unwrap_or(500);
+        run_autoplay(game, Some(Duration::from_millis(delay)));
+    } else if let Some(script) = &args.script {
+        let delay = args.delay.map(Duration::from_millis);
+        process::exit(run_script(game, script, delay));
+    } else if args.json {
+        run_json(game);
+    } else {
+        // Only solo games count towards the statistics
+        if args.host.is_none() {
+            if let Some(stats) = open_stats() {
+                game.set_stats(stats);
+            }
+        }
+        run(&mut game, output);
+    }
+}
+
+/// Play today's challenge board.
+fn daily(output: &Output) {
+    let daily = Daily::today();
+    let mut game = Game::with_seed(daily.seed);
+    if let Err(e) = game.start(daily.size) {
+        eprintln!("{}", e.as_string());
+        process::exit(2);
+    }
+
+    let log = DailyLog::default_path().map(|path| DailyLog::open(&path));
+    match log {
+        Some(Ok(log)) => game.set_daily(daily, log),
+        Some(Err(e)) => {
+            eprintln!("{}", e.as_string());
+            process::exit(1);
+        }
+        None => {
+            eprintln!("{}", t!("daily-no-log"));
+            process::exit(1);
+        }
+    }
+    if let Some(stats) = open_stats() {
+        game.set_stats(stats);
+    }
+    output.apply(&mut game);
+    run(&mut game, output);
+}
+
+/// Continue the saved game. It is deleted once loaded, so that it can only
+/// be continued once.
+fn resume(output: &Output) {
+    let Some(path) = SavedGame::default_path() else {
+        eprintln!("{}", t!("no-saved-game"));
+        process::exit(1);
+    };
+    let saved = match SavedGame::load(&path) {
+        Ok(Some(saved)) => saved,
+        Ok(None) => {
+            eprintln!("{}", t!("no-saved-game"));
+            process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("{}", e.as_string());
+            process::exit(1);
+        }
+    };
     let mut game = Game::new();
-    game.render();
+    if let Err(e) = game.resume(&saved) {
+        eprintln!("{}", e.as_string());
+        process::exit(2);
+    }
+    if let Err(e) = SavedGame::remove(&path) {
+        eprintln!("{}", e.as_string());
+    }
+    if let Some(stats) = open_stats() {
+        game.set_stats(stats);
+    }
+    output.apply(&mut game);
+    run(&mut game, output);
+}
 
-    while game.is_running() {
-        match game.grab_input() {
-            Err(_) => {
-                println!("Couldn't get input");
+/// Walk the player through the tutorial board. Once it has been cleared, it
+/// is no longer shown before the first game. Returns whether it was
+/// cleared, rather than quit.
+fn tutorial(output: &Output) -> bool {
+    let mut game = Game::new();
+    if let Err(e) = game.start_tutorial() {
+        eprintln!("{}", e.as_string());
+        process::exit(2);
+    }
+    output.apply(&mut game);
+    run(&mut game, output);
+    if !game.is_won() {
+        return false;
+    }
+    // Failing to remember it only means the tutorial is shown again
+    if let Some(path) = tutorial_marker() {
+        let _ = path.parent().map(fs::create_dir_all);
+        let _ = fs::write(path, "");
+    }
+    true
+}
+
+/// Let the player pick which profile to play as, or name a new one, if
+/// there are any profiles to pick from.
+fn pick_profile() {
+    let profiles = profile::list();
+    if profiles.is_empty() || !io::stdin().is_terminal() {
+        return;
+    }
+    println!("{}", t!("profile-menu"));
+    for (i, name) in profiles.iter().enumerate() {
+        println!("  {}. {}", i + 1, name);
+    }
+    loop {
+        print!("{} ", t!("profile-prompt", max = profiles.len()));
+        let _ = io::stdout().flush();
+        let mut line = String::new();
+        if matches!(io::stdin().read_line(&mut line), Ok(0) | Err(_)) {
+            return;
+        }
+        let input = line.trim();
+        let name = match input.parse::<usize>() {
+            _ if input.is_empty() => return,
+            Ok(i) if (1..=profiles.len()).contains(&i) => &profiles[i - 1],
+            _ => input,
+        };
+        match profile::select(name) {
+            Ok(()) => return,
+            Err(e) => println!("{}", e.as_string()),
+        }
+    }
+}
+
+/// File whose existence records that the tutorial has been played.
+fn tutorial_marker() -> Option<PathBuf> {
+    Some(stats::data_dir()?.join("tutorial-done"))
+}
+
+/// Check if the tutorial has been cleared, or can't be remembered anyway.
+fn tutorial_done() -> bool {
+    tutorial_marker().is_none_or(|path| path.exists())
+}
+
+/// Play the campaign from the level select screen.
+fn campaign(output: &Output) {
+    let campaign = Campaign::default_path().map(|path| Campaign::open(&path));
+    let mut game = Game::new();
+    let started = match campaign {
+        Some(campaign) => {
+            campaign.and_then(|campaign| game.start_campaign(campaign))
+        }
+        None => {
+            eprintln!("{}", t!("campaign-no-progress"));
+            process::exit(1);
+        }
+    };
+    if let Err(e) = started {
+        eprintln!("{}", e.as_string());
+        process::exit(1);
+    }
+    if let Some(stats) = open_stats() {
+        game.set_stats(stats);
+    }
+    output.apply(&mut game);
+    run(&mut game, output);
+}
+
+/// Play the rounds of a tournament one after the other.
+fn tournament(seed: Option<u64>, output: &Output) {
+    let mut game = match seed {
+        Some(seed) => Game::with_seed(seed),
+        None => Game::new(),
+    };
+    if let Err(e) = game.start_tournament(Tournament::default()) {
+        eprintln!("{}", e.as_string());
+        process::exit(2);
+    }
+    if let Some(stats) = open_stats() {
+        game.set_stats(stats);
+    }
+    output.apply(&mut game);
+    run(&mut game, output);
+}
+
+/// Play the board of a friend's result and compare the two results.
+fn challenge(result: ChallengeResult, output: &Output) {
+    let seed = result.board_code().ok().and_then(|code| code.seed);
+    let mut game = match seed {
+        Some(seed) => Game::with_seed(seed),
+        None => Game::new(),
+    };
+    if let Err(e) = game.challenge(result) {
+        eprintln!("{}", e.as_string());
+        process::exit(2);
+    }
+    if let Some(stats) = open_stats() {
+        game.set_stats(stats);
+    }
+    output.apply(&mut game);
+    run(&mut game, output);
+}
+
+/// Open the statistics file, warning about any problems instead of failing.
+fn open_stats() -> Option<StatsStore> {
+    let path = StatsStore::default_path()?;
+    #[allow(unused_mut)]
+    let mut store = match StatsStore::open(&path) {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("{}", e.as_string());
+            return None;
+        }
+    };
+    #[cfg(feature = "history")]
+    match History::default_path().map(|path| History::open(&path)) {
+        Some(Ok(history)) => store.set_history(history),
+        Some(Err(e)) => eprintln!("{}", e.as_string()),
+        None => {}
+    }
+    Some(store)
+}
+
+/// Open the ratings file, warning about any problems instead of failing.
+fn open_ratings() -> Option<RatingStore> {
+    let path = RatingStore::default_path()?;
+    match RatingStore::open(&path) {
+        Ok(store) => Some(store),
+        Err(e) => {
+            eprintln!("{}", e.as_string());
+            None
+        }
+    }
+}
+
+/// Number of columns the charts of the statistics take up at most.
+const CHART_WIDTH: usize = 40;
+
+/// Number of rows of the line charts of the statistics.
+#[cfg(feature = "history")]
+const CHART_HEIGHT: usize = 8;
+
+/// Print the statistics of all finished games, followed by the history of
+/// the last `recent` games of `mode`, or of all modes.
+fn stats(recent: Option<usize>, mode: Option<&str>, output: &Output) {
+    let store = open_stats().unwrap_or_else(|| {
+        eprintln!("{}", t!("stats-unavailable"));
+        process::exit(1);
+    });
+    let stats = &store.stats;
+
+    println!("{}", t!("games-played", count = stats.games_played));
+    match stats.win_rate() {
+        Some(rate) => {
+            let percent = format!("{:.0}", rate * 100.0);
+            println!("{}", t!("win-rate", percent = percent));
+        }
+        None => println!("{}", t!("win-rate-none")),
+    }
+    match stats.guesses_per_pair() {
+        Some(avg) => {
+            let average = format!("{:.2}", avg);
+            println!("{}", t!("guesses-per-pair", average = average));
+        }
+        None => println!("{}", t!("guesses-per-pair-none")),
+    }
+    if let Some(store) = open_ratings() {
+        let ratings = &store.ratings;
+        println!(
+            "{}",
+            t!("elo-rating", rating = ratings.rating.round() as i64)
+        );
+        for (name, record) in &ratings.opponents {
+            let name = match name.as_str() {
+                "" => t!("opponent-unnamed"),
+                name => name.to_owned(),
+            };
+            println!(
+                "  {}",
+                t!(
+                    "elo-opponent",
+                    name = name,
+                    wins = record.wins,
+                    draws = record.draws,
+                    losses = record.losses,
+                    rating = record.rating.round() as i64
+                )
+            );
+        }
+    }
+    if !stats.best_times.is_empty() {
+        println!("{}", t!("best-times"));
+        let bars: Vec<Bar> = stats
+            .best_times
+            .iter()
+            .map(|(size, millis)| {
+                let time = format_duration(Duration::from_millis(*millis));
+                let stars = stats.best_stars.get(size).copied().unwrap_or(0);
+                Bar {
+                    label: size.clone(),
+                    value: *millis as f64,
+                    text: format!(
+                        "{}  {}",
+                        time,
+                        rating::display(stars, output.unicode)
+                    ),
+                }
+            })
+            .collect();
+        for line in chart::bars(&bars, CHART_WIDTH, output.unicode) {
+            println!("  {}", line);
+        }
+    }
+    let style = Style::new(output.colors);
+    for (size, counts) in stats.heatmaps() {
+        let title = format!("{}x{}", size.x, size.y);
+        println!("\n{}", t!("heatmap-title", size = title));
+        for line in heatmap::render(size, counts, style, output.unicode) {
+            println!("{}", line);
+        }
+    }
+    print_history(&store, recent, mode, output);
+}
+
+/// Print the games of each mode taken together, and the last `recent`
+/// games, only of `mode` if given.
+#[cfg(feature = "history")]
+fn print_history(
+    store: &StatsStore,
+    recent: Option<usize>,
+    mode: Option<&str>,
+    output: &Output,
+) {
+    let Some(history) = store.history() else {
+        return;
+    };
+    let (current, longest) = match history.win_streaks(mode) {
+        Ok(streaks) => streaks,
+        Err(e) => {
+            eprintln!("{}", e.as_string());
+            return;
+        }
+    };
+    println!(
+        "\n{}",
+        t!("history-streaks", current = current, longest = longest)
+    );
+    let wins = match history.recent_wins(mode, CHART_WIDTH) {
+        Ok(wins) => wins,
+        Err(e) => {
+            eprintln!("{}", e.as_string());
+            return;
+        }
+    };
+    // Oldest first
+    let trend: Vec<f64> = wins
+        .iter()
+        .rev()
+        .map(|game| game.guesses as f64 / game.pairs as f64)
+        .collect();
+    if trend.len() > 1 {
+        println!("\n{}", t!("history-trend", count = trend.len()));
+        for line in chart::line(&trend, CHART_HEIGHT, output.unicode) {
+            println!("  {}", line);
+        }
+    }
+
+    let summaries = match history.summaries(mode) {
+        Ok(summaries) => summaries,
+        Err(e) => {
+            eprintln!("{}", e.as_string());
+            return;
+        }
+    };
+    if !summaries.is_empty() {
+        println!("\n{}", t!("history-modes"));
+    }
+    for summary in summaries {
+        let average = summary
+            .average_guesses
+            .map_or("-".to_owned(), |avg| format!("{:.1}", avg));
+        let best = summary.best_time.map_or("-".to_owned(), format_duration);
+        println!(
+            "  {}",
+            t!(
+                "history-mode",
+                mode = summary.mode,
+                played = summary.played,
+                won = summary.won,
+                average = average,
+                best = best,
+            )
+        );
+    }
+
+    let Some(limit) = recent else {
+        return;
+    };
+    let entries = match history.recent(mode, limit) {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("{}", e.as_string());
+            return;
+        }
+    };
+    println!("\n{}", t!("history-recent", count = entries.len()));
+    for entry in entries {
+        let size = format!("{}x{}", entry.size.x, entry.size.y);
+        let seed = entry.seed.map_or("-".to_owned(), |seed| seed.to_string());
+        let outcome = if entry.won {
+            t!("history-won")
+        } else {
+            t!("history-lost")
+        };
+        println!(
+            "  {}",
+            t!(
+                "history-entry",
+                date = entry.date(),
+                mode = entry.mode,
+                size = size,
+                seed = seed,
+                outcome = outcome,
+                guesses = entry.guesses,
+                time = format_duration(entry.duration),
+            )
+        );
+    }
+}
+
+/// Write the statistics and the games of `mode`, or of all modes, to `out`.
+fn export_stats(format: Format, out: &Path, mode: Option<&str>) {
+    let store = open_stats().unwrap_or_else(|| {
+        eprintln!("{}", t!("stats-unavailable"));
+        process::exit(1);
+    });
+    let result =
+        Exporter::create(out, format, &store.stats).and_then(|mut exporter| {
+            export_history(&store, mode, &mut exporter)?;
+            exporter.finish()
+        });
+    match result {
+        Ok(count) => {
+            let path = out.display().to_string();
+            println!("{}", t!("export-done", count = count, path = path));
+        }
+        Err(e) => {
+            eprintln!("{}", e.as_string());
+            process::exit(1);
+        }
+    }
+}
+
+/// Write the games of `mode`, or of all modes, from the history.
+#[cfg(feature = "history")]
+fn export_history(
+    store: &StatsStore,
+    mode: Option<&str>,
+    exporter: &mut Exporter,
+) -> Result<()> {
+    match store.history() {
+        Some(history) => {
+            history.each(mode, |entry| exporter.game(&entry.into()))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Without the `history` feature, only the aggregated statistics are
+/// exported.
+#[cfg(not(feature = "history"))]
+fn export_history(
+    _: &StatsStore,
+    _: Option<&str>,
+    _: &mut Exporter,
+) -> Result<()> {
+    eprintln!("{}", t!("history-unavailable"));
+    Ok(())
+}
+
+/// Without the `history` feature, there is only the aggregated statistics.
+#[cfg(not(feature = "history"))]
+fn print_history(
+    _: &StatsStore,
+    recent: Option<usize>,
+    mode: Option<&str>,
+    _: &Output,
+) {
+    if recent.is_some() || mode.is_some() {
+        eprintln!("{}", t!("history-unavailable"));
+    }
+}
+
+/// Let the solver play a board without rendering it, and report the result.
+fn solve(args: SolveArgs, config: &Config) {
+    let size = args.size.or(config.board_size().unwrap_or_default());
+    let size = size.unwrap_or_else(|| {
+        eprintln!("{}", t!("size-required"));
+        process::exit(2);
+    });
+    let mut game = match args.seed {
+        Some(seed) => Game::with_seed(seed),
+        None => Game::new(),
+    };
+    if let Err(e) = game.start(size) {
+        eprintln!("{}", e.as_string());
+        process::exit(2);
+    }
+
+    let guesses = run_autoplay(game, None);
+    let size = format!("{}x{}", size.x, size.y);
+    println!("{}", t!("solved", size = size, guesses = guesses));
+}
+
+/// Drive the game until the player exits, then restore the terminal.
+fn run(game: &mut Game, output: &Output) {
+    // A screen reader follows the output as it is printed
+    if !output.accessible {
+        terminal::enter_alternate_screen();
+    }
+    let result = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .and_then(|runtime| runtime.block_on(EventLoop::new().run(game)));
+    terminal::restore();
+    if let Err(e) = result {
+        eprintln!("{}", t!("input-failed", reason = e));
+        process::exit(1);
+    }
+}
+
+/// Render the intermediate frames of any running animation.
+fn animate(game: &mut Game) {
+    while game.is_animating() {
+        game.render();
+        thread::sleep(FRAME_TIME);
+        game.tick();
+    }
+}
+
+/// Drive the game with JSON commands until it exits or stdin is closed.
+fn run_json(mut game: Game) {
+    println!("{}", game.snapshot().to_line());
+
+    for line in io::stdin().lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                eprintln!("{}", t!("input-failed", reason = e));
                 process::exit(1);
-            },
-            _ => {}
+            }
+        };
+        println!("{}", protocol::respond(&mut game, &line).to_line());
+        if !game.is_running() {
+            break;
+        }
+    }
+}
+
+/// Serve the page to play in a browser on `port`, with a game following
+/// the configuration for each page opened.
+fn serve_http(port: u16, config: &Config) {
+    let new_game = || {
+        let mut game = Game::new();
+        if let Some(penalty) = config.hint_penalty {
+            game.set_hint_penalty(penalty as i32);
+        }
+        game.set_difficulty(config.difficulty);
+        game.set_layout(config.layout);
+        game.set_card_set(config.card_set);
+        game.set_scoring(config.scoring);
+        game.set_time_limit(config.time_limit.map(Duration::from_secs));
+        if let Some(secs) = config.preview {
+            game.set_preview(Duration::from_secs(secs));
+        }
+        game
+    };
+    println!("{}", t!("serve-listening", port = port));
+    if let Err(e) = serve::serve(port, new_game) {
+        eprintln!("{}", t!("serve-failed", port = port, reason = e));
+        process::exit(1);
+    }
+}
+
+/// Run a lobby on `port` until the process is stopped, with turns limited
+/// to `turn_time`, if set.
+fn serve_lobby(port: u16, turn_time: Option<Duration>) {
+    println!("{}", t!("lobby-listening", port = port));
+    if let Err(e) = lobby::serve(port, turn_time) {
+        eprintln!("{}", t!("serve-failed", port = port, reason = e));
+        process::exit(1);
+    }
+}
+
+/// List the rooms of the lobby at `addr`, or take a seat in one and play
+/// or watch the game of the room.
+fn play_lobby(addr: &str, action: LobbyCommand, output: &Output) {
+    let fail = |e: io::Error| -> ! {
+        eprintln!("{}", t!("join-failed", addr = addr, reason = e));
+        process::exit(1);
+    };
+    let client = LobbyClient::connect(addr).unwrap_or_else(|e| fail(e));
+    let seat = match action {
+        LobbyCommand::List => {
+            let mut client = client;
+            let rooms = client.rooms().unwrap_or_else(|e| fail(e));
+            if rooms.is_empty() {
+                println!("{}", t!("lobby-no-rooms"));
+            }
+            for room in rooms {
+                let size = format!("{}x{}", room.size.x, room.size.y);
+                let id = room.id;
+                if room.full {
+                    println!(
+                        "{}",
+                        t!("lobby-room-playing", room = id, size = size)
+                    );
+                } else {
+                    println!("{}", t!("lobby-room", room = id, size = size));
+                }
+            }
+            return;
+        }
+        LobbyCommand::Create { size } => client.create(size),
+        LobbyCommand::Join { room } => client.join(room),
+        LobbyCommand::Match { size } => client.find_match(size),
+        LobbyCommand::Watch { room } => client.watch(room),
+    };
+    let seat = seat.unwrap_or_else(|e| fail(e));
+    match seat.role {
+        Role::Player(_) => println!("{}", t!("lobby-seated", room = seat.room)),
+        Role::Spectator => {
+            println!("{}", t!("lobby-watching", room = seat.room))
+        }
+    }
+    let playing = matches!(seat.role, Role::Player(_));
+    let mut game = seat.game().unwrap_or_else(|e| fail(e));
+    if playing {
+        if let Some(ratings) = open_ratings() {
+            game.set_ratings(ratings);
+        }
+    }
+    output.apply(&mut game);
+    run(&mut game, output);
+}
+
+/// Feed the lines of the file at `path` to the game as inputs, rendering
+/// every step with a pause after it if a `delay` is given. Returns the exit
+/// status: 0 if the board was cleared, 3 if it wasn't.
+fn run_script(mut game: Game, path: &Path, delay: Option<Duration>) -> i32 {
+    let script = fs::read_to_string(path).unwrap_or_else(|e| {
+        let path = path.display();
+        eprintln!("{}", t!("script-failed", path = path, reason = e));
+        process::exit(1);
+    });
+
+    game.render();
+    let inputs = script.lines().filter(|line| !line.starts_with('#'));
+    for input in inputs {
+        if !game.is_running() {
+            break;
+        }
+        // A batch of guesses is applied one step at a time
+        let mut next = Some(input.to_owned());
+        while let Some(input) = next {
+            if let Some(delay) = delay {
+                thread::sleep(delay);
+            }
+            game.set_input(input);
+            game.update();
+            if delay.is_some() {
+                animate(&mut game);
+            }
+            game.render();
+            next = game.next_queued_input();
+        }
+    }
+
+    if game.snapshot().state == "victory" {
+        0
+    } else {
+        3
+    }
+}
+
+/// Let a perfect-memory [`Solver`] play until the board is cleared, and
+/// return the number of guesses it needed. The game is rendered with a pause
+/// after each step if a `delay` is given.
+fn run_autoplay(mut game: Game, delay: Option<Duration>) -> i32 {
+    let snapshot = game.snapshot();
+    let idx = Idx2d::new(snapshot.size[0], snapshot.size[1]);
+    let holes: Vec<Vec2> = snapshot
+        .holes
+        .iter()
+        .map(|[x, y]| Vec2 { x: x - 1, y: y - 1 })
+        .collect();
+    let cells = idx.iter_all().filter(|c| !holes.contains(c)).collect();
+    let mut solver = Solver::with_cells(cells);
+    let mut layers = snapshot.layers;
+
+    loop {
+        if let Some(delay) = delay {
+            game.render();
+            thread::sleep(delay);
         }
+
+        let snapshot = game.snapshot();
+        if snapshot.reshuffled {
+            let face_down: Vec<Vec2> = idx
+                .iter_all()
+                .filter(|c| {
+                    snapshot.board[c.y as usize][c.x as usize].is_none()
+                        && !holes.contains(c)
+                })
+                .collect();
+            solver.forget(&face_down);
+        }
+        // Faded matches have to be found again
+        for [x, y] in &snapshot.decayed {
+            solver.uncover(Vec2 { x: x - 1, y: y - 1 });
+        }
+        // Matched cards on a stack expose new ones
+        for c in idx.iter_all() {
+            let (x, y) = (c.x as usize, c.y as usize);
+            if snapshot.layers[y][x] != layers[y][x] {
+                solver.uncover(c);
+            }
+        }
+        layers = snapshot.layers.clone();
+        let revealed: Vec<Vec2> = snapshot
+            .revealed
+            .iter()
+            .map(|[x, y]| Vec2 { x: x - 1, y: y - 1 })
+            .collect();
+        let card_at = |c: Vec2| -> Option<Card> {
+            let card = snapshot.board[c.y as usize][c.x as usize].as_ref()?;
+            Some(card.parse().expect("snapshots show cards as displayed"))
+        };
+        for c in &revealed {
+            if let Some(card) = card_at(*c) {
+                solver.observe(*c, card);
+            }
+        }
+
+        let input = match snapshot.state {
+            "guess" => {
+                let c = solver
+                    .next_move(revealed.first().copied())
+                    .expect("an unmatched card is always left while guessing");
+                format!("{},{}", c.x + 1, c.y + 1)
+            }
+            "correct_guess" => {
+                solver.remove_pair(revealed[0], revealed[1]);
+                String::new()
+            }
+            "bomb_exploded" => {
+                for c in &revealed {
+                    if card_at(*c) == Some(Card::BOMB) {
+                        solver.remove(*c);
+                    }
+                }
+                String::new()
+            }
+            "victory" | "exit" => return snapshot.guesses,
+            _ => String::new(),
+        };
+        game.set_input(input);
         game.update();
-        game.render();
+        if delay.is_some() {
+            animate(&mut game);
+        }
+    }
+}
+
+/// Load the recording at `file` and the board it was made on, exiting if
+/// either can't be read.
+fn load_replay(file: &Path) -> (Replay, Game) {
+    let loaded = Replay::load(file)
+        .and_then(|replay| replay.game().map(|game| (replay, game)));
+    loaded.unwrap_or_else(|e| {
+        eprintln!("{}", e.as_string());
+        process::exit(2);
+    })
+}
+
+/// Play back the moves recorded at `file` as they were made.
+fn replay(file: &Path, output: &Output) {
+    let (replay, mut game) = load_replay(file);
+    output.apply_view(&mut game);
+    if !output.accessible {
+        terminal::enter_alternate_screen();
+    }
+    let started = Instant::now();
+    replay.play(&mut game, |at, game| {
+        // Keep any animation going until the move is due
+        while let Some(wait) =
+            (started + at).checked_duration_since(Instant::now())
+        {
+            if !game.is_animating() {
+                thread::sleep(wait);
+                break;
+            }
+            game.render();
+            thread::sleep(FRAME_TIME.min(wait));
+            game.tick();
+        }
+    });
+    animate(&mut game);
+    thread::sleep(REPLAY_END_PAUSE);
+    terminal::restore();
+}
+
+/// How long the last frame of a replay stays on screen.
+const REPLAY_END_PAUSE: Duration = Duration::from_secs(2);
+
+/// Turn the moves recorded at `file` into an asciinema cast file at `out`.
+fn export_cast(file: &Path, out: &Path, output: &Output) {
+    let (replay, mut game) = load_replay(file);
+    output.apply_view(&mut game);
+    // Every frame is drawn whole, without images or animations
+    game.set_accessible(false);
+    game.set_graphics(None);
+    game.set_reduced_motion(true);
+    game.capture_frames();
+    let result = fs::File::create(out).and_then(|file| {
+        replay.write_cast(&mut game, io::BufWriter::new(file))
+    });
+    if let Err(e) = result {
+        let path = out.display().to_string();
+        eprintln!("{}", t!("cast-failed", path = path, reason = e));
+        process::exit(1);
     }
+    println!("{}", t!("cast-done", path = out.display().to_string()));
 }
@@ -0,0 +1,592 @@
+use std::{
+    io::{self, Read, Write},
+    net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs},
+};
+
+use crate::board::{Idx2d, Vec2};
+use crate::fair::{Hash, Salt};
+
+/// Largest frame we are willing to receive, to avoid allocating huge
+/// buffers on garbage input.
+const MAX_FRAME_LEN: usize = 1 << 20;
+
+/// A message exchanged between the two players of a networked game, or
+/// between a player and a [lobby](`crate::lobby`).
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Message {
+    /// The shape of the board chosen by the host and the
+    /// [commitment](`crate::fair`) to its cards, sent once before the first
+    /// turn. Set for the holes in its shape.
+    Commit {
+        size: Vec2,
+        holes: Vec<bool>,
+        hash: Hash,
+    },
+    /// A card picked by the joining player, whose face the host answers
+    /// with.
+    Reveal(Vec2),
+    /// The face of a card revealed by either player, sent by the host.
+    Revealed { at: Vec2, card: u32 },
+    /// The salt and the board committed to, sent by the host once the last
+    /// pair is found. Holes are `None`.
+    Open { salt: Salt, cards: Vec<Option<u32>> },
+    /// Ask the lobby for its rooms.
+    ListRooms,
+    /// The rooms of the lobby.
+    Rooms(Vec<RoomInfo>),
+    /// Open a room with a board of the given size, and wait in it for a
+    /// second player.
+    CreateRoom(Vec2),
+    /// Take the second seat of a room.
+    JoinRoom(u32),
+    /// Take the second seat of any room with a board of the given size, or
+    /// open one if there is none.
+    FindMatch(Vec2),
+    /// Watch the game of a room without taking part in it.
+    Watch(u32),
+    /// The room the player was let into, and in what role. Once both seats
+    /// are taken, the lobby sends the players the [`Message::Commit`], and
+    /// the spectators the [`Message::State`] of the room.
+    Seated { room: u32, role: Role },
+    /// A request the lobby can't meet.
+    Refused(Refusal),
+    /// The whole state of a room, sent to its spectators whenever it
+    /// changes.
+    State(RoomState),
+    /// A line of [chat](`crate::chat`) from the player in `seat`, which a
+    /// lobby passes on to everyone else in the room.
+    Chat { seat: usize, text: String },
+    /// The player in `seat` took too long to pick the cards of their turn,
+    /// which a lobby passes to the other player. A card picked in the turn
+    /// is turned back over.
+    TurnExpired { seat: usize },
+    /// The name of a player, if they play as a [profile](`crate::profile`),
+    /// and their [rating](`crate::elo`), which each player sends once the
+    /// board is dealt and a lobby passes on to the other player.
+    Player { name: String, rating: u32 },
+}
+
+/// A room of a lobby, as listed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RoomInfo {
+    pub id: u32,
+    /// The size of the board.
+    pub size: Vec2,
+    /// Set once both seats are taken, and the room can only be watched.
+    pub full: bool,
+}
+
+/// What a player does in a room of a lobby.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Role {
+    /// Picks cards from the given seat. Seat 0 takes the first turn.
+    Player(usize),
+    /// Only watches.
+    Spectator,
+}
+
+impl Role {
+    /// Sent in place of a seat for spectators.
+    const SPECTATOR: u32 = u32::MAX;
+}
+
+/// The state of the game of a room, as shown to its spectators.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct RoomState {
+    pub size: Vec2,
+    /// Every cell of the board in row major order.
+    pub cells: Vec<Cell>,
+    /// The seat whose turn it is.
+    pub turn: usize,
+    /// Number of pairs found by each seat.
+    pub pairs: [u32; 2],
+}
+
+/// A cell of the board, as shown to spectators.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Cell {
+    Hole,
+    FaceDown,
+    /// Picked in the current turn, or the one before it if no card was
+    /// picked since.
+    Revealed(u32),
+    /// Part of a pair found.
+    Found(u32),
+}
+
+impl Cell {
+    /// The kind of cell and the card on it, as sent.
+    fn words(self) -> [u32; 2] {
+        match self {
+            Cell::Hole => [0, 0],
+            Cell::FaceDown => [1, 0],
+            Cell::Revealed(card) => [2, card],
+            Cell::Found(card) => [3, card],
+        }
+    }
+
+    fn from_words(kind: u32, card: u32) -> Option<Cell> {
+        match kind {
+            0 => Some(Cell::Hole),
+            1 => Some(Cell::FaceDown),
+            2 => Some(Cell::Revealed(card)),
+            3 => Some(Cell::Found(card)),
+            _ => None,
+        }
+    }
+}
+
+/// Why the lobby refused a request.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Refusal {
+    /// There is no room with that number.
+    NoSuchRoom,
+    /// Both seats of the room are taken.
+    RoomFull,
+    /// A board of that size can't be dealt.
+    InvalidSize,
+}
+
+impl Refusal {
+    const CODES: [Refusal; 3] =
+        [Refusal::NoSuchRoom, Refusal::RoomFull, Refusal::InvalidSize];
+
+    fn code(self) -> u32 {
+        self as u32
+    }
+
+    fn from_code(code: u32) -> Option<Refusal> {
+        Refusal::CODES.get(code as usize).copied()
+    }
+}
+
+impl Message {
+    const TAG_COMMIT: u8 = 0;
+    const TAG_REVEAL: u8 = 1;
+    const TAG_REVEALED: u8 = 2;
+    const TAG_OPEN: u8 = 3;
+    const TAG_LIST_ROOMS: u8 = 4;
+    const TAG_ROOMS: u8 = 5;
+    const TAG_CREATE_ROOM: u8 = 6;
+    const TAG_JOIN_ROOM: u8 = 7;
+    const TAG_FIND_MATCH: u8 = 8;
+    const TAG_SEATED: u8 = 9;
+    const TAG_REFUSED: u8 = 10;
+    const TAG_WATCH: u8 = 11;
+    const TAG_STATE: u8 = 12;
+    const TAG_CHAT: u8 = 13;
+    const TAG_TURN_EXPIRED: u8 = 14;
+    const TAG_PLAYER: u8 = 15;
+    /// Sent in place of a card for holes in the board.
+    const HOLE: u32 = u32::MAX;
+    /// Number of words taken up by a hash or a salt.
+    const DIGEST_WORDS: usize = 8;
+
+    /// Serialize the message into a frame payload.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        let mut words = |words: &[u32]| {
+            for word in words {
+                buf.extend_from_slice(&word.to_be_bytes());
+            }
+        };
+        let tag = match self {
+            Message::Commit { size, holes, hash } => {
+                words(&[size.x as u32, size.y as u32]);
+                let holes: Vec<u32> = holes.iter().map(|h| *h as u32).collect();
+                words(&holes);
+                words(&digest_words(hash));
+                Message::TAG_COMMIT
+            }
+            Message::Reveal(c) => {
+                words(&[c.x as u32, c.y as u32]);
+                Message::TAG_REVEAL
+            }
+            Message::Revealed { at, card } => {
+                words(&[at.x as u32, at.y as u32, *card]);
+                Message::TAG_REVEALED
+            }
+            Message::Open { salt, cards } => {
+                words(&digest_words(salt));
+                let cards: Vec<u32> =
+                    cards.iter().map(|c| c.unwrap_or(Message::HOLE)).collect();
+                words(&cards);
+                Message::TAG_OPEN
+            }
+            Message::ListRooms => Message::TAG_LIST_ROOMS,
+            Message::Rooms(rooms) => {
+                for room in rooms {
+                    let RoomInfo { id, size, full } = room;
+                    words(&[*id, size.x as u32, size.y as u32, *full as u32]);
+                }
+                Message::TAG_ROOMS
+            }
+            Message::CreateRoom(size) => {
+                words(&[size.x as u32, size.y as u32]);
+                Message::TAG_CREATE_ROOM
+            }
+            Message::JoinRoom(id) => {
+                words(&[*id]);
+                Message::TAG_JOIN_ROOM
+            }
+            Message::FindMatch(size) => {
+                words(&[size.x as u32, size.y as u32]);
+                Message::TAG_FIND_MATCH
+            }
+            Message::Watch(id) => {
+                words(&[*id]);
+                Message::TAG_WATCH
+            }
+            Message::Seated { room, role } => {
+                let seat = match role {
+                    Role::Player(seat) => *seat as u32,
+                    Role::Spectator => Role::SPECTATOR,
+                };
+                words(&[*room, seat]);
+                Message::TAG_SEATED
+            }
+            Message::Refused(refusal) => {
+                words(&[refusal.code()]);
+                Message::TAG_REFUSED
+            }
+            Message::State(state) => {
+                words(&[state.size.x as u32, state.size.y as u32]);
+                words(&[state.turn as u32, state.pairs[0], state.pairs[1]]);
+                for cell in &state.cells {
+                    words(&cell.words());
+                }
+                Message::TAG_STATE
+            }
+            Message::Chat { seat, text } => {
+                words(&[*seat as u32]);
+                let chars: Vec<u32> = text.chars().map(u32::from).collect();
+                words(&chars);
+                Message::TAG_CHAT
+            }
+            Message::TurnExpired { seat } => {
+                words(&[*seat as u32]);
+                Message::TAG_TURN_EXPIRED
+            }
+            Message::Player { name, rating } => {
+                words(&[*rating]);
+                let chars: Vec<u32> = name.chars().map(u32::from).collect();
+                words(&chars);
+                Message::TAG_PLAYER
+            }
+        };
+        buf.insert(0, tag);
+        buf
+    }
+
+    /// Deserialize a message from a frame payload.
+    pub fn decode(buf: &[u8]) -> io::Result<Message> {
+        let (tag, rest) = buf.split_first().ok_or_else(malformed)?;
+        let words = read_words(rest)?;
+        let vec2 = |x: u32, y: u32| Vec2 {
+            x: x as i32,
+            y: y as i32,
+        };
+
+        match *tag {
+            Message::TAG_COMMIT => {
+                let (size, rest) = match words.as_slice() {
+                    [x, y, rest @ ..] => (vec2(*x, *y), rest),
+                    _ => return Err(malformed()),
+                };
+                let cells = Idx2d::new(size.x, size.y).cells();
+                let cells = match cells {
+                    Some(n) if size.x > 0 && size.y > 0 => n as usize,
+                    _ => return Err(malformed()),
+                };
+                if rest.len() != cells + Message::DIGEST_WORDS {
+                    return Err(malformed());
+                }
+                let (holes, hash) = rest.split_at(cells);
+                Ok(Message::Commit {
+                    size,
+                    holes: holes.iter().map(|h| *h != 0).collect(),
+                    hash: digest_bytes(hash),
+                })
+            }
+            Message::TAG_REVEAL => match words.as_slice() {
+                [x, y] => Ok(Message::Reveal(vec2(*x, *y))),
+                _ => Err(malformed()),
+            },
+            Message::TAG_REVEALED => match words.as_slice() {
+                [x, y, card] => Ok(Message::Revealed {
+                    at: vec2(*x, *y),
+                    card: *card,
+                }),
+                _ => Err(malformed()),
+            },
+            Message::TAG_OPEN => {
+                if words.len() < Message::DIGEST_WORDS {
+                    return Err(malformed());
+                }
+                let (salt, cards) = words.split_at(Message::DIGEST_WORDS);
+                Ok(Message::Open {
+                    salt: digest_bytes(salt),
+                    cards: cards
+                        .iter()
+                        .map(|c| Some(*c).filter(|c| *c != Message::HOLE))
+                        .collect(),
+                })
+            }
+            Message::TAG_LIST_ROOMS if words.is_empty() => {
+                Ok(Message::ListRooms)
+            }
+            Message::TAG_ROOMS => {
+                let rooms = words.chunks_exact(4);
+                if !rooms.remainder().is_empty() {
+                    return Err(malformed());
+                }
+                let rooms = rooms.map(|room| RoomInfo {
+                    id: room[0],
+                    size: vec2(room[1], room[2]),
+                    full: room[3] != 0,
+                });
+                Ok(Message::Rooms(rooms.collect()))
+            }
+            Message::TAG_CREATE_ROOM => match words.as_slice() {
+                [x, y] => Ok(Message::CreateRoom(vec2(*x, *y))),
+                _ => Err(malformed()),
+            },
+            Message::TAG_JOIN_ROOM => match words.as_slice() {
+                [id] => Ok(Message::JoinRoom(*id)),
+                _ => Err(malformed()),
+            },
+            Message::TAG_FIND_MATCH => match words.as_slice() {
+                [x, y] => Ok(Message::FindMatch(vec2(*x, *y))),
+                _ => Err(malformed()),
+            },
+            Message::TAG_WATCH => match words.as_slice() {
+                [id] => Ok(Message::Watch(*id)),
+                _ => Err(malformed()),
+            },
+            Message::TAG_SEATED => {
+                let role = match words.as_slice() {
+                    [_, Role::SPECTATOR] => Role::Spectator,
+                    [_, seat] if *seat < 2 => Role::Player(*seat as usize),
+                    _ => return Err(malformed()),
+                };
+                Ok(Message::Seated {
+                    room: words[0],
+                    role,
+                })
+            }
+            Message::TAG_REFUSED => match words.as_slice() {
+                [code] => Refusal::from_code(*code)
+                    .map(Message::Refused)
+                    .ok_or_else(malformed),
+                _ => Err(malformed()),
+            },
+            Message::TAG_STATE => {
+                let (size, turn, pairs, rest) = match words.as_slice() {
+                    [x, y, turn, first, second, rest @ ..] if *turn < 2 => {
+                        (vec2(*x, *y), *turn, [*first, *second], rest)
+                    }
+                    _ => return Err(malformed()),
+                };
+                let cells = Idx2d::new(size.x, size.y).cells();
+                match cells {
+                    Some(n) if size.x > 0 && size.y > 0 => {
+                        if rest.len() != n as usize * 2 {
+                            return Err(malformed());
+                        }
+                    }
+                    _ => return Err(malformed()),
+                }
+                let cells = rest
+                    .chunks_exact(2)
+                    .map(|cell| Cell::from_words(cell[0], cell[1]))
+                    .collect::<Option<_>>()
+                    .ok_or_else(malformed)?;
+                Ok(Message::State(RoomState {
+                    size,
+                    cells,
+                    turn: turn as usize,
+                    pairs,
+                }))
+            }
+            Message::TAG_CHAT => match words.as_slice() {
+                [seat, text @ ..] if *seat < 2 => {
+                    let text = text
+                        .iter()
+                        .map(|c| char::from_u32(*c))
+                        .collect::<Option<_>>()
+                        .ok_or_else(malformed)?;
+                    Ok(Message::Chat {
+                        seat: *seat as usize,
+                        text,
+                    })
+                }
+                _ => Err(malformed()),
+            },
+            Message::TAG_TURN_EXPIRED => match words.as_slice() {
+                [seat] if *seat < 2 => Ok(Message::TurnExpired {
+                    seat: *seat as usize,
+                }),
+                _ => Err(malformed()),
+            },
+            Message::TAG_PLAYER => match words.as_slice() {
+                [rating, name @ ..] => {
+                    let name = name
+                        .iter()
+                        .map(|c| char::from_u32(*c))
+                        .collect::<Option<_>>()
+                        .ok_or_else(malformed)?;
+                    Ok(Message::Player {
+                        name,
+                        rating: *rating,
+                    })
+                }
+                _ => Err(malformed()),
+            },
+            _ => Err(malformed()),
+        }
+    }
+}
+
+/// Split a hash or a salt into big-endian 32-bit words.
+fn digest_words(digest: &[u8; 32]) -> Vec<u32> {
+    digest
+        .chunks_exact(4)
+        .map(|w| u32::from_be_bytes([w[0], w[1], w[2], w[3]]))
+        .collect()
+}
+
+/// Join the big-endian 32-bit words of a hash or a salt.
+fn digest_bytes(words: &[u32]) -> [u8; 32] {
+    let mut digest = [0; 32];
+    for (chunk, word) in digest.chunks_exact_mut(4).zip(words) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+/// Split a payload into big-endian 32-bit words.
+fn read_words(buf: &[u8]) -> io::Result<Vec<u32>> {
+    let chunks = buf.chunks_exact(4);
+    if !chunks.remainder().is_empty() {
+        return Err(malformed());
+    }
+    Ok(chunks
+        .map(|w| u32::from_be_bytes([w[0], w[1], w[2], w[3]]))
+        .collect())
+}
+
+fn malformed() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "malformed message")
+}
+
+/// A connection to the other player. Messages are sent as frames made of
+/// a big-endian `u32` length followed by that many bytes of payload.
+pub struct Connection {
+    stream: TcpStream,
+}
+
+impl Connection {
+    /// Listen on `port` and wait for a single opponent to connect.
+    pub fn host(port: u16) -> io::Result<Connection> {
+        let listener = TcpListener::bind(("0.0.0.0", port))?;
+        let (stream, _) = listener.accept()?;
+        Connection::from_stream(stream)
+    }
+
+    /// Connect to an opponent hosting a game at `addr`.
+    pub fn join<A: ToSocketAddrs>(addr: A) -> io::Result<Connection> {
+        Connection::from_stream(TcpStream::connect(addr)?)
+    }
+
+    /// Wrap a stream accepted by a server, such as a
+    /// [lobby](`crate::lobby`).
+    pub(crate) fn from_stream(stream: TcpStream) -> io::Result<Connection> {
+        // Moves are tiny and latency matters more than throughput
+        stream.set_nodelay(true)?;
+        Ok(Connection { stream })
+    }
+
+    /// Another handle to the same connection, e.g. to wait for the next
+    /// message on another thread.
+    pub fn try_clone(&self) -> io::Result<Connection> {
+        Ok(Connection {
+            stream: self.stream.try_clone()?,
+        })
+    }
+
+    /// Close the connection in both directions, e.g. to stop another
+    /// thread waiting for a message on it.
+    pub fn close(&self) {
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+
+    /// Send a single message to the opponent.
+    pub fn send(&mut self, msg: &Message) -> io::Result<()> {
+        let payload = msg.encode();
+        self.stream
+            .write_all(&(payload.len() as u32).to_be_bytes())?;
+        self.stream.write_all(&payload)?;
+        self.stream.flush()
+    }
+
+    /// Receive a message that can arrive at any time rather than only in
+    /// answer to a move, such as a line of chat, if the whole of one has
+    /// arrived and is next in line, without waiting for it. Anything else
+    /// is left to be received in turn.
+    pub fn try_recv_unprompted(&mut self) -> io::Result<Option<Message>> {
+        self.stream.set_nonblocking(true)?;
+        let frame = self.peek_frame();
+        self.stream.set_nonblocking(false)?;
+        let unprompted = [
+            Message::TAG_CHAT,
+            Message::TAG_TURN_EXPIRED,
+            Message::TAG_PLAYER,
+        ];
+        match frame? {
+            Some(payload)
+                if payload.first().is_some_and(|t| unprompted.contains(t)) =>
+            {
+                self.recv().map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// The payload of the next frame if all of it has arrived, leaving it
+    /// to be received.
+    fn peek_frame(&self) -> io::Result<Option<Vec<u8>>> {
+        let mut len = [0; 4];
+        match self.stream.peek(&mut len) {
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(4) => {}
+            Ok(_) => return Ok(None),
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(None),
+            Err(e) => return Err(e),
+        }
+        let len = u32::from_be_bytes(len) as usize;
+        if len > MAX_FRAME_LEN {
+            return Err(malformed());
+        }
+        let mut frame = vec![0; 4 + len];
+        match self.stream.peek(&mut frame) {
+            Ok(n) if n == frame.len() => Ok(Some(frame.split_off(4))),
+            Ok(_) => Ok(None),
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Block until the next message from the opponent arrives.
+    pub fn recv(&mut self) -> io::Result<Message> {
+        let mut len = [0; 4];
+        self.stream.read_exact(&mut len)?;
+        let len = u32::from_be_bytes(len) as usize;
+        if len > MAX_FRAME_LEN {
+            return Err(malformed());
+        }
+
+        let mut payload = vec![0; len];
+        self.stream.read_exact(&mut payload)?;
+        Message::decode(&payload)
+    }
+}
@@ -0,0 +1,4 @@
+//! How the files the game keeps, such as the saved game and the
+//! statistics, are laid out on disk.
+
+pub mod format;
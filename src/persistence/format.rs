@@ -0,0 +1,119 @@
+//! A versioned envelope around the data of every file the game keeps, so
+//! that files written by older versions of the game keep loading after the
+//! layout of the data changes.
+//!
+//! The data is written as
+//!
+//! ```json
+//! { "format": "save", "version": 1, "data": { ... } }
+//! ```
+//!
+//! where `format` names the kind of file and `version` the layout of
+//! `data`. Files written before the envelope was introduced hold only the
+//! data, and are read as version 0.
+//!
+//! Each kind of file has a list of migrations, one for every version after
+//! 0, that bring data of the version before up to date. Reading a file runs
+//! the migrations from its version onwards before deserializing the data,
+//! and writing one always writes the latest version. Changing the layout of
+//! the data of a kind of file means adding a migration to its list.
+
+use std::io::{self, ErrorKind};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+/// Bring data of one version up to the next.
+pub type Migration = fn(Value) -> Result<Value, String>;
+
+/// A kind of file, and how to read each version of it.
+pub struct FileFormat {
+    /// The name of the kind of file in the envelope, e.g. `save`.
+    pub kind: &'static str,
+    /// Turn data of version `i` into data of version `i + 1`.
+    pub migrations: &'static [Migration],
+}
+
+/// The envelope as it is read.
+#[derive(Deserialize)]
+struct Envelope {
+    format: String,
+    version: u32,
+    data: Value,
+}
+
+/// The envelope as it is written.
+#[derive(Serialize)]
+struct EnvelopeRef<'a, T> {
+    format: &'a str,
+    version: u32,
+    data: &'a T,
+}
+
+impl FileFormat {
+    /// The version written now.
+    pub fn version(&self) -> u32 {
+        self.migrations.len() as u32
+    }
+
+    /// Read the data of a file of this kind, of any version up to the
+    /// latest one.
+    pub fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> io::Result<T> {
+        let value: Value = serde_json::from_slice(bytes).map_err(invalid)?;
+        let (version, mut data) = match is_envelope(&value) {
+            true => {
+                let envelope: Envelope =
+                    serde_json::from_value(value).map_err(invalid)?;
+                if envelope.format != self.kind {
+                    return Err(invalid(format!(
+                        "expected a {} file, got a {} file",
+                        self.kind, envelope.format
+                    )));
+                }
+                (envelope.version, envelope.data)
+            }
+            false => (0, value),
+        };
+        if version > self.version() {
+            return Err(invalid(format!(
+                "written by a newer version of the game (format version {}, \
+                 this one reads up to {})",
+                version,
+                self.version()
+            )));
+        }
+        for migrate in &self.migrations[version as usize..] {
+            data = migrate(data).map_err(invalid)?;
+        }
+        serde_json::from_value(data).map_err(invalid)
+    }
+
+    /// Write `data` in the latest version.
+    pub fn encode<T: Serialize>(&self, data: &T) -> Vec<u8> {
+        let envelope = EnvelopeRef {
+            format: self.kind,
+            version: self.version(),
+            data,
+        };
+        serde_json::to_vec_pretty(&envelope)
+            .expect("persisted data is always serializable")
+    }
+}
+
+/// The migration from files written before the envelope, whose data is
+/// laid out the same as in version 1.
+pub fn enveloped(data: Value) -> Result<Value, String> {
+    Ok(data)
+}
+
+/// Check if `value` is an envelope rather than the bare data of a file
+/// from before it was introduced.
+fn is_envelope(value: &Value) -> bool {
+    value.get("format").is_some_and(Value::is_string)
+        && value.get("version").is_some_and(Value::is_u64)
+        && value.get("data").is_some()
+}
+
+fn invalid(e: impl ToString) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, e.to_string())
+}
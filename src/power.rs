@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use crate::error::{GameError, Result};
+
+/// A one-off advantage earned by finding pairs in a row, and used with a
+/// `:` command.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PowerUp {
+    /// Show the cards in a 3x3 area for [`PowerUp::PEEK_TIME`].
+    Peek,
+    /// Stop the clock for [`PowerUp::FREEZE_TIME`].
+    Freeze,
+    /// Match a pair without guessing.
+    Match,
+}
+
+impl PowerUp {
+    pub const ALL: [PowerUp; 3] =
+        [PowerUp::Peek, PowerUp::Freeze, PowerUp::Match];
+
+    /// How long a peek shows the cards for.
+    pub const PEEK_TIME: Duration = Duration::from_secs(2);
+
+    /// How long a freeze stops the clock for.
+    pub const FREEZE_TIME: Duration = Duration::from_secs(10);
+
+    /// The power-up earned by reaching a streak of `streak` pairs found in
+    /// a row, if any. The longer the streak, the stronger the power-up.
+    pub fn for_streak(streak: u32) -> Option<PowerUp> {
+        match streak {
+            3 => Some(PowerUp::Peek),
+            5 => Some(PowerUp::Freeze),
+            7 => Some(PowerUp::Match),
+            _ => None,
+        }
+    }
+
+    /// The name of the command using the power-up, without the `:` prefix.
+    pub fn name(self) -> &'static str {
+        match self {
+            PowerUp::Peek => "peek",
+            PowerUp::Freeze => "freeze",
+            PowerUp::Match => "match",
+        }
+    }
+
+    /// Parse the name of a power-up, as returned by [`PowerUp::name`].
+    pub fn parse(s: &str) -> Result<PowerUp> {
+        PowerUp::ALL
+            .into_iter()
+            .find(|p| s.trim().eq_ignore_ascii_case(p.name()))
+            .ok_or_else(|| GameError::UnknownCommand {
+                command: s.trim().to_owned(),
+            })
+    }
+}
+
+/// The power-ups held by the player.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Inventory {
+    /// Number of each power-up held, in the order of [`PowerUp::ALL`].
+    counts: [u32; 3],
+}
+
+impl Inventory {
+    /// Number of `power` held.
+    pub fn count(&self, power: PowerUp) -> u32 {
+        self.counts[power as usize]
+    }
+
+    /// Check if no power-ups are held.
+    pub fn is_empty(&self) -> bool {
+        self.counts.iter().all(|n| *n == 0)
+    }
+
+    /// Add a `power` to the inventory.
+    pub fn add(&mut self, power: PowerUp) {
+        self.counts[power as usize] += 1;
+    }
+
+    /// Take a `power` out of the inventory, or fail if none are held.
+    pub fn take(&mut self, power: PowerUp) -> Result<()> {
+        match &mut self.counts[power as usize] {
+            0 => Err(GameError::NoPowerUp {
+                name: power.name().to_owned(),
+            }),
+            n => {
+                *n -= 1;
+                Ok(())
+            }
+        }
+    }
+}
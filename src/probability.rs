@@ -0,0 +1,133 @@
+//! The chances of the game for a player with perfect memory, given which
+//! of the cards left face down they have seen. Only boards where every
+//! card has exactly one partner are covered: no jokers, bombs or playing
+//! cards, which pair up with any card of their rank.
+
+use crate::board::Pairing;
+
+/// What has been seen of the cards left face down.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct InfoSet {
+    /// Cards never seen.
+    pub unseen: usize,
+    /// Cards seen whose partner was never seen.
+    pub singles: usize,
+    /// Pairs both cards of which were seen.
+    pub pairs: usize,
+}
+
+impl InfoSet {
+    /// A shuffled board of `pairs` pairs, none of which were seen.
+    pub fn fresh(pairs: usize) -> InfoSet {
+        InfoSet {
+            unseen: 2 * pairs,
+            singles: 0,
+            pairs: 0,
+        }
+    }
+
+    /// Work out what has been seen of `cards`, the cards left face down,
+    /// each with whether it was seen. `None` unless every card pairs with
+    /// exactly one other.
+    pub fn new<T: Pairing>(cards: &[(T, bool)]) -> Option<InfoSet> {
+        let mut info = InfoSet {
+            unseen: 0,
+            singles: 0,
+            pairs: 0,
+        };
+        for (i, (card, seen)) in cards.iter().enumerate() {
+            let mut partners = cards
+                .iter()
+                .enumerate()
+                .filter(|(j, (other, _))| *j != i && card.pairs_with(other));
+            let (Some((_, (_, partner_seen))), None) =
+                (partners.next(), partners.next())
+            else {
+                return None;
+            };
+            match (seen, partner_seen) {
+                (false, _) => info.unseen += 1,
+                (true, false) => info.singles += 1,
+                (true, true) => info.pairs += 1,
+            }
+        }
+        // Both cards of a pair seen were counted
+        info.pairs /= 2;
+        Some(info)
+    }
+
+    /// The expected number of guesses a player with perfect memory takes
+    /// to clear the cards: one for every pair seen, and the rest by
+    /// turning over unseen cards, picking up the partner of any card that
+    /// was seen before.
+    pub fn guesses_left(&self) -> f64 {
+        let expected = expected_guesses(self.unseen);
+        self.pairs as f64 + expected[self.unseen][self.singles]
+    }
+}
+
+/// The chance that each of `cards`, the cards left face down each with
+/// whether it was seen, matches the one at `revealed` among them: certain
+/// or none for the cards seen, and the same for every card never seen
+/// unless the partner was seen. `None` in place of the revealed card, and
+/// in place of all of them unless every card pairs with exactly one other.
+pub fn match_chances<T: Pairing>(
+    cards: &[(T, bool)],
+    revealed: usize,
+) -> Option<Vec<Option<f64>>> {
+    InfoSet::new(cards)?;
+    let (card, _) = cards.get(revealed)?;
+    let others = || {
+        cards
+            .iter()
+            .enumerate()
+            .filter(move |(i, _)| *i != revealed)
+            .map(|(_, other)| other)
+    };
+    let partner_seen =
+        others().any(|(other, seen)| *seen && card.pairs_with(other));
+    let unseen = others().filter(|(_, seen)| !seen).count();
+    let chances = cards.iter().enumerate().map(|(i, (other, seen))| {
+        if i == revealed {
+            None
+        } else if *seen {
+            Some(if card.pairs_with(other) { 1.0 } else { 0.0 })
+        } else if partner_seen {
+            Some(0.0)
+        } else {
+            Some(1.0 / unseen as f64)
+        }
+    });
+    Some(chances.collect())
+}
+
+/// `expected[unseen][known]`: the expected number of guesses left with
+/// `unseen` cards never turned over, `known` of which match a card seen
+/// before, for up to `cards` unseen cards.
+fn expected_guesses(cards: usize) -> Vec<Vec<f64>> {
+    let mut expected = vec![vec![0.0; cards + 1]; cards + 1];
+    for unseen in 1..=cards {
+        for known in (unseen % 2..=unseen).step_by(2) {
+            let u = unseen as f64;
+            let k = known as f64;
+            // The first card matches one seen before, which is picked next
+            let mut guesses = 0.0;
+            if known > 0 {
+                guesses += k / u * (1.0 + expected[unseen - 1][known - 1]);
+            }
+            // The first card is new. The second one either matches it,
+            // matches a card seen before, which takes a guess of its own to
+            // pick up, or is new too.
+            if unseen > known {
+                let after = &expected[unseen - 2];
+                let rest = u - 1.0;
+                let second = (1.0 + after[known]) / rest
+                    + k * (2.0 + after[known]) / rest
+                    + (rest - 1.0 - k) * (1.0 + after[known + 2]) / rest;
+                guesses += (u - k) / u * second;
+            }
+            expected[unseen][known] = guesses;
+        }
+    }
+    expected
+}
@@ -0,0 +1,69 @@
+//! Named profiles, each with statistics, saved games and settings of its
+//! own. The files of a profile are kept in `profiles/<name>` below the
+//! usual directories; without a profile, they are where they always were.
+
+use std::{fs, path::PathBuf, sync::OnceLock};
+
+use crate::error::{GameError, Result};
+use crate::stats::shared_data_dir;
+
+/// The profile picked for this run, if any.
+static CURRENT: OnceLock<String> = OnceLock::new();
+
+/// Longest name a profile can have.
+const MAX_NAME_LEN: usize = 32;
+
+/// Use the profile called `name` for the rest of the run. Only the first
+/// profile picked takes effect, before any file is read.
+pub fn select(name: &str) -> Result<()> {
+    let name = name.trim();
+    if !is_valid_name(name) {
+        return Err(GameError::InvalidProfileName {
+            name: name.to_owned(),
+        });
+    }
+    let _ = CURRENT.set(name.to_owned());
+    Ok(())
+}
+
+/// Check if `name` can be the name of a profile: letters, digits, `-` and
+/// `_`, and no more than [`MAX_NAME_LEN`] bytes.
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
+
+/// The name of the profile in use, if any.
+pub fn current() -> Option<&'static str> {
+    CURRENT.get().map(String::as_str)
+}
+
+/// The directory within `dir` that the files of the current profile are
+/// kept in, or `dir` itself without a profile.
+pub fn namespace(dir: PathBuf) -> PathBuf {
+    match current() {
+        Some(name) => dir.join("profiles").join(name),
+        None => dir,
+    }
+}
+
+/// The names of the profiles that have written any data so far, in
+/// alphabetical order.
+pub fn list() -> Vec<String> {
+    let Some(dir) = shared_data_dir() else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(dir.join("profiles")) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    names.sort();
+    names
+}
@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::Game;
+use crate::grid::Layout;
+use crate::t;
+
+/// A command sent by a bot or external UI, one JSON object per line.
+/// Coordinates are 1-based, like the ones typed by human players.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum Command {
+    /// Dismiss the welcome screen or a guess feedback screen.
+    Continue,
+    /// Create a board with the given number of columns and rows.
+    Start { x: i32, y: i32 },
+    /// Reveal the card at the given position.
+    Reveal { x: i32, y: i32 },
+    /// Ask for a hint while guessing.
+    Hint,
+    /// Answer the "play again?" question on the victory screen.
+    PlayAgain { value: bool },
+}
+
+impl Command {
+    /// Parse a single line of input.
+    pub fn parse(line: &str) -> serde_json::Result<Command> {
+        serde_json::from_str(line)
+    }
+
+    /// Translate the command into the text a human player would type.
+    pub fn to_input(&self) -> String {
+        match self {
+            Command::Continue => String::new(),
+            Command::Start { x, y } | Command::Reveal { x, y } => {
+                format!("{},{}", x, y)
+            }
+            Command::Hint => "hint".to_owned(),
+            Command::PlayAgain { value: true } => "y".to_owned(),
+            Command::PlayAgain { value: false } => "n".to_owned(),
+        }
+    }
+}
+
+/// The observable state of the game, printed as one JSON object per line
+/// after each update.
+#[derive(Clone, Debug, Serialize)]
+pub struct Snapshot {
+    /// One of `welcome`, `size_menu`, `set_dimensions`, `memorize`,
+    /// `preview`, `guess`, `correct_guess`, `incorrect_guess`,
+    /// `bomb_exploded`, `victory`, `defeat`, `level_select`, `level_intro`,
+    /// `level_outro`, `confirm_quit`, `confirm_restart`, `settings`,
+    /// `confirm_recover` or `exit`.
+    pub state: &'static str,
+    /// Number of columns and rows, or `[0, 0]` before the board is created.
+    pub size: [i32; 2],
+    /// The board in row major order, with cards as they are drawn, e.g. `☀`
+    /// or `☀2` for the second pair with the same symbol. Face-down cards are
+    /// `null`.
+    pub board: Vec<Vec<Option<String>>>,
+    /// 1-based positions of the holes in the shape of the board, which never
+    /// hold a card.
+    pub holes: Vec<[i32; 2]>,
+    /// `square`, or `hex` if odd rows are shifted right by half a card.
+    pub layout: Layout,
+    /// The 1-based layer of the card on top of each cell, in the same order
+    /// as `board`. Always 1 unless cards are stacked.
+    pub layers: Vec<Vec<usize>>,
+    /// 1-based positions of the cards revealed during the current guess.
+    pub revealed: Vec<[i32; 2]>,
+    /// Number of guesses so far.
+    pub guesses: i32,
+    /// Number of pairs found so far.
+    pub pairs_found: i32,
+    /// Number of hints used so far.
+    pub hints: i32,
+    /// Number of incorrect guesses left before the game is lost, or `null`
+    /// if unlimited.
+    pub lives: Option<u32>,
+    /// Number of each power-up held, keyed by the name of its command.
+    pub power_ups: BTreeMap<&'static str, u32>,
+    /// 1-based positions of the cards shown by the last hint.
+    pub hinted: Vec<[i32; 2]>,
+    /// Set if the face-down cards were reshuffled by the last update.
+    pub reshuffled: bool,
+    /// 1-based positions of the matched cards turned face down again by the
+    /// last update.
+    pub decayed: Vec<[i32; 2]>,
+    /// Points earned on the current board.
+    pub score: u64,
+    /// Number of consecutive pairs found up to the last guess.
+    pub streak: u32,
+    /// The message for the last rejected command, if any.
+    pub error: Option<String>,
+    /// A stable name for the kind of `error`, e.g. `already_revealed`, as
+    /// given by [`GameError::code`](crate::error::GameError::code), or
+    /// `invalid_command` for a line that isn't a command.
+    pub error_code: Option<&'static str>,
+}
+
+/// Apply a line holding a [`Command`] to `game`, and return the snapshot
+/// to answer with. A line that isn't a command leaves the game as it was,
+/// and is reported in the snapshot.
+pub fn respond(game: &mut Game, line: &str) -> Snapshot {
+    match Command::parse(line) {
+        Ok(cmd) => apply(game, &cmd),
+        Err(e) => {
+            let mut snapshot = game.snapshot();
+            snapshot.error = Some(t!("invalid-command", reason = e));
+            snapshot.error_code = Some("invalid_command");
+            snapshot
+        }
+    }
+}
+
+/// Give `command` to `game` as the input it stands for, and return the
+/// snapshot after the update.
+pub fn apply(game: &mut Game, command: &Command) -> Snapshot {
+    game.set_input(command.to_input());
+    game.update();
+    game.snapshot()
+}
+
+impl Snapshot {
+    /// Serialize the snapshot as a single line of JSON.
+    pub fn to_line(&self) -> String {
+        serde_json::to_string(self).expect("snapshot is always serializable")
+    }
+}
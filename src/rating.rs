@@ -0,0 +1,33 @@
+use crate::solver;
+
+/// The most stars a cleared board is rated with.
+pub const MAX_STARS: u8 = 3;
+
+/// The number of guesses to aim for on a board of `pairs` pairs: what a
+/// player with perfect memory takes on average, rounded to the nearest
+/// guess.
+pub fn par(pairs: usize) -> u32 {
+    solver::expected_guesses(pairs).round() as u32
+}
+
+/// The stars earned by clearing a board of `pairs` pairs in `guesses`
+/// guesses: three at or under par, two within half of par over it, and one
+/// for clearing it at all.
+pub fn stars(guesses: i32, pairs: usize) -> u8 {
+    let par = par(pairs);
+    let guesses = guesses.max(0) as u32;
+    if guesses <= par {
+        3
+    } else if guesses <= par + par.div_ceil(2) {
+        2
+    } else {
+        1
+    }
+}
+
+/// Draw a rating as filled and empty stars, e.g. `★★☆`, or `**-` in ASCII.
+pub fn display(stars: u8, unicode: bool) -> String {
+    let (filled, empty) = if unicode { ("★", "☆") } else { ("*", "-") };
+    let stars = stars.min(MAX_STARS) as usize;
+    filled.repeat(stars) + &empty.repeat(MAX_STARS as usize - stars)
+}
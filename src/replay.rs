@@ -0,0 +1,186 @@
+//! Recordings of the moves made on a board, to be played back later or
+//! turned into an [asciinema](https://asciinema.org) cast file to share.
+//!
+//! A recording holds the [board code](`crate::code::BoardCode`) of the
+//! board as it was dealt, which includes the seed for whatever else is
+//! random, and every line of input given until the board was over along
+//! with when it was given. Playing the inputs back on the same board goes
+//! through the same states. Time limits and countdowns run on the clock of
+//! the playback, so a recording made with them is only played back as far
+//! as the same inputs lead.
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+    sync::Arc,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::clock::{SystemTime, UNIX_EPOCH};
+use crate::code::BoardCode;
+use crate::error::{GameError, Result};
+use crate::game::Game;
+use crate::persistence::format::{self, FileFormat};
+use crate::screen::visible_width;
+use crate::stats::write_atomic;
+
+/// The layout of the recordings.
+const FORMAT: FileFormat = FileFormat {
+    kind: "replay",
+    migrations: &[format::enveloped],
+};
+
+/// Time between the steps of a batch of guesses when played back, as they
+/// were applied one after the other without further input.
+const BATCH_STEP: Duration = Duration::from_millis(500);
+
+/// The moves made on a board.
+#[derive(Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub struct Replay {
+    /// The [board code](`crate::code::BoardCode`) of the board as it was
+    /// dealt, along with its rules.
+    pub board: String,
+    /// The input given, in order.
+    pub moves: Vec<Move>,
+}
+
+/// A line of input given while the board was in play.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Move {
+    /// Milliseconds since the board was dealt.
+    pub at_ms: u64,
+    pub input: String,
+}
+
+impl Replay {
+    /// Start recording the moves made on the board of `code`.
+    pub fn new(code: &BoardCode) -> Replay {
+        Replay {
+            board: code.encode(),
+            moves: Vec::new(),
+        }
+    }
+
+    /// Load the recording at `path`.
+    pub fn load(path: &Path) -> Result<Replay> {
+        let bytes = fs::read(path).map_err(|e| replay_io(path, e))?;
+        FORMAT.decode(&bytes).map_err(|e| replay_io(path, e))
+    }
+
+    /// Write the recording to `path`, replacing whatever was there.
+    pub fn store(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.exists()) {
+            fs::create_dir_all(dir).map_err(|e| replay_io(path, e))?;
+        }
+        let bytes = FORMAT.encode(self);
+        write_atomic(path, &bytes).map_err(|e| replay_io(path, e))
+    }
+
+    /// A game on the recorded board, with the same seed, ready for the
+    /// moves to be [played](`Replay::play`) on it.
+    pub fn game(&self) -> Result<Game> {
+        let code = BoardCode::decode(&self.board)?;
+        let mut game = match code.seed {
+            Some(seed) => Game::with_seed(seed),
+            None => Game::new(),
+        };
+        game.start_from_code(&code)?;
+        Ok(game)
+    }
+
+    /// Give the recorded input to `game`, rendering it after every move.
+    /// `wait` is called before each move with the time since the board was
+    /// dealt that it is due at, e.g. to sleep until then.
+    pub fn play(
+        &self,
+        game: &mut Game,
+        mut wait: impl FnMut(Duration, &mut Game),
+    ) {
+        game.render();
+        let mut at = Duration::ZERO;
+        for recorded in &self.moves {
+            if !game.is_running() {
+                break;
+            }
+            at = at.max(Duration::from_millis(recorded.at_ms));
+            wait(at, game);
+            game.set_input(recorded.input.clone());
+            game.update();
+            game.render();
+            while let Some(input) = game.next_queued_input() {
+                at += BATCH_STEP;
+                wait(at, game);
+                game.set_input(input);
+                game.update();
+                game.render();
+            }
+        }
+    }
+
+    /// Play the moves back on `game`, which has to
+    /// [capture its frames](`Game::capture_frames`), and write the frames
+    /// to `out` as an asciinema cast file (version 2).
+    pub fn write_cast(
+        &self,
+        game: &mut Game,
+        mut out: impl Write,
+    ) -> io::Result<()> {
+        let mut frames = Vec::new();
+        let mut shown_at = Duration::ZERO;
+        self.play(game, |at, game| {
+            let taken = game.take_frames();
+            frames.extend(taken.into_iter().map(|frame| (shown_at, frame)));
+            shown_at = at;
+        });
+        let taken = game.take_frames();
+        frames.extend(taken.into_iter().map(|frame| (shown_at, frame)));
+
+        let lines = || frames.iter().flat_map(|(_, frame)| frame.lines());
+        let header = CastHeader {
+            version: 2,
+            width: lines().map(visible_width).max().unwrap_or(0).max(1),
+            height: frames
+                .iter()
+                .map(|(_, frame)| frame.lines().count())
+                .max()
+                .unwrap_or(0)
+                .max(1),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        };
+        serde_json::to_writer(&mut out, &header)?;
+        writeln!(out)?;
+        let esc = 27 as char;
+        for (at, frame) in &frames {
+            // Each frame is drawn from scratch, at the top of the screen
+            let data =
+                format!("{esc}[H{esc}[2J{}", frame.replace('\n', "\r\n"));
+            serde_json::to_writer(&mut out, &(at.as_secs_f64(), "o", data))?;
+            writeln!(out)?;
+        }
+        out.flush()
+    }
+}
+
+/// The first line of a cast file.
+#[derive(Serialize)]
+struct CastHeader {
+    version: u32,
+    width: usize,
+    height: usize,
+    /// Seconds since 1970-01-01 when the cast was made.
+    timestamp: u64,
+}
+
+fn replay_io(path: &Path, e: io::Error) -> GameError {
+    GameError::ReplayIo {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+        source: Some(Arc::new(e)),
+    }
+}
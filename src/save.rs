@@ -0,0 +1,132 @@
+use std::{
+    fs,
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::board::Card;
+use crate::code::BoardCode;
+use crate::error::{GameError, Result};
+use crate::persistence::format::{self, FileFormat};
+use crate::stats::{data_dir, write_atomic};
+
+/// The layout of the saved games.
+const FORMAT: FileFormat = FileFormat {
+    kind: "save",
+    migrations: &[format::enveloped],
+};
+
+/// A solo game saved when quitting, to be continued later with the
+/// `resume` command.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct SavedGame {
+    /// The [board code](`crate::code::BoardCode`) of the board as it was
+    /// dealt, along with its rules.
+    pub board: String,
+    /// The card at each position of each layer, top first, in row major
+    /// order. Differs from the dealt board if the cards were shuffled or
+    /// layers cleared since.
+    pub layers: Vec<Vec<Option<u32>>>,
+    /// The layer showing at each position, counted from the top.
+    pub depth: Vec<usize>,
+    /// Set for the positions whose card was matched.
+    pub discovered: Vec<bool>,
+    pub guesses: i32,
+    pub hints: i32,
+    pub mismatches: i32,
+    pub turns: u32,
+    pub points: u64,
+    pub streak: u32,
+    pub best_streak: u32,
+    /// Milliseconds spent on the board so far.
+    pub elapsed_ms: u64,
+}
+
+impl SavedGame {
+    /// Location of the saved game:
+    /// `$XDG_DATA_HOME/card-matching/save.json`, falling back to
+    /// `~/.local/share/card-matching/save.json`.
+    pub fn default_path() -> Option<PathBuf> {
+        Some(data_dir()?.join("save.json"))
+    }
+
+    /// Location of the autosave of the board in play, in the same format:
+    /// `$XDG_DATA_HOME/card-matching/autosave.json`, falling back to
+    /// `~/.local/share/card-matching/autosave.json`. It is removed when the
+    /// board is over or abandoned, so one found on startup is from a
+    /// session that was interrupted.
+    pub fn autosave_path() -> Option<PathBuf> {
+        Some(data_dir()?.join("autosave.json"))
+    }
+
+    /// Load the game saved at `path`, if there is one.
+    pub fn load(path: &Path) -> Result<Option<SavedGame>> {
+        match fs::read(path) {
+            Ok(bytes) => FORMAT
+                .decode(&bytes)
+                .and_then(|saved: SavedGame| saved.validate().map(|_| saved))
+                .map(Some)
+                .map_err(|e| save_io(path, e)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(save_io(path, e)),
+        }
+    }
+
+    /// Check that the cards and the layers showing fit the board the game
+    /// was dealt, e.g. in a file edited by hand.
+    fn validate(&self) -> io::Result<()> {
+        let invalid = |reason: &str| {
+            io::Error::new(ErrorKind::InvalidData, reason.to_owned())
+        };
+        let code = BoardCode::decode(&self.board)
+            .map_err(|_| invalid("invalid board code"))?;
+        let cells = code.size.x as usize * code.size.y as usize;
+        if self.layers.len() != code.layers.len()
+            || self.layers.iter().any(|layer| layer.len() != cells)
+        {
+            return Err(invalid("layers don't fit the board"));
+        }
+        let cards = self.layers.iter().flatten().flatten();
+        if cards.copied().any(|id| !Card(id).is_valid()) {
+            return Err(invalid("unknown card"));
+        }
+        if self.depth.len() != cells
+            || self.depth.iter().any(|depth| *depth >= self.layers.len())
+        {
+            return Err(invalid("layer depths don't fit the board"));
+        }
+        if self.discovered.len() != cells {
+            return Err(invalid("matched cards don't fit the board"));
+        }
+        Ok(())
+    }
+
+    /// Write the game to `path`, replacing the one saved before.
+    pub fn store(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| save_io(path, e))?;
+        }
+        let bytes = FORMAT.encode(self);
+        write_atomic(path, &bytes).map_err(|e| save_io(path, e))
+    }
+
+    /// Delete the game saved at `path`, once it is continued. A missing
+    /// file is not an error.
+    pub fn remove(path: &Path) -> Result<()> {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(save_io(path, e)),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn save_io(path: &Path, e: io::Error) -> GameError {
+    GameError::SaveIo {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+        source: Some(Arc::new(e)),
+    }
+}
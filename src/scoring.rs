@@ -0,0 +1,100 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::clock::Instant;
+
+/// The formula used to award points for found pairs.
+///
+/// A pair is worth `match_points` times the current streak of consecutive
+/// matches, up to `max_multiplier`. Finding a pair within `fast_seconds` of
+/// the previous guess adds `fast_bonus`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScoringRules {
+    /// Points for a single pair, before the streak multiplier.
+    pub match_points: u32,
+    /// The highest multiplier a streak can reach.
+    pub max_multiplier: u32,
+    /// Points added for a pair found quickly.
+    pub fast_bonus: u32,
+    /// Time to find a pair within to earn the bonus.
+    pub fast_seconds: u64,
+    /// Points lost for revealing a bomb.
+    pub bomb_penalty: u32,
+}
+
+impl Default for ScoringRules {
+    fn default() -> ScoringRules {
+        ScoringRules {
+            match_points: 100,
+            max_multiplier: 5,
+            fast_bonus: 50,
+            fast_seconds: 5,
+            bomb_penalty: 200,
+        }
+    }
+}
+
+/// The running score of a single board.
+#[derive(Clone, Copy, Debug)]
+pub struct Score {
+    /// Points earned so far.
+    pub points: u64,
+    /// Number of consecutive matches up to the last guess.
+    pub streak: u32,
+    /// The longest streak on the board.
+    pub best_streak: u32,
+    /// When the current guess began.
+    turn_started: Instant,
+}
+
+impl Score {
+    /// A score of zero, with the first guess beginning at `now`.
+    pub fn new(now: Instant) -> Score {
+        Score {
+            points: 0,
+            streak: 0,
+            best_streak: 0,
+            turn_started: now,
+        }
+    }
+
+    /// Start timing the next guess from `now`, e.g. after a pause.
+    pub fn start_turn(&mut self, now: Instant) {
+        self.turn_started = now;
+    }
+
+    /// Award the points for a pair found at `now`, and return them.
+    pub fn record_match(&mut self, rules: &ScoringRules, now: Instant) -> u64 {
+        self.streak += 1;
+        self.best_streak = self.best_streak.max(self.streak);
+
+        let multiplier = self.streak.min(rules.max_multiplier.max(1));
+        let mut points = rules.match_points as u64 * multiplier as u64;
+        let elapsed = now.saturating_duration_since(self.turn_started);
+        if elapsed <= Duration::from_secs(rules.fast_seconds) {
+            points += rules.fast_bonus as u64;
+        }
+
+        self.points += points;
+        self.turn_started = now;
+        points
+    }
+
+    /// Break the streak after a mismatch at `now`.
+    pub fn record_mismatch(&mut self, now: Instant) {
+        self.streak = 0;
+        self.turn_started = now;
+    }
+
+    /// Take away the points for a bomb revealed at `now` and break the
+    /// streak. Returns the points lost, which can't be more than what was
+    /// earned.
+    pub fn record_bomb(&mut self, rules: &ScoringRules, now: Instant) -> u64 {
+        let lost = self.points.min(rules.bomb_penalty as u64);
+        self.points -= lost;
+        self.record_mismatch(now);
+        lost
+    }
+}
@@ -0,0 +1,405 @@
+use std::{
+    fmt::Display,
+    io::{self, Write},
+};
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::graphics::Graphics;
+use crate::terminal;
+
+/// Pad `text` with spaces to take up `width` columns of the terminal,
+/// counting wide characters, such as emoji, as two columns.
+pub fn pad(text: &str, width: usize) -> String {
+    let spaces = width.saturating_sub(text.width());
+    format!("{}{}", text, " ".repeat(spaces))
+}
+
+/// Number of columns of the terminal `text` takes up, not counting the
+/// escape codes that style it.
+pub fn visible_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == 27 as char {
+            // A control sequence ends with a character from `@` to `~`
+            if chars.next() == Some('[') {
+                chars.by_ref().find(|c| ('@'..='~').contains(c));
+            }
+        } else {
+            width += c.width().unwrap_or(0);
+        }
+    }
+    width
+}
+
+/// Put the `right` lines next to the lines of `left`, after a `gap` of
+/// spaces, starting at the top. Lines of `right` that are left over go on
+/// lines of their own.
+pub fn beside(left: &str, right: &[String], gap: usize) -> String {
+    let mut lines: Vec<String> = left.split('\n').map(str::to_string).collect();
+    // The text after the last line break stays last
+    let rest = lines.pop().unwrap_or_default();
+    if lines.len() < right.len() {
+        lines.resize(right.len(), String::new());
+    }
+    let width = lines.iter().map(|line| visible_width(line)).max();
+    let width = width.unwrap_or_default() + gap;
+    for (line, text) in lines.iter_mut().zip(right) {
+        let spaces = width - visible_width(line);
+        line.push_str(&" ".repeat(spaces));
+        line.push_str(text);
+    }
+    lines.push(rest);
+    lines.join("\n")
+}
+
+/// The weight of a line drawn between cells.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Stroke {
+    None,
+    Light,
+    Heavy,
+}
+
+/// Box drawing characters by the strokes going up, right, down and left
+/// from the middle of the character, in base 3.
+const BOX_CHARS: [char; 81] = [
+    ' ', '╴', '╸', '╷', '┐', '┑', '╻', '┒', '┓', '╶', '─', '╾', '┌', '┬', '┭',
+    '┎', '┰', '┱', '╺', '╼', '━', '┍', '┮', '┯', '┏', '┲', '┳', '╵', '┘', '┙',
+    '│', '┤', '┥', '╽', '┧', '┪', '└', '┴', '┵', '├', '┼', '┽', '┟', '╁', '╅',
+    '┕', '┶', '┷', '┝', '┾', '┿', '┢', '╆', '╈', '╹', '┚', '┛', '╿', '┦', '┩',
+    '┃', '┨', '┫', '┖', '┸', '┹', '┞', '╀', '╃', '┠', '╂', '╉', '┗', '┺', '┻',
+    '┡', '╄', '╇', '┣', '╊', '╋',
+];
+
+/// The character joining lines of the given strokes going `up`, `right`,
+/// `down` and `left`, e.g. `┼`. Without `unicode`, lines are drawn with
+/// `-`, `=` for heavy ones, `|` and `+`.
+pub fn box_char(
+    up: Stroke,
+    right: Stroke,
+    down: Stroke,
+    left: Stroke,
+    unicode: bool,
+) -> char {
+    if unicode {
+        let index = [up, right, down, left]
+            .iter()
+            .fold(0, |index, stroke| index * 3 + *stroke as usize);
+        return BOX_CHARS[index];
+    }
+    let vertical = up != Stroke::None || down != Stroke::None;
+    let horizontal = left != Stroke::None || right != Stroke::None;
+    match (vertical, horizontal) {
+        (true, true) => '+',
+        (true, false) => '|',
+        (false, true) if left == Stroke::Heavy || right == Stroke::Heavy => '=',
+        (false, true) => '-',
+        (false, false) => ' ',
+    }
+}
+
+/// The text of a single rendered frame: a status line kept at the top,
+/// and the lines below it.
+#[derive(Clone, Debug, Default)]
+pub struct Frame {
+    status: Option<String>,
+    text: String,
+}
+
+impl Frame {
+    pub fn new() -> Frame {
+        Frame::default()
+    }
+
+    /// Append `text` to the current line.
+    pub fn text(&mut self, text: impl Display) {
+        self.text.push_str(&text.to_string());
+    }
+
+    /// Append `text` and end the line.
+    pub fn line(&mut self, text: impl Display) {
+        self.text(text);
+        self.text.push('\n');
+    }
+
+    /// Show `text` on the line reserved at the top of the frame, whenever
+    /// the rest of it is drawn.
+    pub fn status(&mut self, text: impl Display) {
+        self.status = Some(text.to_string());
+    }
+
+    /// The whole frame, starting with the status line if there is one.
+    pub fn contents(&self) -> String {
+        match &self.status {
+            Some(status) => format!("{}\n{}", status, self.text),
+            None => self.text.clone(),
+        }
+    }
+}
+
+/// Draws frames on the terminal, keeping the previous frame to only redraw
+/// the characters that changed.
+#[derive(Debug, Default)]
+pub struct Screen {
+    /// The lines of the last frame that are known to still be on screen.
+    shown: Vec<String>,
+    /// The last line of the last frame, after which the player types.
+    prompt: Option<String>,
+    /// Set while the player types after the last line.
+    typing: bool,
+    /// The protocol of the images in the frames, if any.
+    graphics: Option<Graphics>,
+    /// The contents of the frames presented so far, if they are kept in
+    /// memory instead of drawn.
+    captured: Option<Vec<String>>,
+}
+
+/// The number of columns and rows frames are laid out for when they are
+/// [captured](`Screen::capture`), whatever the size of the terminal.
+const CAPTURE_SIZE: (u16, u16) = (80, 24);
+
+impl Screen {
+    pub fn new() -> Screen {
+        Screen::default()
+    }
+
+    /// A screen that keeps the [contents](`Frame::contents`) of every frame
+    /// in memory instead of drawing it, for tests to check exactly what
+    /// would have been shown.
+    pub fn capture() -> Screen {
+        Screen {
+            captured: Some(Vec::new()),
+            ..Screen::default()
+        }
+    }
+
+    /// The number of columns and rows of the terminal the frames are drawn
+    /// on, if there is one. Captured frames are laid out for a terminal of
+    /// a fixed size, so that they are the same on every machine.
+    pub fn size(&self) -> Option<(u16, u16)> {
+        match self.captured {
+            Some(_) => Some(CAPTURE_SIZE),
+            None => terminal::size(),
+        }
+    }
+
+    /// Take the frames captured since the last call, oldest first. Always
+    /// empty unless the screen was created with [`Screen::capture`].
+    pub fn take_frames(&mut self) -> Vec<String> {
+        self.captured
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Forget what is on screen, so that the next frame is drawn from
+    /// scratch, e.g. after the terminal was resized.
+    pub fn invalidate(&mut self) {
+        self.shown.clear();
+        self.prompt = None;
+    }
+
+    /// Tell if the player is typing after the last line, which is then
+    /// left alone as long as it stays the same.
+    pub fn set_typing(&mut self, typing: bool) {
+        self.typing = typing;
+    }
+
+    /// Remove the images drawn with `graphics` from the lines that are
+    /// redrawn, as they aren't overwritten by the text.
+    pub fn set_graphics(&mut self, graphics: Option<Graphics>) {
+        self.graphics = graphics;
+    }
+
+    /// Redraw the characters of `frame` that differ from the previous one,
+    /// and leave the cursor at the end of its last line. Without
+    /// [escape codes](`terminal::is_ansi`), the whole frame is printed
+    /// below the previous one instead.
+    pub fn present(&mut self, frame: &Frame) {
+        if !terminal::is_ansi() || self.captured.is_some() {
+            self.append(frame);
+            return;
+        }
+
+        let esc = 27 as char;
+        let contents = frame.contents();
+        let lines: Vec<&str> = contents.split('\n').collect();
+
+        let last = lines.last().copied().unwrap_or_default();
+        // Redrawing the last line would wipe out the player's typing, e.g.
+        // when only the clock went on
+        let keep_prompt = self.typing
+            && self.shown.len() + 1 == lines.len()
+            && self.prompt.as_deref() == Some(last);
+
+        let mut out = String::new();
+        if keep_prompt {
+            out.push_str(&format!("{esc}7"));
+        }
+        if self.shown.is_empty() {
+            out.push_str(&format!("{esc}[H{esc}[J"));
+            if let Some(graphics) = self.graphics {
+                out.push_str(&graphics.clear_screen());
+            }
+        }
+        let rows = match keep_prompt {
+            true => self.shown.len(),
+            false => lines.len().max(self.shown.len()),
+        };
+        for row in 0..rows {
+            let line = lines.get(row).copied();
+            let shown = self.shown.get(row).map(String::as_str);
+            if shown == line {
+                continue;
+            }
+            // Images have to be cleared off the whole line
+            if let (Some(shown), Some(line), None) =
+                (shown, line, self.graphics)
+            {
+                if let (Some(before), Some(after)) =
+                    (glyphs(shown), glyphs(line))
+                {
+                    redraw_glyphs(&mut out, row, &before, &after);
+                    continue;
+                }
+            }
+            if let Some(graphics) = self.graphics {
+                out.push_str(&graphics.clear_line(row + 1));
+            }
+            if let Some(line) = line {
+                out.push_str(&format!("{esc}[{};1H{}{esc}[K", row + 1, line));
+            }
+        }
+        if keep_prompt {
+            out.push_str(&format!("{esc}8"));
+        } else {
+            // Whatever is below the frame is left over from a longer one
+            let column = last.width() + 1;
+            out.push_str(&format!("{esc}[{};{}H{esc}[J", lines.len(), column));
+        }
+
+        let mut stdout = io::stdout();
+        let _ = stdout.write_all(out.as_bytes());
+        let _ = stdout.flush();
+
+        // The player's typing is echoed from the cursor onwards, so the last
+        // line has to be redrawn next time
+        self.shown = lines[..lines.len() - 1]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        self.prompt = Some(last.to_owned());
+    }
+
+    /// Print the whole of `frame` below the previous one, e.g. for screen
+    /// readers to follow the output as it is printed.
+    pub fn append(&mut self, frame: &Frame) {
+        if let Some(frames) = &mut self.captured {
+            frames.push(frame.contents());
+            return;
+        }
+        let mut stdout = io::stdout();
+        let _ = write!(stdout, "\n{}", frame.contents());
+        let _ = stdout.flush();
+    }
+}
+
+/// A character on a line of a frame, with the escape codes styling it.
+#[derive(PartialEq, Eq, Debug)]
+struct Glyph {
+    /// The column of the terminal the character starts at, from 0.
+    column: usize,
+    /// The character, along with any combining characters after it.
+    text: String,
+    width: usize,
+    /// The escape codes in effect since the last reset.
+    style: String,
+}
+
+/// Split a line of a frame into the characters drawn, or `None` if it has
+/// escape codes other than colors and attributes, which can't be told
+/// apart by character.
+fn glyphs(line: &str) -> Option<Vec<Glyph>> {
+    let esc = 27 as char;
+    let mut glyphs: Vec<Glyph> = Vec::new();
+    let mut style = String::new();
+    let mut column = 0;
+    let mut chars = line.char_indices();
+    while let Some((start, c)) = chars.next() {
+        if c == esc {
+            if chars.next().map(|(_, c)| c) != Some('[') {
+                return None;
+            }
+            // A control sequence ends with a character from `@` to `~`
+            let (end, last) =
+                chars.by_ref().find(|(_, c)| ('@'..='~').contains(c))?;
+            match (last, &line[start + 2..end]) {
+                ('m', "" | "0") => style.clear(),
+                ('m', _) => style.push_str(&line[start..=end]),
+                _ => return None,
+            }
+            continue;
+        }
+        let width = c.width().unwrap_or(0);
+        match glyphs.last_mut() {
+            Some(glyph) if width == 0 => glyph.text.push(c),
+            _ => {
+                glyphs.push(Glyph {
+                    column,
+                    text: c.to_string(),
+                    width,
+                    style: style.clone(),
+                });
+                column += width;
+            }
+        }
+    }
+    Some(glyphs)
+}
+
+/// Redraw the characters of the line at `row` that differ between
+/// `before` and `after`, and clear whatever is left of a longer line.
+fn redraw_glyphs(
+    out: &mut String,
+    row: usize,
+    before: &[Glyph],
+    after: &[Glyph],
+) {
+    let esc = 27 as char;
+    // Both are in the order of their columns
+    let unchanged = |glyph: &Glyph| {
+        before
+            .binary_search_by_key(&glyph.column, |shown| shown.column)
+            .is_ok_and(|i| before[i] == *glyph)
+    };
+    let mut i = 0;
+    while i < after.len() {
+        if unchanged(&after[i]) {
+            i += 1;
+            continue;
+        }
+        out.push_str(&format!("{esc}[{};{}H", row + 1, after[i].column + 1));
+        let mut style = None;
+        while i < after.len() && !unchanged(&after[i]) {
+            if style != Some(&after[i].style) {
+                style = Some(&after[i].style);
+                out.push_str(&format!("{esc}[0m{}", after[i].style));
+            }
+            out.push_str(&after[i].text);
+            i += 1;
+        }
+        out.push_str(&format!("{esc}[0m"));
+    }
+    let width = |glyphs: &[Glyph]| {
+        glyphs.last().map_or(0, |glyph| glyph.column + glyph.width)
+    };
+    if width(after) < width(before) {
+        out.push_str(&format!(
+            "{esc}[{};{}H{esc}[K",
+            row + 1,
+            width(after) + 1
+        ));
+    }
+}
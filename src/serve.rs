@@ -0,0 +1,349 @@
+//! A minimal HTTP server hosting a web page to play in a browser.
+//!
+//! The page talks to the game with the same JSON commands and snapshots as
+//! `play --json`, one command per request:
+//!
+//! - `GET /` and the files of the page, embedded in the binary.
+//! - `POST /api/games` starts a game and answers with
+//!   `{"id": <id>, "snapshot": {...}}`.
+//! - `POST /api/games/<id>` applies the command in the body to the game and
+//!   answers with its snapshot.
+//! - `GET /api/games/<id>/events` is a stream of [server-sent events]
+//!   with a snapshot of the game whenever it moves on by itself, such as
+//!   when a time limit runs out, starting with the one it is in.
+//!
+//! Every connection is read and written on a thread of its own, so a slow
+//! client only holds up itself. The games stay on the thread serving them,
+//! which applies the requests one at a time. The server only listens on
+//! the loopback interface.
+//!
+//! [server-sent events]: https://html.spec.whatwg.org/multipage/server-sent-events.html
+
+use std::{
+    collections::BTreeMap,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::mpsc::{self, Receiver, RecvTimeoutError, Sender},
+    thread,
+    time::{Duration, Instant},
+};
+
+use rs_card_matching::{game::Game, protocol};
+
+/// The files of the page, by path.
+const ASSETS: &[(&str, &str, &str)] = &[
+    (
+        "/",
+        "text/html; charset=utf-8",
+        include_str!("../assets/web/index.html"),
+    ),
+    (
+        "/app.js",
+        "text/javascript; charset=utf-8",
+        include_str!("../assets/web/app.js"),
+    ),
+    (
+        "/style.css",
+        "text/css; charset=utf-8",
+        include_str!("../assets/web/style.css"),
+    ),
+];
+
+/// Number of games kept at most. Starting another one drops the oldest.
+const MAX_GAMES: usize = 16;
+
+/// Size of the request line and headers taken together at most.
+const MAX_HEAD: usize = 8 * 1024;
+
+/// Size of the body of a request at most. Commands are much shorter.
+const MAX_BODY: usize = 4 * 1024;
+
+/// How long to wait for a client to send its request, or to take in a
+/// response.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How often to send a comment down a stream of events without any, to
+/// notice the client going away.
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// A request, as far as it is needed.
+struct Request {
+    method: String,
+    path: String,
+    body: String,
+}
+
+/// A response to send back.
+struct Response {
+    status: &'static str,
+    content_type: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn json(body: String) -> Response {
+        Response {
+            status: "200 OK",
+            content_type: "application/json",
+            body,
+        }
+    }
+
+    fn error(status: &'static str) -> Response {
+        Response {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: status.to_owned(),
+        }
+    }
+}
+
+/// What a request is answered with.
+enum Reply {
+    Response(Response),
+    /// A stream of events, each a snapshot of the game as a line of JSON.
+    Events(Receiver<String>),
+}
+
+/// A game being played, and the streams of events following it.
+struct Hosted {
+    game: Game,
+    watchers: Vec<Sender<String>>,
+}
+
+impl Hosted {
+    /// Send the snapshot of the game to every stream still open.
+    fn notify(&mut self) {
+        let line = self.game.snapshot().to_line();
+        self.watchers
+            .retain(|watcher| watcher.send(line.clone()).is_ok());
+    }
+}
+
+/// The games being played, each started by `new_game`.
+struct Server<F> {
+    new_game: F,
+    games: BTreeMap<u64, Hosted>,
+    next_id: u64,
+}
+
+/// Serve the page on `port` of the loopback interface until the process is
+/// stopped. `new_game` makes the game of each page that is opened.
+pub fn serve(port: u16, new_game: impl FnMut() -> Game) -> io::Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    let (requests, incoming) = mpsc::channel();
+    thread::spawn(move || accept(listener, requests));
+
+    let mut server = Server {
+        new_game,
+        games: BTreeMap::new(),
+        next_id: 1,
+    };
+    loop {
+        // Wake up for the first game to move on by itself, if any
+        let deadline = server.games.values().filter_map(|g| g.game.deadline());
+        let request = match deadline.min() {
+            Some(deadline) => {
+                let timeout =
+                    deadline.saturating_duration_since(Instant::now());
+                incoming.recv_timeout(timeout)
+            }
+            None => incoming.recv().map_err(RecvTimeoutError::from),
+        };
+        match request {
+            Ok((request, reply)) => {
+                // The client may have gone away in the meantime
+                let _ = reply.send(server.respond(&request));
+            }
+            Err(RecvTimeoutError::Timeout) => server.move_on(),
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+    }
+}
+
+/// Accept connections, and handle each on a thread of its own.
+fn accept(listener: TcpListener, requests: Sender<(Request, Sender<Reply>)>) {
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let requests = requests.clone();
+        thread::spawn(move || {
+            // A client going away only ends its own request
+            let _ = handle(stream, &requests);
+        });
+    }
+}
+
+/// Read the request on `stream`, pass it on to be answered and write the
+/// answer back.
+fn handle(
+    mut stream: TcpStream,
+    requests: &Sender<(Request, Sender<Reply>)>,
+) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let reply = match read_request(&stream)? {
+        Some(request) => {
+            let (reply, answer) = mpsc::channel();
+            if requests.send((request, reply)).is_err() {
+                return Ok(());
+            }
+            match answer.recv() {
+                Ok(reply) => reply,
+                Err(_) => return Ok(()),
+            }
+        }
+        None => Reply::Response(Response::error("400 Bad Request")),
+    };
+    match reply {
+        Reply::Response(response) => write!(
+            stream,
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n\
+             Cache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+            response.status,
+            response.content_type,
+            response.body.len(),
+            response.body
+        )?,
+        Reply::Events(events) => {
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\
+                 Cache-Control: no-store\r\nConnection: close\r\n\r\n"
+            )?;
+            stream.flush()?;
+            // Until the game is dropped, or the client goes away
+            loop {
+                match events.recv_timeout(KEEP_ALIVE) {
+                    Ok(line) => write!(stream, "data: {}\n\n", line)?,
+                    Err(RecvTimeoutError::Timeout) => write!(stream, ":\n\n")?,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+                stream.flush()?;
+            }
+        }
+    }
+    stream.flush()
+}
+
+impl<F: FnMut() -> Game> Server<F> {
+    fn respond(&mut self, request: &Request) -> Reply {
+        let api = request.path.strip_prefix("/api/games");
+        let response = match (request.method.as_str(), api) {
+            ("GET", None) => ASSETS
+                .iter()
+                .find(|(path, ..)| *path == request.path)
+                .map(|(_, content_type, body)| Response {
+                    status: "200 OK",
+                    content_type,
+                    body: (*body).to_owned(),
+                })
+                .unwrap_or_else(|| Response::error("404 Not Found")),
+            ("GET", Some(events)) => match events
+                .strip_suffix("/events")
+                .and_then(|id| self.game(id))
+            {
+                Some(hosted) => {
+                    let (watcher, events) = mpsc::channel();
+                    hosted.watchers.push(watcher);
+                    hosted.notify();
+                    return Reply::Events(events);
+                }
+                None => Response::error("404 Not Found"),
+            },
+            ("POST", Some("")) => self.start(),
+            ("POST", Some(id)) => match self.game(id) {
+                Some(hosted) => {
+                    let snapshot =
+                        protocol::respond(&mut hosted.game, &request.body);
+                    Response::json(snapshot.to_line())
+                }
+                None => Response::error("404 Not Found"),
+            },
+            _ => Response::error("405 Method Not Allowed"),
+        };
+        Reply::Response(response)
+    }
+
+    /// The game with the `id` given as `/<id>`, if it is still kept.
+    fn game(&mut self, id: &str) -> Option<&mut Hosted> {
+        let id = id.strip_prefix('/')?.parse().ok()?;
+        self.games.get_mut(&id)
+    }
+
+    /// Start another game, dropping the ones that are over and the oldest
+    /// one if there are too many.
+    fn start(&mut self) -> Response {
+        self.games.retain(|_, hosted| hosted.game.is_running());
+        if self.games.len() >= MAX_GAMES {
+            self.games.pop_first();
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        let game = (self.new_game)();
+        let body = format!(
+            "{{\"id\":{},\"snapshot\":{}}}",
+            id,
+            game.snapshot().to_line()
+        );
+        let watchers = Vec::new();
+        self.games.insert(id, Hosted { game, watchers });
+        Response::json(body)
+    }
+
+    /// Move on the games whose deadline passed without a command, and let
+    /// the streams following them know.
+    fn move_on(&mut self) {
+        let now = Instant::now();
+        for hosted in self.games.values_mut() {
+            if hosted.game.deadline().is_some_and(|t| t <= now) {
+                hosted.game.set_input(String::new());
+                hosted.game.update();
+                hosted.notify();
+            }
+        }
+    }
+}
+
+/// Read the request line, the headers and the body of a request. Returns
+/// `None` if it isn't a request this server understands.
+fn read_request(stream: &TcpStream) -> io::Result<Option<Request>> {
+    let mut reader = BufReader::new(stream).take(MAX_HEAD as u64);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        return Ok(None);
+    };
+    let method = method.to_owned();
+    let path = path.split('?').next().unwrap_or(path).to_owned();
+
+    let mut length = 0;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            // The head was cut short, or too long
+            return Ok(None);
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                match value.trim().parse() {
+                    Ok(n) if n <= MAX_BODY => length = n,
+                    _ => return Ok(None),
+                }
+            }
+        }
+    }
+
+    let mut reader = reader.into_inner();
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    Ok(String::from_utf8(body)
+        .ok()
+        .map(|body| Request { method, path, body }))
+}
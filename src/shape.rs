@@ -0,0 +1,151 @@
+use std::{fs, path::Path, sync::Arc};
+
+use bitvec::{bitvec, vec::BitVec};
+
+use crate::board::{Board, Idx2d, Vec2};
+use crate::error::{GameError, Result};
+
+/// Masks of the shapes that can be picked by name.
+const BUILTIN: [(&str, &str); 3] = [
+    (
+        "heart",
+        "
+.##...##.
+####.####
+#########
+.#######.
+..#####..
+...###...
+",
+    ),
+    (
+        "diamond",
+        "
+...##...
+..####..
+.######.
+########
+.######.
+..####..
+...##...
+",
+    ),
+    (
+        "donut",
+        "
+.######.
+########
+###..###
+###..###
+########
+.######.
+",
+    ),
+];
+
+/// The cells of a board that hold cards. Cells outside the shape are holes
+/// that never hold a card, e.g. the corners of a heart.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Shape {
+    idx: Idx2d,
+    /// A set bit marks a cell that holds a card, in row major order.
+    cells: BitVec,
+}
+
+impl Shape {
+    /// A rectangular board without holes.
+    pub fn rectangle(size: Vec2) -> Shape {
+        let len = size.x.max(0) as usize * size.y.max(0) as usize;
+        Shape {
+            idx: Idx2d::new(size.x, size.y),
+            cells: bitvec![1; len],
+        }
+    }
+
+    /// A shape with the cells for which `cells` yields `true`, in row major
+    /// order.
+    pub fn from_cells(idx: Idx2d, cells: impl Iterator<Item = bool>) -> Shape {
+        Shape {
+            idx,
+            cells: cells.collect(),
+        }
+    }
+
+    /// Parse a text mask, where `#` is a cell and `.` or a space is a hole.
+    /// Rows are padded with holes to the length of the longest one, and
+    /// blank lines at the start and end are skipped.
+    pub fn parse(mask: &str) -> Result<Shape> {
+        let rows: Vec<&str> =
+            mask.trim_matches('\n').lines().map(str::trim_end).collect();
+        let width = rows.iter().map(|r| r.chars().count()).max();
+        let size = Vec2 {
+            x: width.unwrap_or(0) as i32,
+            y: rows.len() as i32,
+        };
+
+        let mut cells = Vec::new();
+        for row in &rows {
+            for c in row.chars() {
+                match c {
+                    '#' => cells.push(true),
+                    '.' | ' ' => cells.push(false),
+                    c => return Err(GameError::InvalidShapeChar { char: c }),
+                }
+            }
+            cells.resize(
+                cells.len() + size.x as usize - row.chars().count(),
+                false,
+            );
+        }
+
+        let idx = Idx2d::new(size.x, size.y);
+        let shape = Shape::from_cells(idx, cells.into_iter());
+        shape.validate()?;
+        Ok(shape)
+    }
+
+    /// Load one of the built-in shapes (`heart`, `diamond` or `donut`) by
+    /// name, or a mask from the file at `path` otherwise.
+    pub fn load(path: &Path) -> Result<Shape> {
+        let builtin = BUILTIN.iter().find(|(name, _)| Path::new(name) == path);
+        if let Some((_, mask)) = builtin {
+            return Shape::parse(mask);
+        }
+        let mask =
+            fs::read_to_string(path).map_err(|e| GameError::ShapeIo {
+                path: path.display().to_string(),
+                reason: e.to_string(),
+                source: Some(Arc::new(e)),
+            })?;
+        Shape::parse(&mask)
+    }
+
+    /// Check that a board of this shape can be created.
+    fn validate(&self) -> Result<()> {
+        let cells = self.cells() as i32;
+        if cells == 0 {
+            return Err(GameError::EmptyShape);
+        }
+        if cells > Board::MAX_ASCII_SIZE {
+            return Err(GameError::NotEnoughCardTypes {
+                max: Board::MAX_ASCII_SIZE / 2,
+            });
+        }
+        Ok(())
+    }
+
+    /// The indexer of the rectangle around the shape.
+    pub fn idx(&self) -> &Idx2d {
+        &self.idx
+    }
+
+    /// Check if there is a cell at `c`, rather than a hole.
+    pub fn contains(&self, c: Vec2) -> bool {
+        self.idx.of(c).is_ok_and(|i| self.cells[i])
+    }
+
+    /// The number of cells that hold cards.
+    pub fn cells(&self) -> usize {
+        self.cells.count_ones()
+    }
+}
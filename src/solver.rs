@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use crate::board::{Idx2d, Pairing, Vec2};
+use crate::probability::InfoSet;
+
+/// A player with perfect memory. Remembers every card it has seen but not
+/// yet matched, and pairs them greedily: as soon as both cards of a kind are
+/// known, they are picked on the next turn. Otherwise it turns over cards it
+/// has never seen, in row major order. Cards that [pair up](`Pairing`)
+/// without being equal, such as jokers, are only picked when no two known
+/// cards are equal.
+pub struct Solver<T> {
+    /// Positions that have never been revealed, in row major order.
+    unseen: Vec<Vec2>,
+    /// Cards that have been revealed but not matched yet.
+    known: HashMap<Vec2, T>,
+}
+
+impl<T: PartialEq + Pairing> Solver<T> {
+    /// Create a solver for a board of the given dimensions.
+    pub fn new(idx: &Idx2d) -> Solver<T> {
+        Solver::with_cells(idx.iter_all().collect())
+    }
+
+    /// Create a solver for a board with cards at the given positions only,
+    /// in row major order.
+    pub fn with_cells(cells: Vec<Vec2>) -> Solver<T> {
+        Solver {
+            unseen: cells,
+            known: HashMap::new(),
+        }
+    }
+
+    /// Remember the card revealed at a position.
+    pub fn observe(&mut self, c: Vec2, card: T) {
+        self.unseen.retain(|u| *u != c);
+        self.known.insert(c, card);
+    }
+
+    /// Forget everything known about the given positions, after the cards
+    /// there have been shuffled.
+    pub fn forget(&mut self, shuffled: &[Vec2]) {
+        self.known.retain(|c, _| !shuffled.contains(c));
+        self.unseen = shuffled.to_vec();
+    }
+
+    /// Treat a position as never revealed, after a matched card was removed
+    /// from it to expose the one under it.
+    pub fn uncover(&mut self, c: Vec2) {
+        self.known.remove(&c);
+        if !self.unseen.contains(&c) {
+            self.unseen.push(c);
+        }
+    }
+
+    /// Forget about a card that left the board without being matched, such
+    /// as a bomb that went off.
+    pub fn remove(&mut self, c: Vec2) {
+        self.known.remove(&c);
+        self.unseen.retain(|u| *u != c);
+    }
+
+    /// Forget about a pair of cards that were matched.
+    pub fn remove_pair(&mut self, c1: Vec2, c2: Vec2) {
+        self.known.remove(&c1);
+        self.known.remove(&c2);
+    }
+
+    /// Pick the next card to reveal. `first` is the card already revealed
+    /// during the current guess, if any.
+    pub fn next_move(&self, first: Option<Vec2>) -> Option<Vec2> {
+        match first {
+            None => self
+                .known_pair()
+                .map(|(c, _)| c)
+                .or_else(|| self.explore(None)),
+            Some(first) => {
+                self.partner_of(first).or_else(|| self.explore(Some(first)))
+            }
+        }
+    }
+
+    /// Find two known, unmatched cards that are equal, or that pair up
+    /// otherwise if there are none.
+    fn known_pair(&self) -> Option<(Vec2, Vec2)> {
+        let pair = |find: fn(&Self, Vec2) -> Option<Vec2>| {
+            self.known
+                .keys()
+                .find_map(|c| find(self, *c).map(|p| (*c, p)))
+        };
+        pair(Self::twin_of).or_else(|| pair(Self::any_partner_of))
+    }
+
+    /// Find the known card matching the one at `c`.
+    fn partner_of(&self, c: Vec2) -> Option<Vec2> {
+        self.twin_of(c).or_else(|| self.any_partner_of(c))
+    }
+
+    /// Find a known card equal to the one at `c` that pairs with it.
+    fn twin_of(&self, c: Vec2) -> Option<Vec2> {
+        let card = self.known.get(&c)?;
+        self.known
+            .iter()
+            .find(|(p, other)| {
+                **p != c && *other == card && card.pairs_with(other)
+            })
+            .map(|(p, _)| *p)
+    }
+
+    /// Find any known card that pairs with the one at `c`.
+    fn any_partner_of(&self, c: Vec2) -> Option<Vec2> {
+        let card = self.known.get(&c)?;
+        self.known
+            .iter()
+            .find(|(p, other)| **p != c && card.pairs_with(other))
+            .map(|(p, _)| *p)
+    }
+
+    /// Pick a card that has never been seen.
+    fn explore(&self, exclude: Option<Vec2>) -> Option<Vec2> {
+        self.unseen.iter().copied().find(|c| Some(*c) != exclude)
+    }
+}
+
+/// The expected number of guesses the [`Solver`] takes to clear a shuffled
+/// board of `pairs` pairs, with no jokers or bombs. This is the best a
+/// player with perfect memory can hope for on average, give or take a
+/// fraction of a guess.
+pub fn expected_guesses(pairs: usize) -> f64 {
+    InfoSet::fresh(pairs).guesses_left()
+}
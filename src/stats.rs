@@ -0,0 +1,276 @@
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    io::{self, ErrorKind, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::board::Vec2;
+use crate::config::parse_size;
+use crate::error::{GameError, Result};
+#[cfg(feature = "history")]
+use crate::history::History;
+use crate::persistence::format::{self, FileFormat};
+use crate::profile;
+use crate::rating;
+
+/// The outcome of a single finished game.
+#[derive(Clone, Debug)]
+pub struct GameRecord {
+    /// Number of columns and rows of the board.
+    pub size: Vec2,
+    /// Number of guesses, including hint penalties.
+    pub guesses: i32,
+    /// Number of pairs on the board.
+    pub pairs: i32,
+    /// Time from the board being dealt to the last pair being found.
+    pub duration: Duration,
+    /// Whether the player cleared the board.
+    pub won: bool,
+    /// Whether the game was played with a limit that can make the player
+    /// lose.
+    pub limited: bool,
+    /// The positions of the cards turned over, once for every time.
+    pub picks: Vec<Vec2>,
+    /// The seed the board was shuffled with, if it was seeded.
+    pub seed: Option<u64>,
+    /// The kind of game, e.g. `classic` or `daily`.
+    pub mode: &'static str,
+}
+
+/// The layout of the statistics file.
+const FORMAT: FileFormat = FileFormat {
+    kind: "stats",
+    migrations: &[format::enveloped],
+};
+
+/// Statistics aggregated over all finished games.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    pub games_played: u32,
+    /// Games played with a limit that can make the player lose.
+    pub limited_games: u32,
+    /// Games with a limit that were won.
+    pub limited_wins: u32,
+    /// Guesses made in won games.
+    pub total_guesses: u64,
+    /// Pairs found in won games.
+    pub total_pairs: u64,
+    /// Fastest win in milliseconds, keyed by board size as `<x>x<y>`.
+    pub best_times: BTreeMap<String, u64>,
+    /// Number of times each position was turned over in row major order,
+    /// keyed by board size as `<x>x<y>`.
+    pub heatmaps: BTreeMap<String, Vec<u32>>,
+    /// Most [stars](`crate::rating::stars`) earned, keyed by board size as
+    /// `<x>x<y>`.
+    pub best_stars: BTreeMap<String, u8>,
+}
+
+impl Stats {
+    /// Add a finished game to the statistics. Returns `true` if it set a
+    /// new best time for its board size.
+    pub fn record(&mut self, game: &GameRecord) -> bool {
+        self.games_played += 1;
+        let cells = (game.size.x * game.size.y) as usize;
+        let heatmap = self.heatmaps.entry(size_key(game.size)).or_default();
+        heatmap.resize(cells, 0);
+        for c in &game.picks {
+            if let Some(count) =
+                heatmap.get_mut((c.y * game.size.x + c.x) as usize)
+            {
+                *count += 1;
+            }
+        }
+        if game.limited {
+            self.limited_games += 1;
+            if game.won {
+                self.limited_wins += 1;
+            }
+        }
+        if !game.won {
+            return false;
+        }
+
+        self.total_guesses += game.guesses as u64;
+        self.total_pairs += game.pairs as u64;
+
+        let stars = rating::stars(game.guesses, game.pairs as usize);
+        let best = self.best_stars.entry(size_key(game.size)).or_default();
+        *best = stars.max(*best);
+
+        let millis = game.duration.as_millis() as u64;
+        let best = self.best_times.entry(size_key(game.size)).or_insert(millis);
+        if millis <= *best {
+            *best = millis;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Share of games with limits that were won, if any were played.
+    pub fn win_rate(&self) -> Option<f64> {
+        if self.limited_games == 0 {
+            return None;
+        }
+        Some(self.limited_wins as f64 / self.limited_games as f64)
+    }
+
+    /// Average number of guesses needed to find a pair, if any were found.
+    pub fn guesses_per_pair(&self) -> Option<f64> {
+        if self.total_pairs == 0 {
+            return None;
+        }
+        Some(self.total_guesses as f64 / self.total_pairs as f64)
+    }
+
+    /// How many times each position of a board of the given size was turned
+    /// over, in row major order, if such a board was ever played.
+    pub fn heatmap(&self, size: Vec2) -> Option<&[u32]> {
+        self.heatmaps.get(&size_key(size)).map(Vec::as_slice)
+    }
+
+    /// The board sizes played, with their [heatmaps](`Stats::heatmap`).
+    pub fn heatmaps(&self) -> impl Iterator<Item = (Vec2, &[u32])> {
+        self.heatmaps.iter().filter_map(|(key, counts)| {
+            let size = parse_size(key).ok()?;
+            Some((size, counts.as_slice()))
+        })
+    }
+
+    /// The most stars earned on a board of the given size, if it was ever
+    /// cleared.
+    pub fn best_stars(&self, size: Vec2) -> Option<u8> {
+        self.best_stars.get(&size_key(size)).copied()
+    }
+
+    /// The fastest win on a board of the given size.
+    pub fn best_time(&self, size: Vec2) -> Option<Duration> {
+        self.best_times
+            .get(&size_key(size))
+            .map(|millis| Duration::from_millis(*millis))
+    }
+}
+
+/// Statistics backed by a file.
+pub struct StatsStore {
+    path: PathBuf,
+    pub stats: Stats,
+    /// Where every finished game is recorded as well, if anywhere.
+    #[cfg(feature = "history")]
+    history: Option<History>,
+}
+
+impl StatsStore {
+    /// Location of the statistics file:
+    /// `$XDG_DATA_HOME/card-matching/stats.json`, falling back to
+    /// `~/.local/share/card-matching/stats.json`.
+    pub fn default_path() -> Option<PathBuf> {
+        Some(data_dir()?.join("stats.json"))
+    }
+
+    /// Load the statistics stored at `path`. A missing file yields empty
+    /// statistics.
+    pub fn open(path: &Path) -> Result<StatsStore> {
+        let stats = match fs::read(path) {
+            Ok(bytes) => {
+                FORMAT.decode(&bytes).map_err(|e| stats_io(path, e))?
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => Stats::default(),
+            Err(e) => return Err(stats_io(path, e)),
+        };
+        Ok(StatsStore {
+            path: path.to_owned(),
+            stats,
+            #[cfg(feature = "history")]
+            history: None,
+        })
+    }
+
+    /// Record every finished game in `history` as well.
+    #[cfg(feature = "history")]
+    pub fn set_history(&mut self, history: History) {
+        self.history = Some(history);
+    }
+
+    /// The history of games recorded along with the statistics, if any.
+    #[cfg(feature = "history")]
+    pub fn history(&self) -> Option<&History> {
+        self.history.as_ref()
+    }
+
+    /// Add a finished game and save the statistics. Returns `true` if it
+    /// set a new best time for its board size.
+    pub fn record(&mut self, game: &GameRecord) -> Result<bool> {
+        let best = self.stats.record(game);
+        self.save()?;
+        #[cfg(feature = "history")]
+        if let Some(history) = &self.history {
+            history.record(game)?;
+        }
+        Ok(best)
+    }
+
+    /// Write the statistics back to their file.
+    pub fn save(&self) -> Result<()> {
+        let bytes = FORMAT.encode(&self.stats);
+        write_atomic(&self.path, &bytes).map_err(|e| stats_io(&self.path, e))
+    }
+}
+
+/// Directory for the files written by the game for the
+/// [current profile](`crate::profile`), within [`shared_data_dir`].
+pub fn data_dir() -> Option<PathBuf> {
+    Some(profile::namespace(shared_data_dir()?))
+}
+
+/// Directory for the files written by the game, shared by all profiles:
+/// `$XDG_DATA_HOME/card-matching`, falling back to
+/// `~/.local/share/card-matching`.
+pub fn shared_data_dir() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".local/share"),
+    };
+    Some(base.join("card-matching"))
+}
+
+/// Replace the contents of `path` so that readers see either the old or the
+/// new contents, never a partial write: the data goes to a temporary file in
+/// the same directory first, which is then renamed over the original.
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+
+    let mut file = fs::File::create(&tmp)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)
+}
+
+/// Format a duration as `m:ss`.
+pub fn format_duration(d: Duration) -> String {
+    let secs = d.as_secs();
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+fn size_key(size: Vec2) -> String {
+    format!("{}x{}", size.x, size.y)
+}
+
+fn stats_io(path: &Path, e: io::Error) -> GameError {
+    GameError::StatsIo {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+        source: Some(Arc::new(e)),
+    }
+}
@@ -0,0 +1,54 @@
+use crate::color::{ColorDepth, Rgb};
+
+/// The one place styled text is made. Every color and text attribute drawn
+/// by the game goes through here, so that turning styles off leaves plain
+/// text behind.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Style {
+    colors: Option<ColorDepth>,
+}
+
+impl Style {
+    /// No colors or attributes at all.
+    pub const PLAIN: Style = Style { colors: None };
+
+    /// Style text for a terminal that shows `colors`, or not at all
+    /// without them.
+    pub fn new(colors: Option<ColorDepth>) -> Style {
+        Style { colors }
+    }
+
+    /// Check if styles are drawn at all.
+    pub fn is_enabled(self) -> bool {
+        self.colors.is_some()
+    }
+
+    /// Draw `text` in `color`, if there is one.
+    pub fn paint(self, text: &str, color: Option<Rgb>) -> String {
+        match (color, self.colors) {
+            (Some(color), Some(depth)) => color.paint(text, depth),
+            _ => text.to_string(),
+        }
+    }
+
+    /// Draw `text` faint, e.g. for cards that are out of play.
+    pub fn dim(self, text: &str) -> String {
+        self.attribute(text, 2, 22)
+    }
+
+    /// Swap the color of `text` with the background, e.g. for cards that
+    /// were just picked.
+    pub fn inverse(self, text: &str) -> String {
+        self.attribute(text, 7, 27)
+    }
+
+    /// Wrap `text` in the escape codes that turn an attribute `on` and
+    /// back `off`.
+    fn attribute(self, text: &str, on: u8, off: u8) -> String {
+        if !self.is_enabled() {
+            return text.to_string();
+        }
+        let esc = 27 as char;
+        format!("{esc}[{}m{}{esc}[{}m", on, text, off)
+    }
+}
@@ -0,0 +1,175 @@
+use std::{
+    env,
+    io::{self, IsTerminal, Read, Write},
+    process::{Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use terminal_size::{Height, Width};
+
+use crate::color::ColorDepth;
+use crate::graphics::Graphics;
+
+/// What the terminal the game is drawn on can show.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Capabilities {
+    /// Escape codes for moving the cursor and clearing the screen work.
+    pub ansi: bool,
+    /// Characters beyond ASCII, such as the card symbols, are drawn.
+    pub unicode: bool,
+    /// The colors the terminal can show, if any.
+    pub colors: Option<ColorDepth>,
+    /// The protocol for drawing images, if the terminal has one.
+    pub graphics: Option<Graphics>,
+}
+
+impl Capabilities {
+    /// Find out what the terminal can do from the environment. Output that
+    /// doesn't go to a terminal, e.g. because it is piped into a file, gets
+    /// no escape codes at all, and setting
+    /// [`NO_COLOR`](https://no-color.org) turns colors off.
+    pub fn detect() -> Capabilities {
+        let dumb = env::var("TERM").is_ok_and(|term| term == "dumb");
+        let ansi = io::stdout().is_terminal() && !dumb;
+        Capabilities {
+            ansi,
+            unicode: cfg!(windows) || utf8_locale(),
+            colors: (ansi && !no_color()).then(ColorDepth::detect),
+            graphics: ansi.then(Graphics::detect).flatten(),
+        }
+    }
+}
+
+/// Check if the user asked for no colors by setting `NO_COLOR` to anything
+/// but an empty string.
+fn no_color() -> bool {
+    env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
+
+/// Check if the locale asks for UTF-8, e.g. `en_US.UTF-8`.
+fn utf8_locale() -> bool {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default()
+        .to_lowercase();
+    locale.contains("utf-8") || locale.contains("utf8")
+}
+
+/// Cleared if escape codes would end up as garbage in the output.
+static ANSI: AtomicBool = AtomicBool::new(true);
+
+/// Allow or forbid the escape codes for moving the cursor and clearing
+/// the screen, e.g. after [detecting](`Capabilities::detect`) that the
+/// output doesn't go to a terminal.
+pub fn set_ansi(ansi: bool) {
+    ANSI.store(ansi, Ordering::SeqCst);
+}
+
+/// Check if escape codes may be written.
+pub fn is_ansi() -> bool {
+    ANSI.load(Ordering::SeqCst)
+}
+
+/// The number of columns and rows of the terminal, if the output goes to
+/// one.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn size() -> Option<(u16, u16)> {
+    let (Width(columns), Height(rows)) = terminal_size::terminal_size()?;
+    Some((columns, rows))
+}
+
+/// There is no terminal in a browser.
+#[cfg(target_arch = "wasm32")]
+pub fn size() -> Option<(u16, u16)> {
+    None
+}
+
+/// Set while the game is drawn on the alternate screen.
+static ALTERNATE_SCREEN: AtomicBool = AtomicBool::new(false);
+
+/// Switch to the terminal's alternate screen, so that the game doesn't
+/// overwrite the scrollback. [`restore`] switches back.
+pub fn enter_alternate_screen() {
+    if !is_ansi() {
+        return;
+    }
+    let mut stdout = io::stdout();
+    let _ = write!(stdout, "{esc}[?1049h{esc}[H", esc = 27 as char);
+    let _ = stdout.flush();
+    ALTERNATE_SCREEN.store(true, Ordering::SeqCst);
+}
+
+/// The `stty` settings to go back to while [`read_key`] has the terminal
+/// out of line mode.
+static SAVED_MODE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Put the terminal back the way the game found it: leave the alternate
+/// screen, reset colors, show the cursor and move to a fresh line. Also
+/// switches back to line mode if the game exits, e.g. on Ctrl+C, while
+/// [`read_key`] waits for a key. Safe to call more than once.
+pub fn restore() {
+    restore_mode();
+    let mut stdout = io::stdout();
+    if ALTERNATE_SCREEN.swap(false, Ordering::SeqCst) {
+        let _ = write!(stdout, "{esc}[?1049l", esc = 27 as char);
+    }
+    if is_ansi() {
+        let _ = write!(stdout, "{esc}[0m{esc}[?25h", esc = 27 as char);
+    }
+    let _ = writeln!(stdout);
+    let _ = stdout.flush();
+}
+
+/// Read a single key press from stdin without waiting for <Enter>, as the
+/// text it sends, or `None` if no key was pressed within `timeout`. Fails
+/// if stdin isn't a terminal that `stty` can switch out of line mode.
+pub fn read_key(timeout: Option<Duration>) -> io::Result<Option<String>> {
+    let saved = stty(&["-g"])?;
+    *SAVED_MODE.lock().unwrap_or_else(|e| e.into_inner()) =
+        Some(saved.trim().to_owned());
+    // The terminal waits for up to `time` tenths of a second
+    let (min, time) = match timeout {
+        Some(t) => ("0", (t.as_millis() / 100).clamp(1, 255).to_string()),
+        None => ("1", "0".to_owned()),
+    };
+    if let Err(e) = stty(&["-icanon", "-echo", "min", min, "time", &time]) {
+        restore_mode();
+        return Err(e);
+    }
+    // Enough for the escape sequence of any key
+    let mut buf = [0; 16];
+    let read = io::stdin().read(&mut buf);
+    restore_mode();
+    let n = read?;
+    Ok((n > 0).then(|| String::from_utf8_lossy(&buf[..n]).into_owned()))
+}
+
+/// Switch back to the settings saved by [`read_key`], if it changed them.
+fn restore_mode() {
+    let saved = SAVED_MODE.lock().unwrap_or_else(|e| e.into_inner()).take();
+    if let Some(saved) = saved {
+        let _ = stty(&[&saved]);
+    }
+}
+
+/// Run `stty` on the terminal of stdin, returning what it printed.
+fn stty(args: &[&str]) -> io::Result<String> {
+    if !io::stdin().is_terminal() {
+        return Err(io::ErrorKind::Unsupported.into());
+    }
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other("stty failed"));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,104 @@
+use crate::color::Rgb;
+use crate::error::{GameError, Result};
+
+/// The colors of the parts of the screen. A part without a color is drawn
+/// in the terminal's default one.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Theme {
+    /// The name the theme is picked by.
+    pub name: &'static str,
+    /// The lines of the grid around the cells.
+    pub board: Option<Rgb>,
+    /// The background of the cards just picked, which are drawn in inverse
+    /// video in their own color without one.
+    pub highlight: Option<Rgb>,
+    /// Error messages, and the flash of mismatched cards.
+    pub error: Option<Rgb>,
+    /// The score and lives above the board.
+    pub score: Option<Rgb>,
+    /// Set if every symbol is drawn in a [color of its
+    /// own](`crate::color::card_color`).
+    pub card_colors: bool,
+}
+
+impl Theme {
+    /// Cards in their own colors and red errors, on the terminal's colors.
+    pub const DEFAULT: Theme = Theme {
+        name: "default",
+        board: None,
+        highlight: None,
+        error: Some(Rgb::RED),
+        score: None,
+        card_colors: true,
+    };
+
+    /// The accent colors of the Solarized palette.
+    pub const SOLARIZED: Theme = Theme {
+        name: "solarized",
+        board: Some(Rgb(88, 110, 117)),
+        highlight: Some(Rgb(181, 137, 0)),
+        error: Some(Rgb(220, 50, 47)),
+        score: Some(Rgb(42, 161, 152)),
+        card_colors: true,
+    };
+
+    /// Bright colors only, for telling the parts apart at a glance. Cards
+    /// are drawn in the terminal's default color, as some of theirs are
+    /// dark.
+    pub const HIGH_CONTRAST: Theme = Theme {
+        name: "high-contrast",
+        board: Some(Rgb(255, 255, 255)),
+        highlight: Some(Rgb(255, 255, 0)),
+        error: Some(Rgb(255, 0, 0)),
+        score: Some(Rgb(0, 255, 255)),
+        card_colors: false,
+    };
+
+    /// No colors at all. Picked and matched cards still stand out in
+    /// inverse video and faint text.
+    pub const MONOCHROME: Theme = Theme {
+        name: "monochrome",
+        board: None,
+        highlight: None,
+        error: None,
+        score: None,
+        card_colors: false,
+    };
+
+    /// Every theme, in the order `:theme` cycles through them.
+    pub const ALL: [Theme; 4] = [
+        Theme::DEFAULT,
+        Theme::SOLARIZED,
+        Theme::HIGH_CONTRAST,
+        Theme::MONOCHROME,
+    ];
+
+    /// The theme called `name`, ignoring case.
+    pub fn named(name: &str) -> Result<Theme> {
+        let name = name.trim().to_lowercase();
+        Theme::ALL
+            .into_iter()
+            .find(|theme| theme.name == name)
+            .ok_or_else(|| GameError::UnknownTheme {
+                name,
+                themes: Theme::names(),
+            })
+    }
+
+    /// The names of every theme, separated by commas.
+    pub fn names() -> String {
+        Theme::ALL.map(|theme| theme.name).join(", ")
+    }
+
+    /// The theme after this one in [`Theme::ALL`], wrapping around.
+    pub fn next(self) -> Theme {
+        let index = Theme::ALL.iter().position(|theme| *theme == self);
+        Theme::ALL[index.map_or(0, |i| (i + 1) % Theme::ALL.len())]
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme::DEFAULT
+    }
+}
@@ -0,0 +1,121 @@
+use std::time::Duration;
+
+use crate::board::Vec2;
+
+/// Board sizes played in a tournament, from the first round to the last.
+const ROUNDS: [Vec2; 4] = [
+    Vec2 { x: 4, y: 3 },
+    Vec2 { x: 4, y: 4 },
+    Vec2 { x: 6, y: 4 },
+    Vec2 { x: 6, y: 6 },
+];
+
+/// Points for finding every pair of a round without a single mismatch.
+const ACCURACY_POINTS: u64 = 1000;
+/// Points for clearing a round within par time.
+const SPEED_POINTS: u64 = 500;
+/// Seconds per pair a round should be cleared in to earn every speed
+/// point.
+const PAR_SECONDS_PER_PAIR: u64 = 4;
+
+/// How a single round of a tournament went.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RoundResult {
+    /// Size of the board.
+    pub size: Vec2,
+    /// Number of pairs on the board.
+    pub pairs: i32,
+    /// Number of guesses, including hint penalties.
+    pub guesses: i32,
+    /// Time taken to clear the board.
+    pub time: Duration,
+}
+
+impl RoundResult {
+    /// Tournament points for the round. Accuracy points are scaled down by
+    /// the guesses beyond one per pair, and speed points by the time beyond
+    /// par.
+    pub fn score(&self) -> u64 {
+        let pairs = self.pairs.max(1) as u64;
+        let guesses = (self.guesses.max(0) as u64).max(pairs);
+        let accuracy = ACCURACY_POINTS * pairs / guesses;
+
+        let par = Duration::from_secs(pairs * PAR_SECONDS_PER_PAIR);
+        let time = self.time.max(par);
+        let speed = SPEED_POINTS as u128 * par.as_millis() / time.as_millis();
+
+        accuracy + speed as u64
+    }
+}
+
+/// A fixed series of boards of increasing size, whose results add up to a
+/// final score.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Tournament {
+    /// Size of the board of every round.
+    rounds: Vec<Vec2>,
+    /// Results of the rounds played so far.
+    results: Vec<RoundResult>,
+}
+
+impl Default for Tournament {
+    fn default() -> Tournament {
+        Tournament::new(ROUNDS.to_vec())
+    }
+}
+
+impl Tournament {
+    /// A tournament played on boards of the given sizes, in order.
+    pub fn new(rounds: Vec<Vec2>) -> Tournament {
+        Tournament {
+            rounds,
+            results: Vec::new(),
+        }
+    }
+
+    /// Size of the board of the round to play next, or `None` once every
+    /// round was played.
+    pub fn next_round(&self) -> Option<Vec2> {
+        self.rounds.get(self.results.len()).copied()
+    }
+
+    /// Number of rounds.
+    pub fn len(&self) -> usize {
+        self.rounds.len()
+    }
+
+    /// Check if there are no rounds at all.
+    pub fn is_empty(&self) -> bool {
+        self.rounds.is_empty()
+    }
+
+    /// Check if every round was played.
+    pub fn is_over(&self) -> bool {
+        self.next_round().is_none()
+    }
+
+    /// Record the result of the round just played.
+    pub fn record(&mut self, result: RoundResult) {
+        self.results.push(result);
+    }
+
+    /// Results of the rounds played so far.
+    pub fn results(&self) -> &[RoundResult] {
+        &self.results
+    }
+
+    /// Total guesses over the rounds played so far.
+    pub fn guesses(&self) -> i32 {
+        self.results.iter().map(|r| r.guesses).sum()
+    }
+
+    /// Total time over the rounds played so far.
+    pub fn time(&self) -> Duration {
+        self.results.iter().map(|r| r.time).sum()
+    }
+
+    /// Total points over the rounds played so far.
+    pub fn score(&self) -> u64 {
+        self.results.iter().map(RoundResult::score).sum()
+    }
+}
@@ -0,0 +1,232 @@
+//! What is shown of a game, apart from how it is drawn. A
+//! [`GameView`] is a plain snapshot taken by
+//! [`Game::view`](crate::game::Game::view): the board, the score and the
+//! ids of the messages and prompt on screen. The text of those ids is
+//! looked up here, so other frontends can word them their own way.
+
+use crate::board::{Card, Vec2};
+use crate::flow::GameState;
+use crate::screen::Frame;
+use crate::t;
+
+/// What a cell of the board shows.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CellStatus {
+    /// A gap in the shape of the board, which never holds a card
+    Hole,
+    FaceDown,
+    /// Face up for everyone to see, e.g. while memorizing the board, after
+    /// the game is lost, or during a peek
+    FaceUp,
+    /// Turned over during the current guess
+    Revealed,
+    /// Part of a pair that was found
+    Matched,
+}
+
+/// A cell of the board.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Cell {
+    /// The card in the cell, or `None` while it is face down.
+    pub card: Option<Card>,
+    pub status: CellStatus,
+}
+
+/// How the player is doing on the current board.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ScoreView {
+    pub points: u64,
+    /// Pairs found in a row up to the last guess.
+    pub streak: u32,
+    pub guesses: i32,
+    pub pairs_found: usize,
+    pub pairs: usize,
+    pub hints: i32,
+    /// Incorrect guesses left before the game is lost, if limited.
+    pub lives: Option<u32>,
+}
+
+/// Feedback about what just happened.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Message {
+    CardsShuffled,
+    PairsFaded {
+        count: usize,
+    },
+    CorrectGuess,
+    PointsEarned {
+        points: u64,
+        streak: u32,
+    },
+    /// A power-up was earned, named after its command.
+    PowerUpEarned {
+        name: &'static str,
+    },
+    IncorrectGuess,
+    TimeUp,
+    /// The turn of a networked game ran out of time, and passed to the
+    /// other player.
+    TurnExpired {
+        yours: bool,
+    },
+    BombPenalty {
+        points: u64,
+    },
+    BombShuffle,
+    OutOfLives,
+    TutorialDone,
+}
+
+impl Message {
+    pub fn text(&self) -> String {
+        match self {
+            Message::CardsShuffled => t!("cards-shuffled"),
+            Message::PairsFaded { count } => t!("pairs-faded", count = count),
+            Message::CorrectGuess => t!("correct-guess"),
+            Message::PointsEarned { points, streak } => {
+                t!("points-earned", points = points, streak = streak)
+            }
+            Message::PowerUpEarned { name } => {
+                t!("power-up-earned", name = name)
+            }
+            Message::IncorrectGuess => t!("incorrect-guess"),
+            Message::TimeUp => t!("time-up"),
+            Message::TurnExpired { yours: true } => t!("turn-expired-yours"),
+            Message::TurnExpired { yours: false } => t!("turn-expired-theirs"),
+            Message::BombPenalty { points } => {
+                t!("bomb-penalty", points = points)
+            }
+            Message::BombShuffle => t!("bomb-shuffle"),
+            Message::OutOfLives => t!("out-of-lives"),
+            Message::TutorialDone => t!("tutorial-done"),
+        }
+    }
+}
+
+/// What the player is asked for next.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Prompt {
+    Welcome,
+    /// Pick one of `max` entries of the board size menu, or <Enter> for
+    /// the `choice` called `name`.
+    SizeMenu {
+        max: usize,
+        choice: usize,
+        name: String,
+    },
+    SetDimensions,
+    Memorize,
+    Preview {
+        seconds: u64,
+    },
+    /// Pick a card, with the seconds left for the guess if limited.
+    PickCard {
+        seconds_left: Option<u128>,
+    },
+    /// Wait for the opponent's move, mentioning the chat if `can_chat`.
+    WaitingForOpponent {
+        can_chat: bool,
+    },
+    PlayAgain,
+    PlayAgainAfterDefeat,
+    PressEnterToContinue,
+    PressEnterToExit,
+    /// Pick a level up to `max`, or <Enter> for `level`.
+    LevelSelect {
+        level: usize,
+        max: usize,
+    },
+    LevelStart,
+    /// Confirm quitting, offering to save the board if `can_save` is set.
+    ConfirmQuit {
+        can_save: bool,
+    },
+    ConfirmRestart,
+    ConfirmRematch,
+    ConfirmResize,
+    ConfirmRecover,
+    Settings {
+        max: usize,
+    },
+}
+
+impl Prompt {
+    pub fn lines(&self) -> Vec<String> {
+        let line = match self {
+            Prompt::Welcome => {
+                return vec![t!("welcome"), t!("welcome-settings")];
+            }
+            Prompt::SizeMenu { max, choice, name } => {
+                t!("size-menu-prompt", max = max, choice = choice, name = name,)
+            }
+            Prompt::SetDimensions => t!("set-dimensions"),
+            Prompt::Memorize => t!("memorize"),
+            Prompt::Preview { seconds } => t!("preview", seconds = seconds),
+            Prompt::PickCard { seconds_left } => match seconds_left {
+                Some(seconds) => {
+                    let left = t!("time-left", seconds = seconds);
+                    format!("{} ({})", t!("pick-card"), left)
+                }
+                None => t!("pick-card"),
+            },
+            Prompt::WaitingForOpponent { can_chat: false } => {
+                t!("waiting-for-opponent")
+            }
+            Prompt::WaitingForOpponent { can_chat: true } => {
+                return vec![t!("waiting-for-opponent"), t!("waiting-chat")];
+            }
+            Prompt::PlayAgain => {
+                return vec![t!("play-again"), t!("play-again-commands")];
+            }
+            Prompt::PlayAgainAfterDefeat => {
+                return vec![
+                    t!("play-again-after-defeat"),
+                    t!("play-again-commands"),
+                ];
+            }
+            Prompt::PressEnterToContinue => t!("press-enter-to-continue"),
+            Prompt::PressEnterToExit => t!("press-enter-to-exit"),
+            Prompt::LevelSelect { level, max } => {
+                t!("level-select", level = level, max = max)
+            }
+            Prompt::LevelStart => t!("level-start"),
+            Prompt::ConfirmQuit { can_save: true } => t!("confirm-quit-save"),
+            Prompt::ConfirmQuit { can_save: false } => t!("confirm-quit"),
+            Prompt::ConfirmRestart => t!("confirm-restart"),
+            Prompt::ConfirmRematch => t!("confirm-rematch"),
+            Prompt::ConfirmResize => t!("confirm-resize"),
+            Prompt::ConfirmRecover => t!("confirm-recover"),
+            Prompt::Settings { max } => t!("settings-prompt", max = max),
+        };
+        vec![line]
+    }
+}
+
+/// A snapshot of everything shown of a game.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct GameView {
+    pub state: GameState,
+    /// Number of columns and rows.
+    pub size: Vec2,
+    /// The cells of the board in row major order.
+    pub cells: Vec<Cell>,
+    pub score: ScoreView,
+    pub messages: Vec<Message>,
+    pub prompt: Option<Prompt>,
+}
+
+impl GameView {
+    /// Draw the messages, one per line.
+    pub fn draw_messages(&self, out: &mut Frame) {
+        for message in &self.messages {
+            out.line(message.text());
+        }
+    }
+
+    /// Draw the prompt, if any.
+    pub fn draw_prompt(&self, out: &mut Frame) {
+        for line in self.prompt.iter().flat_map(Prompt::lines) {
+            out.line(line);
+        }
+    }
+}
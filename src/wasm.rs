@@ -0,0 +1,59 @@
+//! Bindings to play the game from JavaScript, when built for WebAssembly
+//! with `wasm-bindgen`, e.g. with `wasm-pack build --target web`.
+//!
+//! A game is driven the same way as with `play --json`: it takes the
+//! [commands](`crate::protocol::Command`) as JSON and answers with a
+//! [snapshot](`crate::protocol::Snapshot`) of its state as JSON. Nothing is
+//! drawn, saved or counted towards the statistics.
+
+use wasm_bindgen::prelude::*;
+
+use crate::board::Vec2;
+use crate::game::Game;
+use crate::protocol::{self, Command};
+
+/// A game played from JavaScript.
+#[wasm_bindgen]
+pub struct WasmGame {
+    game: Game,
+}
+
+/// Start guessing on a board of `columns` by `rows` cards right away,
+/// shuffled with `seed` if given.
+#[wasm_bindgen]
+pub fn new_game(
+    columns: i32,
+    rows: i32,
+    seed: Option<u64>,
+) -> Result<WasmGame, JsError> {
+    let mut game = match seed {
+        Some(seed) => Game::with_seed(seed),
+        None => Game::new(),
+    };
+    game.start(Vec2 {
+        x: columns,
+        y: rows,
+    })
+    .map_err(|e| JsError::new(&e.as_string()))?;
+    Ok(WasmGame { game })
+}
+
+#[wasm_bindgen]
+impl WasmGame {
+    /// Reveal the card at the 1-based position `x`, `y`, and return the
+    /// snapshot after it.
+    pub fn guess(&mut self, x: i32, y: i32) -> String {
+        protocol::apply(&mut self.game, &Command::Reveal { x, y }).to_line()
+    }
+
+    /// Apply a command given as JSON, e.g. `{"cmd":"continue"}`, and return
+    /// the snapshot after it.
+    pub fn command(&mut self, json: &str) -> String {
+        protocol::respond(&mut self.game, json).to_line()
+    }
+
+    /// The snapshot of the game as it is.
+    pub fn state_json(&self) -> String {
+        self.game.snapshot().to_line()
+    }
+}
@@ -0,0 +1,13 @@
+//! Tests of judging guesses by what the player had seen.
+
+use rs_card_matching::analysis::known_pair;
+use rs_card_matching::board::{Card, Vec2};
+
+#[test]
+fn known_pair_skips_jokers_and_bombs() {
+    let at = |x| Vec2 { x, y: 0 };
+    let seen = [(at(0), Card::JOKER), (at(1), Card(0)), (at(2), Card::BOMB)];
+    assert_eq!(known_pair(&seen), None);
+    let seen = [(at(0), Card(0)), (at(1), Card::JOKER), (at(2), Card(0))];
+    assert_eq!(known_pair(&seen), Some((at(0), at(2))));
+}
@@ -0,0 +1,40 @@
+//! Tests of the board codes players share to play the same board.
+
+use rs_card_matching::board::Vec2;
+use rs_card_matching::code::BoardCode;
+use rs_card_matching::config::Difficulty;
+use rs_card_matching::game::Game;
+use rs_card_matching::grid::Layout;
+
+/// A board of 2x1 cells with the given layers.
+fn code(layers: Vec<Vec<Option<u32>>>) -> BoardCode {
+    BoardCode {
+        seed: None,
+        difficulty: Difficulty::Normal,
+        layout: Layout::Square,
+        hint_penalty: 0,
+        min_pair_distance: 1,
+        size: Vec2 { x: 2, y: 1 },
+        layers,
+    }
+}
+
+#[test]
+fn layers() {
+    let code = code(vec![vec![Some(0), Some(0)], vec![Some(1), Some(1)]]);
+    let decoded = BoardCode::decode(&code.encode()).unwrap();
+    assert_eq!(decoded, code);
+    assert!(Game::with_seed(1).start_from_code(&decoded).is_ok());
+}
+
+#[test]
+fn pair_split_across_layers() {
+    let code = BoardCode::decode("AQEAAAABAgECAQIBAg").unwrap();
+    assert!(Game::with_seed(1).start_from_code(&code).is_err());
+}
+
+#[test]
+fn unknown_card() {
+    let code = code(vec![vec![Some(u32::MAX), Some(u32::MAX)]]);
+    assert!(BoardCode::decode(&code.encode()).is_err());
+}
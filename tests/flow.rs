@@ -0,0 +1,83 @@
+//! Tests of the transition table, and of games refusing to move on when
+//! it has no transition for what is asked.
+
+use std::env;
+
+use rs_card_matching::board::Vec2;
+use rs_card_matching::campaign::Campaign;
+use rs_card_matching::error::GameError;
+use rs_card_matching::flow::{
+    self, Effect, GameState, Target, Transition, Trigger, TRANSITIONS,
+};
+use rs_card_matching::game::Game;
+
+/// The states a transition can lead to, leaving out the one it resumes.
+fn targets(transition: &Transition) -> Vec<GameState> {
+    match transition.to {
+        Target::To(state) => vec![state],
+        Target::FirstTurn => {
+            vec![GameState::Memorize, GameState::Preview, GameState::Guess]
+        }
+        Target::Resume => Vec::new(),
+    }
+}
+
+#[test]
+fn every_state_reachable() {
+    let mut reached = vec![GameState::Welcome];
+    let mut i = 0;
+    while let Some(&from) = reached.get(i) {
+        let next = TRANSITIONS.iter().filter(|t| t.from.contains(&from));
+        for state in next.flat_map(targets) {
+            if !reached.contains(&state) {
+                reached.push(state);
+            }
+        }
+        i += 1;
+    }
+    for state in GameState::ALL {
+        assert!(reached.contains(&state), "{} unreachable", state.name());
+    }
+}
+
+#[test]
+fn every_state_exits() {
+    for state in GameState::ALL {
+        let to = flow::transition(state, Trigger::Exit).map(|t| t.to);
+        assert_eq!(to, Some(Target::To(GameState::Exit)));
+    }
+}
+
+#[test]
+fn resumed_states_remember() {
+    let resumed = TRANSITIONS
+        .iter()
+        .filter(|t| t.to == Target::Resume)
+        .flat_map(|t| t.from);
+    for state in resumed {
+        for t in TRANSITIONS.iter().filter(|t| targets(t).contains(state)) {
+            assert!(t.effects.contains(&Effect::Remember), "{:?}", t);
+        }
+    }
+}
+
+#[test]
+fn deal_in_progress() {
+    let mut game = Game::with_seed(1);
+    game.start(Vec2 { x: 2, y: 2 }).unwrap();
+    let result = game.start(Vec2 { x: 4, y: 3 });
+    assert!(matches!(result, Err(GameError::InvalidState { .. })));
+    assert_eq!(game.state(), GameState::Guess);
+    assert_eq!(game.snapshot().size, [2, 2]);
+}
+
+#[test]
+fn campaign_in_progress() {
+    let path = env::temp_dir().join("card-matching-no-campaign.json");
+    let campaign = Campaign::open(&path).unwrap();
+    let mut game = Game::with_seed(1);
+    game.start(Vec2 { x: 2, y: 2 }).unwrap();
+    let result = game.start_campaign(campaign);
+    assert!(matches!(result, Err(GameError::InvalidState { .. })));
+    assert_eq!(game.state(), GameState::Guess);
+}
@@ -0,0 +1,34 @@
+//! Tests of the JSON protocol that bots play through.
+
+use rs_card_matching::game::Game;
+use rs_card_matching::{i18n, protocol, terminal};
+
+fn game() -> Game {
+    terminal::set_ansi(false);
+    i18n::set_locale(Some("en"));
+    let mut game = Game::with_seed(1);
+    game.capture_frames();
+    game
+}
+
+#[test]
+fn start() {
+    let mut game = game();
+    let snapshot = protocol::respond(&mut game, r#"{"cmd":"continue"}"#);
+    assert_eq!(snapshot.state, "size_menu");
+    let snapshot =
+        protocol::respond(&mut game, r#"{"cmd":"start","x":2,"y":2}"#);
+    assert_eq!(snapshot.error, None);
+    assert_eq!(snapshot.state, "guess");
+    assert_eq!(snapshot.size, [2, 2]);
+}
+
+#[test]
+fn start_invalid() {
+    let mut game = game();
+    protocol::respond(&mut game, r#"{"cmd":"continue"}"#);
+    let snapshot =
+        protocol::respond(&mut game, r#"{"cmd":"start","x":0,"y":2}"#);
+    assert_eq!(snapshot.state, "size_menu");
+    assert!(snapshot.error_code.is_some());
+}
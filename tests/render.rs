@@ -0,0 +1,113 @@
+//! Snapshot tests of the frames a game renders, captured in memory.
+
+use rs_card_matching::board::Vec2;
+use rs_card_matching::game::Game;
+use rs_card_matching::{i18n, terminal};
+
+/// A game with a reproducible board, drawn without colors or Unicode so
+/// that the frames are plain text.
+fn game() -> Game {
+    terminal::set_ansi(false);
+    i18n::set_locale(Some("en"));
+    let mut game = Game::with_seed(1);
+    game.set_unicode(false);
+    game.set_reduced_motion(true);
+    game.capture_frames();
+    game
+}
+
+fn play(game: &mut Game, inputs: &[&str]) {
+    for input in inputs {
+        game.set_input(input.to_string());
+        game.update();
+    }
+}
+
+fn frame(game: &mut Game) -> String {
+    game.render();
+    game.take_frames().pop().expect("a frame was rendered")
+}
+
+#[test]
+fn welcome() {
+    let mut game = game();
+    assert_eq!(
+        frame(&mut game),
+        concat!(
+            "Welcome! Press <Enter> to begin.\n",
+            "Type :settings to change the settings.\n",
+        )
+    );
+}
+
+#[test]
+fn size_menu() {
+    let mut game = game();
+    play(&mut game, &[""]);
+    assert_eq!(
+        frame(&mut game),
+        concat!(
+            "Pick a board size:\n",
+            "  1. Easy      4x3\n",
+            "  2. Medium    6x4\n",
+            "  3. Hard      8x6\n",
+            "  4. Expert  10x10\n",
+            "  5. Custom\n",
+            "\n",
+            "Type a number (1-5), or press <Enter> for 2. Medium\n",
+        )
+    );
+}
+
+#[test]
+fn fresh_board() {
+    let mut game = game();
+    game.start(Vec2 { x: 4, y: 3 }).unwrap();
+    assert_eq!(
+        frame(&mut game),
+        concat!(
+            " Solo | Normal | 0:00 | Pairs left: 6/6 \n",
+            "Guesses: 0 | Correct guesses: 0\n",
+            "\n",
+            "   a   b   c   d  \n",
+            "   1   2   3   4  \n",
+            "  +---+---+---+---+\n",
+            "1 |#  |#  |#  |#  |\n",
+            "  +---+---+---+---+\n",
+            "2 |#  |#  |#  |#  |\n",
+            "  +---+---+---+---+\n",
+            "3 |#  |#  |#  |#  |\n",
+            "  +---+---+---+---+\n",
+            "\n",
+            "Board code: AQEAAQAAAAAAAAABAQEEAwGGgICAAoSAgIACg4CAgAKCgICAAoWAgIAC",
+            "hICAgAKFgICAAoKAgIACgYCAgAKBgICAAoaAgIACg4CAgAI\n",
+            "Pick a card (x, y or e.g. c4), or type `hint`\n",
+        )
+    );
+}
+
+#[test]
+fn mismatch() {
+    let mut game = game();
+    game.start(Vec2 { x: 4, y: 3 }).unwrap();
+    play(&mut game, &["a1", "b1"]);
+    assert_eq!(
+        frame(&mut game),
+        concat!(
+            " Solo | Normal | 0:00 | Pairs left: 6/6 \n",
+            "Guesses: 0 | Correct guesses: 0\n",
+            "\n",
+            "   a   b   c   d      Last guesses\n",
+            "   1   2   3   4      1. a1 F, b1 D: miss\n",
+            "  +---+===+---+---+\n",
+            "1 |F <|D <|#  |#  |\n",
+            "  +---+===+---+---+\n",
+            "2 |#  |#  |#  |#  |\n",
+            "  +---+---+---+---+\n",
+            "3 |#  |#  |#  |#  |\n",
+            "  +---+---+---+---+\n",
+            "\n",
+            "Try again\n",
+        )
+    );
+}
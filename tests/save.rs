@@ -0,0 +1,103 @@
+//! Tests of loading the game saved when quitting.
+
+use std::{collections::BTreeMap, env, fs, process};
+
+use rs_card_matching::board::Vec2;
+use rs_card_matching::code::BoardCode;
+use rs_card_matching::config::Difficulty;
+use rs_card_matching::error::{GameError, Result};
+use rs_card_matching::flow::GameState;
+use rs_card_matching::game::Game;
+use rs_card_matching::grid::Layout;
+use rs_card_matching::keymap::Keymap;
+use rs_card_matching::save::SavedGame;
+
+/// A game on a board of a single pair, with nothing matched yet.
+fn saved() -> SavedGame {
+    let code = BoardCode {
+        seed: None,
+        difficulty: Difficulty::Normal,
+        layout: Layout::Square,
+        hint_penalty: 0,
+        min_pair_distance: 1,
+        size: Vec2 { x: 2, y: 1 },
+        layers: vec![vec![Some(0), Some(0)]],
+    };
+    SavedGame {
+        board: code.encode(),
+        layers: code.layers.clone(),
+        depth: vec![0, 0],
+        discovered: vec![false, false],
+        guesses: 0,
+        hints: 0,
+        mismatches: 0,
+        turns: 0,
+        points: 0,
+        streak: 0,
+        best_streak: 0,
+        elapsed_ms: 0,
+    }
+}
+
+/// Store `saved` in a file of its own and load it back.
+fn reload(saved: &SavedGame, name: &str) -> Result<Option<SavedGame>> {
+    let file = format!("card-matching-{}-{}.json", process::id(), name);
+    let path = env::temp_dir().join(file);
+    saved.store(&path)?;
+    let loaded = SavedGame::load(&path);
+    let _ = fs::remove_file(&path);
+    loaded
+}
+
+#[test]
+fn valid() {
+    let saved = saved();
+    assert_eq!(reload(&saved, "valid").unwrap(), Some(saved));
+}
+
+#[test]
+fn depth_out_of_range() {
+    let mut saved = saved();
+    saved.depth[1] = 1;
+    let loaded = reload(&saved, "depth");
+    assert!(matches!(loaded, Err(GameError::SaveIo { .. })));
+}
+
+#[test]
+fn unknown_card() {
+    let mut saved = saved();
+    saved.layers[0][0] = Some(u32::MAX);
+    let loaded = reload(&saved, "card");
+    assert!(matches!(loaded, Err(GameError::SaveIo { .. })));
+}
+
+#[test]
+fn save_key_reserved() {
+    let bindings = BTreeMap::from([("settings".to_owned(), "s".to_owned())]);
+    let keymap = Keymap::new(&bindings);
+    assert!(matches!(keymap, Err(GameError::InvalidConfig { .. })));
+}
+
+#[test]
+fn save_with_keys_rebound() {
+    let file = format!("card-matching-{}-quit.json", process::id());
+    let path = env::temp_dir().join(file);
+    let bindings = BTreeMap::from([
+        ("quit".to_owned(), "x".to_owned()),
+        ("settings".to_owned(), "q".to_owned()),
+    ]);
+    let mut game = Game::with_seed(1);
+    game.set_keymap(Keymap::new(&bindings).unwrap());
+    game.set_save_file(Some(path.clone()));
+    game.start(Vec2 { x: 2, y: 2 }).unwrap();
+    for (input, state) in
+        [("x", GameState::ConfirmQuit), ("s", GameState::Exit)]
+    {
+        game.set_input(input.to_owned());
+        game.update();
+        assert_eq!(game.state(), state);
+    }
+    let loaded = SavedGame::load(&path);
+    let _ = fs::remove_file(&path);
+    assert!(loaded.unwrap().is_some());
+}
@@ -0,0 +1,67 @@
+//! Tests of watching a room of a lobby, with states sent by the lobby.
+
+use std::{
+    io::{self, Write},
+    net::TcpListener,
+    thread,
+};
+
+use rs_card_matching::board::Vec2;
+use rs_card_matching::game::Game;
+use rs_card_matching::net::{Cell, Connection, Message, RoomState};
+
+/// A room of a board of two pairs, the first of which was found.
+fn state() -> RoomState {
+    RoomState {
+        size: Vec2 { x: 2, y: 2 },
+        cells: vec![Cell::Found(0), Cell::Found(0), Cell::FaceDown, Cell::Hole],
+        turn: 1,
+        pairs: [1, 0],
+    }
+}
+
+/// Start watching a room whose first state is `first`.
+fn spectate(first: RoomState) -> io::Result<Game> {
+    let listener = TcpListener::bind(("127.0.0.1", 0))?;
+    let addr = listener.local_addr()?;
+    let lobby = thread::spawn(move || -> io::Result<()> {
+        let (mut stream, _) = listener.accept()?;
+        let payload = Message::State(first).encode();
+        stream.write_all(&(payload.len() as u32).to_be_bytes())?;
+        stream.write_all(&payload)
+    });
+    let game = Game::spectate(Connection::join(addr)?);
+    lobby.join().unwrap()?;
+    game
+}
+
+#[test]
+fn valid() {
+    let mut game = spectate(state()).unwrap();
+    let mut next = state();
+    next.cells[2] = Cell::Revealed(1);
+    game.set_opponent_move(Message::State(next)).unwrap();
+}
+
+#[test]
+fn unknown_card() {
+    let mut first = state();
+    first.cells[0] = Cell::Found(u32::MAX - 3);
+    let e = spectate(first).err().unwrap();
+    assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn board_changed() {
+    let mut game = spectate(state()).unwrap();
+    let mut moved = state();
+    moved.cells.swap(2, 3);
+    let e = game.set_opponent_move(Message::State(moved)).unwrap_err();
+    assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+
+    let mut grown = state();
+    grown.size = Vec2 { x: 3, y: 2 };
+    grown.cells.extend([Cell::FaceDown, Cell::FaceDown]);
+    let e = game.set_opponent_move(Message::State(grown)).unwrap_err();
+    assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+}
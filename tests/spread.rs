@@ -0,0 +1,25 @@
+//! Tests of dealing the cards of each pair apart.
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use rs_card_matching::board::{Board, Card, Idx2d, Suit};
+use rs_card_matching::grid::Grid;
+
+/// Every playing card of a rank pairs with the other three, so the
+/// partners of the cards swapped overlap.
+#[test]
+fn ranks_of_four() {
+    let idx = Idx2d::new(4, 4);
+    for seed in 0..5 {
+        let cards = (0..16)
+            .map(|i| Some(Card::playing(i / 4, Suit::ALL[i as usize % 4])))
+            .collect();
+        let mut board = Board::from_cards(4, 4, cards);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let distance = board.spread_pairs(&idx, 3, &mut rng);
+        for c in idx.iter_all() {
+            let partner = board.find_partner(c).unwrap();
+            assert!(idx.distance(c, partner) >= distance);
+        }
+    }
+}